@@ -11,7 +11,9 @@ pub fn env_required(name: &str) -> Result<String, Box<dyn std::error::Error>> {
 
 pub fn env_bool(name: &str, default: bool) -> bool {
     match env::var(name) {
-        Ok(value) => matches!(value.as_str(), "1" | "true" | "TRUE" | "yes" | "YES"),
+        Ok(value) => ["1", "true", "yes", "on"]
+            .iter()
+            .any(|v| value.trim().eq_ignore_ascii_case(v)),
         Err(_) => default,
     }
 }
@@ -0,0 +1,563 @@
+//! Blocking (synchronous) client wrapper, enabled by the `blocking` feature.
+//!
+//! `PveClientBlocking` owns a current-thread Tokio runtime and drives the async client with
+//! `block_on`. Do not use it from inside an async runtime; `block_on` panics there.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::client::PveClient;
+use crate::client_option::ClientOption;
+use crate::error::PveError;
+use crate::models::{
+    AccessAcl, AccessRole, AccessUser, ClusterResource, ClusterStatusItem, LxcStatus, LxcSummary,
+    NetworkInterface, NodeStorageStatus, NodeSummary, NodeTask, QemuStatus, QemuVmSummary,
+    StorageContentItem, StorageIndexItem, TaskLogLine, TaskStatus, VersionInfo,
+};
+use crate::params::PveParams;
+use crate::requests;
+
+#[derive(Debug, Clone)]
+pub struct PveClientBlocking {
+    inner: PveClient,
+    runtime: Arc<Runtime>,
+}
+
+impl PveClientBlocking {
+    pub fn build(option: ClientOption) -> Result<Self, PveError> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        let inner = runtime.block_on(option.build())?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    pub fn inner(&self) -> &PveClient {
+        &self.inner
+    }
+
+    /// Runs any async SDK call to completion, for methods without a blocking mirror.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    pub fn connect(&self) -> Result<(), PveError> {
+        self.block_on(self.inner.connect())
+    }
+
+    pub fn version(&self) -> Result<VersionInfo, PveError> {
+        self.block_on(self.inner.version())
+    }
+
+    pub fn access(&self) -> AccessApi<'_> {
+        AccessApi { client: self }
+    }
+
+    pub fn cluster(&self) -> ClusterApi<'_> {
+        ClusterApi { client: self }
+    }
+
+    pub fn node(&self) -> NodeApi<'_> {
+        NodeApi { client: self }
+    }
+
+    pub fn qemu(&self) -> QemuApi<'_> {
+        QemuApi { client: self }
+    }
+
+    pub fn lxc(&self) -> LxcApi<'_> {
+        LxcApi { client: self }
+    }
+
+    pub fn storage(&self) -> StorageApi<'_> {
+        StorageApi { client: self }
+    }
+
+    pub fn backup(&self) -> BackupApi<'_> {
+        BackupApi { client: self }
+    }
+
+    pub fn task(&self) -> TaskApi<'_> {
+        TaskApi { client: self }
+    }
+
+    pub fn raw(&self) -> RawApi<'_> {
+        RawApi { client: self }
+    }
+}
+
+pub struct AccessApi<'a> {
+    client: &'a PveClientBlocking,
+}
+
+impl<'a> AccessApi<'a> {
+    pub fn users(&self) -> Result<Vec<AccessUser>, PveError> {
+        self.client.block_on(self.client.inner.access_users())
+    }
+
+    pub fn roles(&self) -> Result<Vec<AccessRole>, PveError> {
+        self.client.block_on(self.client.inner.access_roles())
+    }
+
+    pub fn acl_with(&self, query: &requests::AccessAclQuery) -> Result<Vec<AccessAcl>, PveError> {
+        self.client
+            .block_on(self.client.inner.access_acl_with(query))
+    }
+
+    pub fn set_acl_with(&self, request: &requests::AccessSetAclRequest) -> Result<(), PveError> {
+        self.client
+            .block_on(self.client.inner.access_set_acl_with(request))
+    }
+
+    pub fn delete_acl_with(
+        &self,
+        request: &requests::AccessDeleteAclRequest,
+    ) -> Result<(), PveError> {
+        self.client
+            .block_on(self.client.inner.access_delete_acl_with(request))
+    }
+}
+
+pub struct ClusterApi<'a> {
+    client: &'a PveClientBlocking,
+}
+
+impl<'a> ClusterApi<'a> {
+    pub fn resources_with(
+        &self,
+        query: &requests::ClusterResourcesQuery,
+    ) -> Result<Vec<ClusterResource>, PveError> {
+        self.client
+            .block_on(self.client.inner.cluster_resources_with(query))
+    }
+
+    pub fn status(&self) -> Result<Vec<ClusterStatusItem>, PveError> {
+        self.client.block_on(self.client.inner.cluster_status())
+    }
+
+    pub fn next_id(&self, vmid: Option<u32>) -> Result<u32, PveError> {
+        self.client
+            .block_on(self.client.inner.cluster_next_id(vmid))
+    }
+}
+
+pub struct NodeApi<'a> {
+    client: &'a PveClientBlocking,
+}
+
+impl<'a> NodeApi<'a> {
+    pub fn list(&self) -> Result<Vec<NodeSummary>, PveError> {
+        self.client.block_on(self.client.inner.nodes())
+    }
+
+    pub fn status(&self, node: &str) -> Result<Value, PveError> {
+        self.client.block_on(self.client.inner.node_status(node))
+    }
+
+    pub fn version(&self, node: &str) -> Result<VersionInfo, PveError> {
+        self.client.block_on(self.client.inner.node_version(node))
+    }
+
+    pub fn tasks_with(
+        &self,
+        node: &str,
+        query: &requests::NodeTasksQuery,
+    ) -> Result<Vec<NodeTask>, PveError> {
+        self.client
+            .block_on(self.client.inner.node_tasks_with(node, query))
+    }
+
+    pub fn network_with(
+        &self,
+        node: &str,
+        query: &requests::NodeNetworkQuery,
+    ) -> Result<Vec<NetworkInterface>, PveError> {
+        self.client
+            .block_on(self.client.inner.node_network_with(node, query))
+    }
+}
+
+pub struct QemuApi<'a> {
+    client: &'a PveClientBlocking,
+}
+
+impl<'a> QemuApi<'a> {
+    pub fn list(&self, node: &str, full: Option<bool>) -> Result<Vec<QemuVmSummary>, PveError> {
+        self.client
+            .block_on(self.client.inner.qemu_list(node, full))
+    }
+
+    pub fn create_with(
+        &self,
+        node: &str,
+        request: &requests::QemuCreateRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.qemu_create_with(node, request))
+    }
+
+    pub fn config_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        query: &requests::QemuConfigQuery,
+    ) -> Result<Value, PveError> {
+        self.client
+            .block_on(self.client.inner.qemu_config_with(node, vmid, query))
+    }
+
+    pub fn set_config_sync_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuSetConfigRequest,
+    ) -> Result<(), PveError> {
+        self.client.block_on(
+            self.client
+                .inner
+                .qemu_set_config_sync_with(node, vmid, request),
+        )
+    }
+
+    pub fn status(&self, node: &str, vmid: u32) -> Result<QemuStatus, PveError> {
+        self.client
+            .block_on(self.client.inner.qemu_status(node, vmid))
+    }
+
+    pub fn start_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuActionRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.qemu_start_with(node, vmid, request))
+    }
+
+    pub fn shutdown_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuActionRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.qemu_shutdown_with(node, vmid, request))
+    }
+
+    pub fn stop_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuActionRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.qemu_stop_with(node, vmid, request))
+    }
+
+    pub fn reboot_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuActionRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.qemu_reboot_with(node, vmid, request))
+    }
+
+    pub fn clone_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuCloneRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.qemu_clone_with(node, vmid, request))
+    }
+
+    pub fn migrate_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuMigrateRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.qemu_migrate_with(node, vmid, request))
+    }
+}
+
+pub struct LxcApi<'a> {
+    client: &'a PveClientBlocking,
+}
+
+impl<'a> LxcApi<'a> {
+    pub fn list(&self, node: &str) -> Result<Vec<LxcSummary>, PveError> {
+        self.client.block_on(self.client.inner.lxc_list(node))
+    }
+
+    pub fn create_with(
+        &self,
+        node: &str,
+        request: &requests::LxcCreateRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.lxc_create_with(node, request))
+    }
+
+    pub fn config_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        query: &requests::LxcConfigQuery,
+    ) -> Result<Value, PveError> {
+        self.client
+            .block_on(self.client.inner.lxc_config_with(node, vmid, query))
+    }
+
+    pub fn set_config_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::LxcSetConfigRequest,
+    ) -> Result<(), PveError> {
+        self.client
+            .block_on(self.client.inner.lxc_set_config_with(node, vmid, request))
+    }
+
+    pub fn status(&self, node: &str, vmid: u32) -> Result<LxcStatus, PveError> {
+        self.client
+            .block_on(self.client.inner.lxc_status(node, vmid))
+    }
+
+    pub fn start_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::LxcActionRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.lxc_start_with(node, vmid, request))
+    }
+
+    pub fn shutdown_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::LxcActionRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.lxc_shutdown_with(node, vmid, request))
+    }
+
+    pub fn stop_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::LxcActionRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.lxc_stop_with(node, vmid, request))
+    }
+
+    pub fn migrate_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::LxcMigrateRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.lxc_migrate_with(node, vmid, request))
+    }
+}
+
+pub struct StorageApi<'a> {
+    client: &'a PveClientBlocking,
+}
+
+impl<'a> StorageApi<'a> {
+    pub fn index(&self, storage_type: Option<&str>) -> Result<Vec<StorageIndexItem>, PveError> {
+        self.client
+            .block_on(self.client.inner.storage_index(storage_type))
+    }
+
+    pub fn node_storage_with(
+        &self,
+        node: &str,
+        query: &requests::NodeStorageQuery,
+    ) -> Result<Vec<NodeStorageStatus>, PveError> {
+        self.client
+            .block_on(self.client.inner.node_storage_with(node, query))
+    }
+
+    pub fn content_with(
+        &self,
+        node: &str,
+        storage: &str,
+        query: &requests::StorageContentQuery,
+    ) -> Result<Vec<StorageContentItem>, PveError> {
+        self.client
+            .block_on(self.client.inner.storage_content_with(node, storage, query))
+    }
+
+    pub fn upload_with(
+        &self,
+        node: &str,
+        storage: &str,
+        request: &requests::StorageUploadRequest,
+    ) -> Result<String, PveError> {
+        self.client.block_on(
+            self.client
+                .inner
+                .storage_upload_with(node, storage, request),
+        )
+    }
+
+    pub fn delete_volume_with(
+        &self,
+        node: &str,
+        storage: &str,
+        volume: &str,
+        request: &requests::StorageDeleteVolumeRequest,
+    ) -> Result<String, PveError> {
+        self.client.block_on(
+            self.client
+                .inner
+                .storage_delete_volume_with(node, storage, volume, request),
+        )
+    }
+}
+
+pub struct BackupApi<'a> {
+    client: &'a PveClientBlocking,
+}
+
+impl<'a> BackupApi<'a> {
+    pub fn vzdump_with(
+        &self,
+        node: &str,
+        request: &requests::VzdumpRequest,
+    ) -> Result<String, PveError> {
+        self.client
+            .block_on(self.client.inner.vzdump_backup_with(node, request))
+    }
+}
+
+pub struct TaskApi<'a> {
+    client: &'a PveClientBlocking,
+}
+
+impl<'a> TaskApi<'a> {
+    pub fn status(&self, node: &str, upid: &str) -> Result<TaskStatus, PveError> {
+        self.client
+            .block_on(self.client.inner.task_status(node, upid))
+    }
+
+    pub fn log_with(
+        &self,
+        node: &str,
+        upid: &str,
+        query: &requests::TaskLogQuery,
+    ) -> Result<Vec<TaskLogLine>, PveError> {
+        self.client
+            .block_on(self.client.inner.task_log_with(node, upid, query))
+    }
+
+    pub fn wait_with_options(
+        &self,
+        node: &str,
+        upid: &str,
+        options: &requests::WaitTaskOptions,
+    ) -> Result<TaskStatus, PveError> {
+        self.client.block_on(
+            self.client
+                .inner
+                .wait_for_task_with_options(node, upid, options),
+        )
+    }
+}
+
+pub struct RawApi<'a> {
+    client: &'a PveClientBlocking,
+}
+
+impl<'a> RawApi<'a> {
+    pub fn json(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        query: Option<&PveParams>,
+        form: Option<&PveParams>,
+    ) -> Result<Value, PveError> {
+        self.client
+            .block_on(self.client.inner.raw_json(method, path, query, form))
+    }
+
+    pub fn get(&self, path: &str, query: Option<&PveParams>) -> Result<Value, PveError> {
+        self.client.block_on(self.client.inner.raw_get(path, query))
+    }
+
+    pub fn post(&self, path: &str, form: Option<&PveParams>) -> Result<Value, PveError> {
+        self.client.block_on(self.client.inner.raw_post(path, form))
+    }
+
+    pub fn put(&self, path: &str, form: Option<&PveParams>) -> Result<Value, PveError> {
+        self.client.block_on(self.client.inner.raw_put(path, form))
+    }
+
+    pub fn delete(&self, path: &str, query: Option<&PveParams>) -> Result<Value, PveError> {
+        self.client
+            .block_on(self.client.inner.raw_delete(path, query))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::PveClientBlocking;
+    use crate::client_option::ClientOption;
+
+    #[test]
+    fn blocking_client_runs_requests_without_external_runtime() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind listener");
+        let port = listener.local_addr().expect("listener addr").port();
+        let server = thread::spawn(move || {
+            let (mut socket, _) = listener.accept().expect("accept");
+            let mut buffer = [0_u8; 4096];
+            let _ = socket.read(&mut buffer).expect("read request");
+            let body = r#"{"data":{"version":"8.2.1"}}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).expect("write");
+        });
+
+        let client = PveClientBlocking::build(
+            ClientOption::new("127.0.0.1")
+                .port(port)
+                .https(false)
+                .auth_none(),
+        )
+        .expect("must build");
+
+        let version = client.version().expect("version");
+        assert_eq!(version.version, "8.2.1");
+        server.join().expect("server thread");
+
+        let _ = client.access();
+        let _ = client.cluster();
+        let _ = client.node();
+        let _ = client.qemu();
+        let _ = client.lxc();
+        let _ = client.storage();
+        let _ = client.backup();
+        let _ = client.task();
+        let _ = client.raw();
+    }
+}
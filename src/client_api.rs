@@ -1,6 +1,8 @@
+use std::collections::HashMap;
 use std::path::Path;
 use std::time::Duration;
 
+use futures_util::Stream;
 use reqwest::multipart;
 use serde_json::Value;
 
@@ -10,10 +12,42 @@ use crate::models::{
     AccessAcl, AccessGroup, AccessRole, AccessUser, AccessUserToken, ClusterResource,
     ClusterStatusItem, DatacenterConfig, LxcStatus, LxcSummary, NetworkInterface,
     NodeStorageStatus, NodeSummary, NodeTask, QemuStatus, QemuVmSummary, SnapshotInfo,
-    StorageContentItem, StorageIndexItem, TaskLogLine, TaskStatus, TicketInfo,
+    StorageContentItem, StorageIndexItem, TaskLogLine, TaskStatus, TicketInfo, VersionInfo,
 };
 use crate::params::PveParams;
 use crate::requests;
+use crate::types::access::{
+    AccessDomain, DomainCreateRequest, DomainUpdateRequest, TfaDeleteRequest, TfaEntry,
+};
+use crate::types::backup::{
+    BackupFile, BackupJob, BackupJobRequest, BackupJobUpdate, IncludedGuest,
+};
+use crate::types::ceph::{CephOsdTree, CephPool, CephStatus};
+use crate::types::cluster::{ClusterConfigNode, ClusterJoinInfo, ClusterLogEntry};
+use crate::types::common::{
+    ApiIndexItem, CreateOutcome, GuestFeature, GuestState, PendingConfigEntry, Tags,
+};
+use crate::types::console::{TermProxyInfo, VncProxyInfo, VncProxyRequest};
+use crate::types::disk::{
+    DirectoryCreateRequest, LvmCreateRequest, ZfsCreateRequest, ZfsPool, ZfsPoolDetail,
+};
+use crate::types::ha::{HaManagerStatus, HaStatusEntry};
+use crate::types::hardware::{MdevType, PciDevice, UsbDevice};
+use crate::types::lxc::{Appliance, LxcCloneRequest, LxcDeleteRequest, LxcMoveVolumeRequest};
+use crate::types::metrics_server::{MetricsServer, MetricsServerRequest, MetricsServerUpdate};
+use crate::types::node::{
+    JournalQuery, NetworkCreateRequest, NetworkUpdateRequest, SyslogLine, SyslogQuery,
+};
+use crate::types::notifications::{
+    NotificationEndpoint, NotificationEndpointRequest, NotificationEndpointType,
+    NotificationMatcher, NotificationMatcherRequest, NotificationTarget,
+};
+use crate::types::qemu::{
+    AgentFstrimResult, MigratePreconditions, QemuConfig, QemuDeleteRequest, QemuEnsureStateOptions,
+    QemuProvisionRequest, QemuProvisionResult,
+};
+use crate::types::sdn::{SdnVnet, SdnZone};
+use crate::types::task::{TransferProgress, Upid};
 
 pub struct AccessApi<'a> {
     client: &'a PveClient,
@@ -197,6 +231,64 @@ impl<'a> AccessApi<'a> {
     pub async fn delete_user_token(&self, userid: &str, tokenid: &str) -> Result<Value, PveError> {
         self.client.access_delete_user_token(userid, tokenid).await
     }
+
+    pub async fn domains(&self) -> Result<Vec<AccessDomain>, PveError> {
+        self.client.access_domains().await
+    }
+
+    pub async fn domain(&self, realm: &str) -> Result<AccessDomain, PveError> {
+        self.client.access_domain(realm).await
+    }
+
+    pub async fn create_domain_with(&self, request: &DomainCreateRequest) -> Result<(), PveError> {
+        self.client.access_create_domain_with(request).await
+    }
+
+    pub async fn update_domain_with(
+        &self,
+        realm: &str,
+        request: &DomainUpdateRequest,
+    ) -> Result<(), PveError> {
+        self.client.access_update_domain_with(realm, request).await
+    }
+
+    pub async fn delete_domain(&self, realm: &str) -> Result<(), PveError> {
+        self.client.access_delete_domain(realm).await
+    }
+
+    pub async fn user_tfa(&self, userid: &str) -> Result<Vec<TfaEntry>, PveError> {
+        self.client.access_user_tfa(userid).await
+    }
+
+    pub async fn delete_tfa(
+        &self,
+        userid: &str,
+        tfa_id: &str,
+        password: Option<&str>,
+    ) -> Result<(), PveError> {
+        self.client
+            .access_delete_tfa(userid, tfa_id, password)
+            .await
+    }
+
+    pub async fn delete_tfa_with(
+        &self,
+        userid: &str,
+        tfa_id: &str,
+        request: &TfaDeleteRequest,
+    ) -> Result<(), PveError> {
+        self.client
+            .access_delete_tfa_with(userid, tfa_id, request)
+            .await
+    }
+
+    pub async fn permissions(
+        &self,
+        userid: Option<&str>,
+        path: Option<&str>,
+    ) -> Result<HashMap<String, HashMap<String, u8>>, PveError> {
+        self.client.access_permissions(userid, path).await
+    }
 }
 
 pub struct ClusterApi<'a> {
@@ -218,6 +310,31 @@ impl<'a> ClusterApi<'a> {
         self.client.cluster_resources_with(query).await
     }
 
+    pub async fn resources_in_pool(&self, pool: &str) -> Result<Vec<ClusterResource>, PveError> {
+        self.client.cluster_resources_in_pool(pool).await
+    }
+
+    pub async fn resources_with_tag(&self, tag: &str) -> Result<Vec<ClusterResource>, PveError> {
+        self.client.cluster_resources_with_tag(tag).await
+    }
+
+    pub async fn tasks(
+        &self,
+        query: &requests::NodeTasksQuery,
+    ) -> Result<Vec<(String, NodeTask)>, PveError> {
+        self.client.cluster_tasks(query).await
+    }
+
+    pub async fn tasks_with_concurrency(
+        &self,
+        query: &requests::NodeTasksQuery,
+        max_concurrency: usize,
+    ) -> Result<Vec<(String, NodeTask)>, PveError> {
+        self.client
+            .cluster_tasks_with_concurrency(query, max_concurrency)
+            .await
+    }
+
     pub async fn status(&self) -> Result<Vec<ClusterStatusItem>, PveError> {
         self.client.cluster_status().await
     }
@@ -225,6 +342,26 @@ impl<'a> ClusterApi<'a> {
     pub async fn next_id(&self, vmid: Option<u32>) -> Result<u32, PveError> {
         self.client.cluster_next_id(vmid).await
     }
+
+    pub async fn is_vmid_free(&self, vmid: u32) -> Result<bool, PveError> {
+        self.client.cluster_is_vmid_free(vmid).await
+    }
+
+    pub async fn log(&self, max: Option<u64>) -> Result<Vec<ClusterLogEntry>, PveError> {
+        self.client.cluster_log(max).await
+    }
+
+    pub async fn config_nodes(&self) -> Result<Vec<ClusterConfigNode>, PveError> {
+        self.client.cluster_config_nodes().await
+    }
+
+    pub async fn config_totem(&self) -> Result<Value, PveError> {
+        self.client.cluster_config_totem().await
+    }
+
+    pub async fn config_join_info(&self) -> Result<ClusterJoinInfo, PveError> {
+        self.client.cluster_config_join_info().await
+    }
 }
 
 pub struct NodeApi<'a> {
@@ -261,6 +398,10 @@ impl<'a> NodeApi<'a> {
         self.client.node_status(node).await
     }
 
+    pub async fn version(&self, node: &str) -> Result<VersionInfo, PveError> {
+        self.client.node_version(node).await
+    }
+
     pub async fn tasks(&self, node: &str, query: &PveParams) -> Result<Vec<NodeTask>, PveError> {
         self.client.node_tasks(node, query).await
     }
@@ -288,6 +429,65 @@ impl<'a> NodeApi<'a> {
     ) -> Result<Vec<NetworkInterface>, PveError> {
         self.client.node_network_with(node, query).await
     }
+
+    pub async fn create_network_with(
+        &self,
+        node: &str,
+        request: &NetworkCreateRequest,
+    ) -> Result<(), PveError> {
+        self.client.node_create_network_with(node, request).await
+    }
+
+    pub async fn update_network_with(
+        &self,
+        node: &str,
+        iface: &str,
+        request: &NetworkUpdateRequest,
+    ) -> Result<(), PveError> {
+        self.client
+            .node_update_network_with(node, iface, request)
+            .await
+    }
+
+    pub async fn delete_network(&self, node: &str, iface: &str) -> Result<(), PveError> {
+        self.client.node_delete_network(node, iface).await
+    }
+
+    pub async fn apply_network(&self, node: &str) -> Result<String, PveError> {
+        self.client.node_apply_network(node).await
+    }
+
+    pub async fn revert_network(&self, node: &str) -> Result<(), PveError> {
+        self.client.node_revert_network(node).await
+    }
+
+    pub async fn syslog(
+        &self,
+        node: &str,
+        query: &SyslogQuery,
+    ) -> Result<Vec<SyslogLine>, PveError> {
+        self.client.node_syslog(node, query).await
+    }
+
+    pub async fn journal(&self, node: &str, query: &JournalQuery) -> Result<Vec<String>, PveError> {
+        self.client.node_journal(node, query).await
+    }
+
+    pub async fn pci_devices(&self, node: &str) -> Result<Vec<PciDevice>, PveError> {
+        self.client.node_pci_devices(node).await
+    }
+
+    pub async fn pci_mdev_types(
+        &self,
+        node: &str,
+        pci_id: &str,
+    ) -> Result<Vec<MdevType>, PveError> {
+        self.client.node_pci_mdev_types(node, pci_id).await
+    }
+
+    pub async fn usb_devices(&self, node: &str) -> Result<Vec<UsbDevice>, PveError> {
+        self.client.node_usb_devices(node).await
+    }
 }
 
 pub struct QemuApi<'a> {
@@ -320,6 +520,33 @@ impl<'a> QemuApi<'a> {
         self.client.qemu_create_with(node, request).await
     }
 
+    pub async fn create_idempotent(
+        &self,
+        node: &str,
+        request: &requests::QemuCreateRequest,
+    ) -> Result<CreateOutcome, PveError> {
+        self.client.qemu_create_idempotent(node, request).await
+    }
+
+    pub async fn create_auto_id(
+        &self,
+        node: &str,
+        request: &requests::QemuCreateRequest,
+    ) -> Result<(u32, String), PveError> {
+        self.client.qemu_create_auto_id(node, request).await
+    }
+
+    pub async fn create_auto_id_with_attempts(
+        &self,
+        node: &str,
+        request: &requests::QemuCreateRequest,
+        attempts: u32,
+    ) -> Result<(u32, String), PveError> {
+        self.client
+            .qemu_create_auto_id_with_attempts(node, request, attempts)
+            .await
+    }
+
     pub async fn config(
         &self,
         node: &str,
@@ -339,6 +566,68 @@ impl<'a> QemuApi<'a> {
         self.client.qemu_config_with(node, vmid, query).await
     }
 
+    pub async fn config_typed(
+        &self,
+        node: &str,
+        vmid: u32,
+        query: &requests::QemuConfigQuery,
+    ) -> Result<QemuConfig, PveError> {
+        self.client.qemu_config_typed(node, vmid, query).await
+    }
+
+    pub async fn pending(
+        &self,
+        node: &str,
+        vmid: u32,
+    ) -> Result<Vec<PendingConfigEntry>, PveError> {
+        self.client.qemu_pending(node, vmid).await
+    }
+
+    pub async fn needs_reboot(&self, node: &str, vmid: u32) -> Result<bool, PveError> {
+        self.client.qemu_needs_reboot(node, vmid).await
+    }
+
+    pub async fn get_description(&self, node: &str, vmid: u32) -> Result<Option<String>, PveError> {
+        self.client.qemu_get_description(node, vmid).await
+    }
+
+    pub async fn set_description(&self, node: &str, vmid: u32, text: &str) -> Result<(), PveError> {
+        self.client.qemu_set_description(node, vmid, text).await
+    }
+
+    pub async fn tags(&self, node: &str, vmid: u32) -> Result<Tags, PveError> {
+        self.client.qemu_tags(node, vmid).await
+    }
+
+    pub async fn set_tags(&self, node: &str, vmid: u32, tags: &[&str]) -> Result<(), PveError> {
+        self.client.qemu_set_tags(node, vmid, tags).await
+    }
+
+    pub async fn add_tags(&self, node: &str, vmid: u32, tags: &[&str]) -> Result<Tags, PveError> {
+        self.client.qemu_add_tags(node, vmid, tags).await
+    }
+
+    pub async fn remove_tags(
+        &self,
+        node: &str,
+        vmid: u32,
+        tags: &[&str],
+    ) -> Result<Tags, PveError> {
+        self.client.qemu_remove_tags(node, vmid, tags).await
+    }
+
+    pub async fn feature(
+        &self,
+        node: &str,
+        vmid: u32,
+        feature: GuestFeature,
+        snapname: Option<&str>,
+    ) -> Result<bool, PveError> {
+        self.client
+            .qemu_feature(node, vmid, feature, snapname)
+            .await
+    }
+
     pub async fn set_config_async(
         &self,
         node: &str,
@@ -379,10 +668,56 @@ impl<'a> QemuApi<'a> {
             .await
     }
 
+    pub async fn set_config_verified(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuSetConfigRequest,
+    ) -> Result<QemuConfig, PveError> {
+        self.client
+            .qemu_set_config_verified(node, vmid, request)
+            .await
+    }
+
     pub async fn status(&self, node: &str, vmid: u32) -> Result<QemuStatus, PveError> {
         self.client.qemu_status(node, vmid).await
     }
 
+    pub async fn status_opt(&self, node: &str, vmid: u32) -> Result<Option<QemuStatus>, PveError> {
+        self.client.qemu_status_opt(node, vmid).await
+    }
+
+    pub async fn exists(&self, node: &str, vmid: u32) -> Result<bool, PveError> {
+        self.client.qemu_exists(node, vmid).await
+    }
+
+    pub async fn delete(
+        &self,
+        node: &str,
+        vmid: u32,
+        params: &PveParams,
+    ) -> Result<String, PveError> {
+        self.client.qemu_delete(node, vmid, params).await
+    }
+
+    pub async fn delete_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &QemuDeleteRequest,
+    ) -> Result<String, PveError> {
+        self.client.qemu_delete_with(node, vmid, request).await
+    }
+
+    pub async fn convert_to_template(
+        &self,
+        node: &str,
+        vmid: u32,
+        disk: Option<&str>,
+    ) -> Result<String, PveError> {
+        self.client.qemu_template(node, vmid, disk).await
+    }
+
     pub async fn start(
         &self,
         node: &str,
@@ -401,6 +736,15 @@ impl<'a> QemuApi<'a> {
         self.client.qemu_start_with(node, vmid, request).await
     }
 
+    pub async fn start_upid(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuActionRequest,
+    ) -> Result<Upid, PveError> {
+        self.client.qemu_start_upid(node, vmid, request).await
+    }
+
     pub async fn shutdown(
         &self,
         node: &str,
@@ -419,6 +763,15 @@ impl<'a> QemuApi<'a> {
         self.client.qemu_shutdown_with(node, vmid, request).await
     }
 
+    pub async fn shutdown_upid(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuActionRequest,
+    ) -> Result<Upid, PveError> {
+        self.client.qemu_shutdown_upid(node, vmid, request).await
+    }
+
     pub async fn stop(
         &self,
         node: &str,
@@ -437,6 +790,15 @@ impl<'a> QemuApi<'a> {
         self.client.qemu_stop_with(node, vmid, request).await
     }
 
+    pub async fn stop_upid(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuActionRequest,
+    ) -> Result<Upid, PveError> {
+        self.client.qemu_stop_upid(node, vmid, request).await
+    }
+
     pub async fn reboot(
         &self,
         node: &str,
@@ -455,6 +817,15 @@ impl<'a> QemuApi<'a> {
         self.client.qemu_reboot_with(node, vmid, request).await
     }
 
+    pub async fn reboot_upid(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuActionRequest,
+    ) -> Result<Upid, PveError> {
+        self.client.qemu_reboot_upid(node, vmid, request).await
+    }
+
     pub async fn suspend(
         &self,
         node: &str,
@@ -541,6 +912,39 @@ impl<'a> QemuApi<'a> {
             .await
     }
 
+    pub async fn snapshot_delete(
+        &self,
+        node: &str,
+        vmid: u32,
+        snapname: &str,
+        force: Option<bool>,
+    ) -> Result<String, PveError> {
+        self.client
+            .qemu_snapshot_delete(node, vmid, snapname, force)
+            .await
+    }
+
+    pub async fn snapshot_config(
+        &self,
+        node: &str,
+        vmid: u32,
+        snapname: &str,
+    ) -> Result<Value, PveError> {
+        self.client.qemu_snapshot_config(node, vmid, snapname).await
+    }
+
+    pub async fn snapshot_update_description(
+        &self,
+        node: &str,
+        vmid: u32,
+        snapname: &str,
+        description: &str,
+    ) -> Result<(), PveError> {
+        self.client
+            .qemu_snapshot_update_description(node, vmid, snapname, description)
+            .await
+    }
+
     pub async fn clone(
         &self,
         node: &str,
@@ -560,6 +964,17 @@ impl<'a> QemuApi<'a> {
         self.client.qemu_clone_with(node, vmid, request).await
     }
 
+    pub async fn migrate_preconditions(
+        &self,
+        node: &str,
+        vmid: u32,
+        target: Option<&str>,
+    ) -> Result<MigratePreconditions, PveError> {
+        self.client
+            .qemu_migrate_preconditions(node, vmid, target)
+            .await
+    }
+
     pub async fn migrate(
         &self,
         node: &str,
@@ -578,35 +993,162 @@ impl<'a> QemuApi<'a> {
     ) -> Result<String, PveError> {
         self.client.qemu_migrate_with(node, vmid, request).await
     }
-}
-
-pub struct LxcApi<'a> {
-    client: &'a PveClient,
-}
-
-impl<'a> LxcApi<'a> {
-    pub async fn list(&self, node: &str) -> Result<Vec<LxcSummary>, PveError> {
-        self.client.lxc_list(node).await
-    }
 
-    pub async fn create(
+    pub async fn migrate_and_track<F>(
         &self,
         node: &str,
         vmid: u32,
-        ostemplate: &str,
-        params: &PveParams,
-    ) -> Result<String, PveError> {
-        self.client.lxc_create(node, vmid, ostemplate, params).await
+        request: &requests::QemuMigrateRequest,
+        wait: &requests::WaitTaskOptions,
+        on_progress: F,
+    ) -> Result<TaskStatus, PveError>
+    where
+        F: FnMut(TransferProgress),
+    {
+        self.client
+            .qemu_migrate_and_track(node, vmid, request, wait, on_progress)
+            .await
     }
 
-    pub async fn create_with(
-        &self,
+    pub async fn agent_ping(&self, node: &str, vmid: u32) -> Result<(), PveError> {
+        self.client.qemu_agent_ping(node, vmid).await
+    }
+
+    pub async fn agent_fstrim(&self, node: &str, vmid: u32) -> Result<AgentFstrimResult, PveError> {
+        self.client.qemu_agent_fstrim(node, vmid).await
+    }
+
+    pub async fn agent_fsfreeze_freeze(&self, node: &str, vmid: u32) -> Result<u32, PveError> {
+        self.client.qemu_agent_fsfreeze_freeze(node, vmid).await
+    }
+
+    pub async fn agent_fsfreeze_thaw(&self, node: &str, vmid: u32) -> Result<u32, PveError> {
+        self.client.qemu_agent_fsfreeze_thaw(node, vmid).await
+    }
+
+    pub async fn agent_fsfreeze_status(&self, node: &str, vmid: u32) -> Result<String, PveError> {
+        self.client.qemu_agent_fsfreeze_status(node, vmid).await
+    }
+
+    pub async fn wait_for_agent(
+        &self,
+        node: &str,
+        vmid: u32,
+        timeout: Duration,
+    ) -> Result<(), PveError> {
+        self.client.qemu_wait_for_agent(node, vmid, timeout).await
+    }
+
+    pub async fn ensure_state(
+        &self,
+        node: &str,
+        vmid: u32,
+        desired: GuestState,
+        options: &QemuEnsureStateOptions,
+    ) -> Result<TaskStatus, PveError> {
+        self.client
+            .qemu_ensure_state(node, vmid, desired, options)
+            .await
+    }
+
+    pub async fn set_pool(
+        &self,
+        node: &str,
+        vmid: u32,
+        pool: Option<&str>,
+    ) -> Result<(), PveError> {
+        self.client.qemu_set_pool(node, vmid, pool).await
+    }
+
+    pub async fn provision_from_template(
+        &self,
+        node: &str,
+        template_vmid: u32,
+        request: &QemuProvisionRequest,
+    ) -> Result<QemuProvisionResult, PveError> {
+        self.client
+            .qemu_provision_from_template(node, template_vmid, request)
+            .await
+    }
+}
+
+pub struct LxcApi<'a> {
+    client: &'a PveClient,
+}
+
+impl<'a> LxcApi<'a> {
+    pub async fn list(&self, node: &str) -> Result<Vec<LxcSummary>, PveError> {
+        self.client.lxc_list(node).await
+    }
+
+    pub async fn templates(
+        &self,
+        node: &str,
+        storage: &str,
+    ) -> Result<Vec<StorageContentItem>, PveError> {
+        self.client.list_ct_templates(node, storage).await
+    }
+
+    pub async fn appliances(&self, node: &str) -> Result<Vec<Appliance>, PveError> {
+        self.client.list_appliances(node).await
+    }
+
+    pub async fn download_appliance(
+        &self,
+        node: &str,
+        storage: &str,
+        template: &str,
+    ) -> Result<String, PveError> {
+        self.client
+            .download_appliance(node, storage, template)
+            .await
+    }
+
+    pub async fn create(
+        &self,
+        node: &str,
+        vmid: u32,
+        ostemplate: &str,
+        params: &PveParams,
+    ) -> Result<String, PveError> {
+        self.client.lxc_create(node, vmid, ostemplate, params).await
+    }
+
+    pub async fn create_with(
+        &self,
         node: &str,
         request: &requests::LxcCreateRequest,
     ) -> Result<String, PveError> {
         self.client.lxc_create_with(node, request).await
     }
 
+    pub async fn create_idempotent(
+        &self,
+        node: &str,
+        request: &requests::LxcCreateRequest,
+    ) -> Result<CreateOutcome, PveError> {
+        self.client.lxc_create_idempotent(node, request).await
+    }
+
+    pub async fn create_auto_id(
+        &self,
+        node: &str,
+        request: &requests::LxcCreateRequest,
+    ) -> Result<(u32, String), PveError> {
+        self.client.lxc_create_auto_id(node, request).await
+    }
+
+    pub async fn create_auto_id_with_attempts(
+        &self,
+        node: &str,
+        request: &requests::LxcCreateRequest,
+        attempts: u32,
+    ) -> Result<(u32, String), PveError> {
+        self.client
+            .lxc_create_auto_id_with_attempts(node, request, attempts)
+            .await
+    }
+
     pub async fn config(
         &self,
         node: &str,
@@ -626,6 +1168,57 @@ impl<'a> LxcApi<'a> {
         self.client.lxc_config_with(node, vmid, query).await
     }
 
+    pub async fn pending(
+        &self,
+        node: &str,
+        vmid: u32,
+    ) -> Result<Vec<PendingConfigEntry>, PveError> {
+        self.client.lxc_pending(node, vmid).await
+    }
+
+    pub async fn needs_reboot(&self, node: &str, vmid: u32) -> Result<bool, PveError> {
+        self.client.lxc_needs_reboot(node, vmid).await
+    }
+
+    pub async fn get_description(&self, node: &str, vmid: u32) -> Result<Option<String>, PveError> {
+        self.client.lxc_get_description(node, vmid).await
+    }
+
+    pub async fn set_description(&self, node: &str, vmid: u32, text: &str) -> Result<(), PveError> {
+        self.client.lxc_set_description(node, vmid, text).await
+    }
+
+    pub async fn tags(&self, node: &str, vmid: u32) -> Result<Tags, PveError> {
+        self.client.lxc_tags(node, vmid).await
+    }
+
+    pub async fn set_tags(&self, node: &str, vmid: u32, tags: &[&str]) -> Result<(), PveError> {
+        self.client.lxc_set_tags(node, vmid, tags).await
+    }
+
+    pub async fn add_tags(&self, node: &str, vmid: u32, tags: &[&str]) -> Result<Tags, PveError> {
+        self.client.lxc_add_tags(node, vmid, tags).await
+    }
+
+    pub async fn remove_tags(
+        &self,
+        node: &str,
+        vmid: u32,
+        tags: &[&str],
+    ) -> Result<Tags, PveError> {
+        self.client.lxc_remove_tags(node, vmid, tags).await
+    }
+
+    pub async fn feature(
+        &self,
+        node: &str,
+        vmid: u32,
+        feature: GuestFeature,
+        snapname: Option<&str>,
+    ) -> Result<bool, PveError> {
+        self.client.lxc_feature(node, vmid, feature, snapname).await
+    }
+
     pub async fn set_config(
         &self,
         node: &str,
@@ -648,6 +1241,36 @@ impl<'a> LxcApi<'a> {
         self.client.lxc_status(node, vmid).await
     }
 
+    pub async fn status_opt(&self, node: &str, vmid: u32) -> Result<Option<LxcStatus>, PveError> {
+        self.client.lxc_status_opt(node, vmid).await
+    }
+
+    pub async fn exists(&self, node: &str, vmid: u32) -> Result<bool, PveError> {
+        self.client.lxc_exists(node, vmid).await
+    }
+
+    pub async fn delete(
+        &self,
+        node: &str,
+        vmid: u32,
+        params: &PveParams,
+    ) -> Result<String, PveError> {
+        self.client.lxc_delete(node, vmid, params).await
+    }
+
+    pub async fn delete_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &LxcDeleteRequest,
+    ) -> Result<String, PveError> {
+        self.client.lxc_delete_with(node, vmid, request).await
+    }
+
+    pub async fn convert_to_template(&self, node: &str, vmid: u32) -> Result<(), PveError> {
+        self.client.lxc_template(node, vmid).await
+    }
+
     pub async fn start(
         &self,
         node: &str,
@@ -770,6 +1393,58 @@ impl<'a> LxcApi<'a> {
             .await
     }
 
+    pub async fn snapshot_delete(
+        &self,
+        node: &str,
+        vmid: u32,
+        snapname: &str,
+        force: Option<bool>,
+    ) -> Result<String, PveError> {
+        self.client
+            .lxc_snapshot_delete(node, vmid, snapname, force)
+            .await
+    }
+
+    pub async fn snapshot_config(
+        &self,
+        node: &str,
+        vmid: u32,
+        snapname: &str,
+    ) -> Result<Value, PveError> {
+        self.client.lxc_snapshot_config(node, vmid, snapname).await
+    }
+
+    pub async fn snapshot_update_description(
+        &self,
+        node: &str,
+        vmid: u32,
+        snapname: &str,
+        description: &str,
+    ) -> Result<(), PveError> {
+        self.client
+            .lxc_snapshot_update_description(node, vmid, snapname, description)
+            .await
+    }
+
+    pub async fn clone(
+        &self,
+        node: &str,
+        vmid: u32,
+        newid: u32,
+        params: &PveParams,
+    ) -> Result<String, PveError> {
+        self.client.lxc_clone(node, vmid, newid, params).await
+    }
+
+    pub async fn clone_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &LxcCloneRequest,
+    ) -> Result<String, PveError> {
+        self.client.lxc_clone_with(node, vmid, request).await
+    }
+
     pub async fn migrate(
         &self,
         node: &str,
@@ -788,6 +1463,59 @@ impl<'a> LxcApi<'a> {
     ) -> Result<String, PveError> {
         self.client.lxc_migrate_with(node, vmid, request).await
     }
+
+    pub async fn ensure_state(
+        &self,
+        node: &str,
+        vmid: u32,
+        desired: GuestState,
+        options: &requests::WaitTaskOptions,
+    ) -> Result<TaskStatus, PveError> {
+        self.client
+            .lxc_ensure_state(node, vmid, desired, options)
+            .await
+    }
+
+    pub async fn set_pool(
+        &self,
+        node: &str,
+        vmid: u32,
+        pool: Option<&str>,
+    ) -> Result<(), PveError> {
+        self.client.lxc_set_pool(node, vmid, pool).await
+    }
+
+    pub async fn resize(
+        &self,
+        node: &str,
+        vmid: u32,
+        disk: &str,
+        size: &str,
+    ) -> Result<String, PveError> {
+        self.client.lxc_resize(node, vmid, disk, size).await
+    }
+
+    pub async fn move_volume_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &LxcMoveVolumeRequest,
+    ) -> Result<String, PveError> {
+        self.client.lxc_move_volume_with(node, vmid, request).await
+    }
+
+    pub async fn termproxy(&self, node: &str, vmid: u32) -> Result<TermProxyInfo, PveError> {
+        self.client.lxc_termproxy(node, vmid).await
+    }
+
+    pub async fn vncproxy(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &VncProxyRequest,
+    ) -> Result<VncProxyInfo, PveError> {
+        self.client.lxc_vncproxy(node, vmid, request).await
+    }
 }
 
 pub struct StorageApi<'a> {
@@ -802,6 +1530,25 @@ impl<'a> StorageApi<'a> {
         self.client.storage_index(storage_type).await
     }
 
+    pub async fn create_storage_with(
+        &self,
+        request: &requests::StorageCreateRequest,
+    ) -> Result<(), PveError> {
+        self.client.storage_create_with(request).await
+    }
+
+    pub async fn update_storage_with(
+        &self,
+        storage: &str,
+        request: &requests::StorageUpdateRequest,
+    ) -> Result<(), PveError> {
+        self.client.storage_update_with(storage, request).await
+    }
+
+    pub async fn delete_storage(&self, storage: &str) -> Result<(), PveError> {
+        self.client.storage_delete(storage).await
+    }
+
     pub async fn node_storage(
         &self,
         node: &str,
@@ -836,6 +1583,38 @@ impl<'a> StorageApi<'a> {
         self.client.storage_content_with(node, storage, query).await
     }
 
+    pub async fn isos(
+        &self,
+        node: &str,
+        storage: &str,
+    ) -> Result<Vec<StorageContentItem>, PveError> {
+        self.client.list_isos(node, storage).await
+    }
+
+    pub fn content_stream(
+        &self,
+        node: &str,
+        storage: &str,
+        query: &requests::StorageContentQuery,
+    ) -> impl Stream<Item = Result<StorageContentItem, PveError>> + 'a {
+        self.client.storage_content_stream(node, storage, query)
+    }
+
+    pub async fn content_filtered<F>(
+        &self,
+        node: &str,
+        storage: &str,
+        query: &requests::StorageContentQuery,
+        predicate: F,
+    ) -> Result<Vec<StorageContentItem>, PveError>
+    where
+        F: Fn(&StorageContentItem) -> bool,
+    {
+        self.client
+            .storage_content_filtered(node, storage, query, predicate)
+            .await
+    }
+
     pub async fn allocate_disk(
         &self,
         node: &str,
@@ -882,6 +1661,30 @@ impl<'a> StorageApi<'a> {
             .await
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upload_file_with_progress(
+        &self,
+        node: &str,
+        storage: &str,
+        content: &str,
+        file_path: impl AsRef<Path>,
+        checksum: Option<&str>,
+        checksum_algorithm: Option<&str>,
+        on_progress: impl FnMut(u64, u64) + Send + Sync + 'static,
+    ) -> Result<String, PveError> {
+        self.client
+            .storage_upload_file_with_progress(
+                node,
+                storage,
+                content,
+                file_path,
+                checksum,
+                checksum_algorithm,
+                on_progress,
+            )
+            .await
+    }
+
     pub async fn upload_form(
         &self,
         node: &str,
@@ -943,6 +1746,235 @@ impl<'a> BackupApi<'a> {
     ) -> Result<String, PveError> {
         self.client.vzdump_backup_with(node, request).await
     }
+
+    pub async fn list(
+        &self,
+        node: &str,
+        storage: &str,
+        vmid: Option<u32>,
+    ) -> Result<Vec<BackupFile>, PveError> {
+        self.client.list_backups(node, storage, vmid).await
+    }
+
+    pub async fn protect(
+        &self,
+        node: &str,
+        storage: &str,
+        volume: &str,
+        protected: bool,
+    ) -> Result<(), PveError> {
+        self.client
+            .backup_protect(node, storage, volume, protected)
+            .await
+    }
+
+    pub async fn backup_jobs(&self) -> Result<Vec<BackupJob>, PveError> {
+        self.client.backup_jobs().await
+    }
+
+    pub async fn backup_job(&self, id: &str) -> Result<BackupJob, PveError> {
+        self.client.backup_job(id).await
+    }
+
+    pub async fn create_backup_job_with(&self, request: &BackupJobRequest) -> Result<(), PveError> {
+        self.client.create_backup_job_with(request).await
+    }
+
+    pub async fn update_backup_job_with(
+        &self,
+        id: &str,
+        request: &BackupJobUpdate,
+    ) -> Result<(), PveError> {
+        self.client.update_backup_job_with(id, request).await
+    }
+
+    pub async fn delete_backup_job(&self, id: &str) -> Result<(), PveError> {
+        self.client.delete_backup_job(id).await
+    }
+
+    pub async fn backup_job_included_guests(
+        &self,
+        id: &str,
+    ) -> Result<Vec<IncludedGuest>, PveError> {
+        self.client.backup_job_included_guests(id).await
+    }
+}
+
+pub struct CephApi<'a> {
+    client: &'a PveClient,
+}
+
+impl<'a> CephApi<'a> {
+    pub async fn status(&self) -> Result<CephStatus, PveError> {
+        self.client.ceph_status().await
+    }
+
+    pub async fn osds(&self, node: &str) -> Result<CephOsdTree, PveError> {
+        self.client.ceph_osds(node).await
+    }
+
+    pub async fn pools(&self, node: &str) -> Result<Vec<CephPool>, PveError> {
+        self.client.ceph_pools(node).await
+    }
+}
+
+pub struct DiskApi<'a> {
+    client: &'a PveClient,
+}
+
+impl<'a> DiskApi<'a> {
+    pub async fn zfs_pools(&self, node: &str) -> Result<Vec<ZfsPool>, PveError> {
+        self.client.node_zfs_pools(node).await
+    }
+
+    pub async fn zfs_pool_detail(&self, node: &str, name: &str) -> Result<ZfsPoolDetail, PveError> {
+        self.client.node_zfs_pool_detail(node, name).await
+    }
+
+    pub async fn create_zfs_pool_with(
+        &self,
+        node: &str,
+        request: &ZfsCreateRequest,
+    ) -> Result<String, PveError> {
+        self.client.node_create_zfs_pool_with(node, request).await
+    }
+
+    pub async fn create_lvm_with(
+        &self,
+        node: &str,
+        request: &LvmCreateRequest,
+    ) -> Result<String, PveError> {
+        self.client.node_create_lvm_with(node, request).await
+    }
+
+    pub async fn create_lvmthin_with(
+        &self,
+        node: &str,
+        request: &LvmCreateRequest,
+    ) -> Result<String, PveError> {
+        self.client.node_create_lvmthin_with(node, request).await
+    }
+
+    pub async fn create_directory_with(
+        &self,
+        node: &str,
+        request: &DirectoryCreateRequest,
+    ) -> Result<String, PveError> {
+        self.client.node_create_directory_with(node, request).await
+    }
+}
+
+pub struct HaApi<'a> {
+    client: &'a PveClient,
+}
+
+impl<'a> HaApi<'a> {
+    pub async fn manager_status(&self) -> Result<HaManagerStatus, PveError> {
+        self.client.ha_manager_status().await
+    }
+
+    pub async fn status_current(&self) -> Result<Vec<HaStatusEntry>, PveError> {
+        self.client.ha_status_current().await
+    }
+}
+
+pub struct MetricsApi<'a> {
+    client: &'a PveClient,
+}
+
+impl<'a> MetricsApi<'a> {
+    pub async fn servers(&self) -> Result<Vec<MetricsServer>, PveError> {
+        self.client.metrics_servers().await
+    }
+
+    pub async fn server(&self, id: &str) -> Result<MetricsServer, PveError> {
+        self.client.metrics_server(id).await
+    }
+
+    pub async fn create_server_with(&self, request: &MetricsServerRequest) -> Result<(), PveError> {
+        self.client.create_metrics_server_with(request).await
+    }
+
+    pub async fn update_server_with(
+        &self,
+        id: &str,
+        request: &MetricsServerUpdate,
+    ) -> Result<(), PveError> {
+        self.client.update_metrics_server_with(id, request).await
+    }
+
+    pub async fn delete_server(&self, id: &str) -> Result<(), PveError> {
+        self.client.delete_metrics_server(id).await
+    }
+}
+
+pub struct NotificationsApi<'a> {
+    client: &'a PveClient,
+}
+
+impl<'a> NotificationsApi<'a> {
+    pub async fn targets(&self) -> Result<Vec<NotificationTarget>, PveError> {
+        self.client.notification_targets().await
+    }
+
+    pub async fn test_target(&self, target: &str) -> Result<(), PveError> {
+        self.client.test_notification_target(target).await
+    }
+
+    pub async fn endpoints(
+        &self,
+        type_: NotificationEndpointType,
+    ) -> Result<Vec<NotificationEndpoint>, PveError> {
+        self.client.notification_endpoints(type_).await
+    }
+
+    pub async fn create_endpoint_with(
+        &self,
+        request: &NotificationEndpointRequest,
+    ) -> Result<(), PveError> {
+        self.client.create_notification_endpoint_with(request).await
+    }
+
+    pub async fn delete_endpoint(
+        &self,
+        type_: NotificationEndpointType,
+        name: &str,
+    ) -> Result<(), PveError> {
+        self.client.delete_notification_endpoint(type_, name).await
+    }
+
+    pub async fn matchers(&self) -> Result<Vec<NotificationMatcher>, PveError> {
+        self.client.notification_matchers().await
+    }
+
+    pub async fn create_matcher_with(
+        &self,
+        request: &NotificationMatcherRequest,
+    ) -> Result<(), PveError> {
+        self.client.create_notification_matcher_with(request).await
+    }
+
+    pub async fn delete_matcher(&self, name: &str) -> Result<(), PveError> {
+        self.client.delete_notification_matcher(name).await
+    }
+}
+
+pub struct SdnApi<'a> {
+    client: &'a PveClient,
+}
+
+impl<'a> SdnApi<'a> {
+    pub async fn zones(&self) -> Result<Vec<SdnZone>, PveError> {
+        self.client.sdn_zones().await
+    }
+
+    pub async fn vnets(&self) -> Result<Vec<SdnVnet>, PveError> {
+        self.client.sdn_vnets().await
+    }
+
+    pub async fn apply(&self) -> Result<String, PveError> {
+        self.client.sdn_apply().await
+    }
 }
 
 pub struct TaskApi<'a> {
@@ -979,6 +2011,38 @@ impl<'a> RawApi<'a> {
     pub async fn delete(&self, path: &str, query: Option<&PveParams>) -> Result<Value, PveError> {
         self.client.raw_delete(path, query).await
     }
+
+    pub async fn bytes(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        query: Option<&PveParams>,
+    ) -> Result<Vec<u8>, PveError> {
+        self.client.raw_bytes(method, path, query).await
+    }
+
+    pub async fn text(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        query: Option<&PveParams>,
+    ) -> Result<String, PveError> {
+        self.client.raw_text(method, path, query).await
+    }
+
+    pub async fn download(
+        &self,
+        path: &str,
+        query: Option<&PveParams>,
+        dest: impl AsRef<Path>,
+        progress: impl FnMut(u64),
+    ) -> Result<u64, PveError> {
+        self.client.raw_download(path, query, dest, progress).await
+    }
+
+    pub async fn index(&self, path: &str) -> Result<Vec<ApiIndexItem>, PveError> {
+        self.client.api_index(path).await
+    }
 }
 
 impl<'a> TaskApi<'a> {
@@ -986,6 +2050,10 @@ impl<'a> TaskApi<'a> {
         self.client.task_status(node, upid).await
     }
 
+    pub async fn stop(&self, node: &str, upid: &str) -> Result<(), PveError> {
+        self.client.task_stop(node, upid).await
+    }
+
     pub async fn log(
         &self,
         node: &str,
@@ -1005,6 +2073,10 @@ impl<'a> TaskApi<'a> {
         self.client.task_log_with(node, upid, query).await
     }
 
+    pub async fn output(&self, node: &str, upid: &str) -> Result<String, PveError> {
+        self.client.task_output(node, upid).await
+    }
+
     pub async fn wait(
         &self,
         node: &str,
@@ -1027,6 +2099,27 @@ impl<'a> TaskApi<'a> {
             .wait_for_task_with_options(node, upid, options)
             .await
     }
+
+    #[cfg(feature = "cancellation")]
+    pub async fn wait_cancellable(
+        &self,
+        node: &str,
+        upid: &str,
+        options: &requests::WaitTaskOptions,
+        token: &tokio_util::sync::CancellationToken,
+    ) -> Result<TaskStatus, PveError> {
+        self.client
+            .wait_for_task_cancellable(node, upid, options, token)
+            .await
+    }
+
+    pub async fn wait_upid(
+        &self,
+        upid: &Upid,
+        options: &requests::WaitTaskOptions,
+    ) -> Result<TaskStatus, PveError> {
+        self.client.wait_for_upid(upid, options).await
+    }
 }
 
 impl PveClient {
@@ -1062,6 +2155,30 @@ impl PveClient {
         BackupApi { client: self }
     }
 
+    pub fn ceph(&self) -> CephApi<'_> {
+        CephApi { client: self }
+    }
+
+    pub fn disks(&self) -> DiskApi<'_> {
+        DiskApi { client: self }
+    }
+
+    pub fn ha(&self) -> HaApi<'_> {
+        HaApi { client: self }
+    }
+
+    pub fn metrics(&self) -> MetricsApi<'_> {
+        MetricsApi { client: self }
+    }
+
+    pub fn notifications(&self) -> NotificationsApi<'_> {
+        NotificationsApi { client: self }
+    }
+
+    pub fn sdn(&self) -> SdnApi<'_> {
+        SdnApi { client: self }
+    }
+
     pub fn task(&self) -> TaskApi<'_> {
         TaskApi { client: self }
     }
@@ -1092,6 +2209,12 @@ mod tests {
         let _ = client.lxc();
         let _ = client.storage();
         let _ = client.backup();
+        let _ = client.ceph();
+        let _ = client.disks();
+        let _ = client.ha();
+        let _ = client.metrics();
+        let _ = client.notifications();
+        let _ = client.sdn();
         let _ = client.task();
         let _ = client.raw();
     }
@@ -1,8 +1,19 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use reqwest::header::{AUTHORIZATION, COOKIE, HeaderValue};
 use reqwest::{Method, RequestBuilder};
+use serde::{Deserialize, Serialize};
 
 use crate::error::PveError;
 
+/// PVE tickets expire two hours after issue; cached ones are dropped a little earlier so a
+/// reused ticket does not lapse mid-run.
+const TICKET_LIFETIME_SECS: u64 = 2 * 60 * 60;
+const TICKET_REUSE_MARGIN_SECS: u64 = 10 * 60;
+
 #[derive(Debug, Clone)]
 pub enum Auth {
     None,
@@ -46,3 +57,165 @@ pub(crate) fn apply_auth(
         }
     }
 }
+
+/// On-disk form of a ticket obtained by password login, bound to the server and login it
+/// was issued for.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct CachedTicket {
+    pub base_url: String,
+    pub username: String,
+    pub realm: Option<String>,
+    pub ticket: String,
+    pub csrf: String,
+}
+
+impl CachedTicket {
+    /// Issue time encoded in the ticket (`PVE:<userid>:<hex unix time>::<signature>`).
+    fn issued_at(&self) -> Option<u64> {
+        let hex = self.ticket.split(':').nth(2)?;
+        u64::from_str_radix(hex, 16).ok()
+    }
+
+    fn is_fresh(&self, now: u64) -> bool {
+        self.issued_at().is_some_and(|issued| {
+            now.saturating_sub(issued) + TICKET_REUSE_MARGIN_SECS < TICKET_LIFETIME_SECS
+        })
+    }
+}
+
+/// Returns the cached ticket for this login if the file exists, belongs to the same server
+/// and user, and the ticket has not expired. A cache file other users can read is ignored
+/// (and later overwritten with private permissions).
+pub(crate) fn load_cached_ticket(
+    path: &Path,
+    base_url: &str,
+    username: &str,
+    realm: Option<&str>,
+) -> Option<CachedTicket> {
+    let metadata = fs::metadata(path).ok()?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o077 != 0 {
+            return None;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = metadata;
+
+    let cached: CachedTicket = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    (cached.base_url == base_url
+        && cached.username == username
+        && cached.realm.as_deref() == realm
+        && cached.is_fresh(now))
+    .then_some(cached)
+}
+
+/// Writes the cache readable by the owner only, replacing any previous file atomically.
+/// The temporary file gets a fresh name and is created exclusively, so a symlink planted
+/// next to the cache is never followed.
+pub(crate) fn store_cached_ticket(path: &Path, ticket: &CachedTicket) -> Result<(), PveError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let mut tmp_name = path.as_os_str().to_owned();
+    tmp_name.push(format!(".{}.{nanos:08x}.tmp", std::process::id()));
+    let tmp = Path::new(&tmp_name);
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(tmp)?;
+    let written = file
+        .write_all(&serde_json::to_vec(ticket)?)
+        .and_then(|()| file.sync_all())
+        .and_then(|()| {
+            drop(file);
+            fs::rename(tmp, path)
+        });
+    if let Err(err) = written {
+        let _ = fs::remove_file(tmp);
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{CachedTicket, load_cached_ticket, store_cached_ticket};
+
+    fn ticket_issued(secs_ago: u64) -> CachedTicket {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_secs();
+        CachedTicket {
+            base_url: "https://pve.example.com:8006/".to_string(),
+            username: "root".to_string(),
+            realm: Some("pam".to_string()),
+            ticket: format!("PVE:root@pam:{:X}::sig", now - secs_ago),
+            csrf: "csrf".to_string(),
+        }
+    }
+
+    fn cache_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "pve-sdk-ticket-{}-{name}/ticket.json",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn cached_ticket_round_trips_for_the_same_login() {
+        let path = cache_path("roundtrip");
+        let ticket = ticket_issued(60);
+        store_cached_ticket(&path, &ticket).expect("store");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).expect("meta").permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let url = "https://pve.example.com:8006/";
+        assert_eq!(
+            load_cached_ticket(&path, url, "root", Some("pam")),
+            Some(ticket)
+        );
+        assert_eq!(load_cached_ticket(&path, url, "admin", Some("pam")), None);
+        assert_eq!(
+            load_cached_ticket(&path, "https://other:8006/", "root", Some("pam")),
+            None
+        );
+        let _ = std::fs::remove_dir_all(path.parent().expect("dir"));
+    }
+
+    #[test]
+    fn expired_or_exposed_cache_is_ignored() {
+        let path = cache_path("expired");
+        let url = "https://pve.example.com:8006/";
+        store_cached_ticket(&path, &ticket_issued(2 * 60 * 60)).expect("store");
+        assert_eq!(load_cached_ticket(&path, url, "root", Some("pam")), None);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            store_cached_ticket(&path, &ticket_issued(60)).expect("store");
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).expect("chmod");
+            assert_eq!(load_cached_ticket(&path, url, "root", Some("pam")), None);
+        }
+        let _ = std::fs::remove_dir_all(path.parent().expect("dir"));
+    }
+}
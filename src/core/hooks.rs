@@ -0,0 +1,56 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::Method;
+
+use crate::params::PveParams;
+
+/// Passed to the `on_request` hook before a call is sent. Never carries headers or bodies.
+#[derive(Debug, Clone)]
+pub struct RequestInfo {
+    pub method: Method,
+    pub path: String,
+}
+
+/// Passed to the `on_response` hook once a call finishes. `status` is `None` when no HTTP
+/// response was received (connect/timeout failures).
+#[derive(Debug, Clone)]
+pub struct ResponseInfo {
+    pub method: Method,
+    pub path: String,
+    pub status: Option<u16>,
+    pub duration: Duration,
+}
+
+/// Passed to the `on_dry_run` hook for each write a dry-run client skips. Unlike
+/// `RequestInfo` this carries the parameters, since reviewing them is the point.
+/// `path` is the API path without the `/api2/json` prefix; `params` holds the form body,
+/// or the query string for `DELETE`s and other body-less writes.
+#[derive(Debug, Clone)]
+pub struct DryRunRequest {
+    pub method: Method,
+    pub path: String,
+    pub params: PveParams,
+}
+
+pub type RequestHook = Arc<dyn Fn(&RequestInfo) + Send + Sync>;
+pub type ResponseHook = Arc<dyn Fn(&ResponseInfo) + Send + Sync>;
+pub type DryRunHook = Arc<dyn Fn(&DryRunRequest) + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub(crate) struct ClientHooks {
+    pub on_request: Option<RequestHook>,
+    pub on_response: Option<ResponseHook>,
+    pub on_dry_run: Option<DryRunHook>,
+}
+
+impl fmt::Debug for ClientHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientHooks")
+            .field("on_request", &self.on_request.is_some())
+            .field("on_response", &self.on_response.is_some())
+            .field("on_dry_run", &self.on_dry_run.is_some())
+            .finish()
+    }
+}
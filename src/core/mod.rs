@@ -1,2 +1,5 @@
 pub(crate) mod auth;
+pub(crate) mod hooks;
+pub(crate) mod stream;
+pub(crate) mod telemetry;
 pub(crate) mod transport;
@@ -0,0 +1,302 @@
+//! Incremental decoding of `{"data": [...]}` envelopes.
+//!
+//! Elements of the `data` array are split out of the byte stream as they complete, so callers
+//! can process or drop items without buffering the whole response.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io;
+
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::core::transport::extjs_failure_status;
+use crate::error::PveError;
+
+/// Envelope bytes outside `data` kept for `finish`; an ExtJS failure body is far smaller.
+const ENVELOPE_LIMIT: usize = 64 * 1024;
+
+/// Byte-level splitter for the top-level `data` array of an API envelope.
+#[derive(Debug, Default)]
+pub(crate) struct DataArraySplitter {
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    key: Option<Vec<u8>>,
+    last_key: Vec<u8>,
+    in_data: bool,
+    closed: bool,
+    item: Vec<u8>,
+    /// Everything outside the `data` array, up to `ENVELOPE_LIMIT` bytes.
+    envelope: Vec<u8>,
+}
+
+impl DataArraySplitter {
+    /// Feeds a chunk and returns the raw bytes of every element completed by it. Fails on
+    /// a closing bracket that no element opened.
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Result<Vec<Vec<u8>>, PveError> {
+        let mut items = Vec::new();
+        for &byte in chunk {
+            if !self.in_data && self.envelope.len() < ENVELOPE_LIMIT {
+                self.envelope.push(byte);
+            }
+            if self.in_string {
+                self.push_string_byte(byte);
+                continue;
+            }
+
+            if self.in_data {
+                match byte {
+                    b',' | b']' if self.depth == 2 => {
+                        let item = std::mem::take(&mut self.item);
+                        if !item.is_empty() {
+                            items.push(item);
+                        }
+                        if byte == b']' {
+                            self.depth = 1;
+                            self.in_data = false;
+                            self.closed = true;
+                        }
+                    }
+                    b' ' | b'\n' | b'\r' | b'\t' if self.depth == 2 => {}
+                    _ => {
+                        match byte {
+                            b'"' => self.in_string = true,
+                            b'{' | b'[' => self.depth += 1,
+                            b'}' | b']' if self.depth > 2 => self.depth -= 1,
+                            b'}' => return Err(malformed("unbalanced '}' in the data array")),
+                            _ => {}
+                        }
+                        self.item.push(byte);
+                    }
+                }
+                continue;
+            }
+
+            match byte {
+                b'"' => {
+                    self.in_string = true;
+                    if self.depth == 1 {
+                        self.key = Some(Vec::new());
+                    }
+                }
+                b'[' if self.depth == 1 && self.last_key == b"data" && !self.closed => {
+                    self.depth = 2;
+                    self.in_data = true;
+                }
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' => self.depth = self.depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        Ok(items)
+    }
+
+    /// Checks the envelope once the body has ended: an unterminated `data` array and a
+    /// body without one are `Decode` errors, and an ExtJS `"success": 0` reply is mapped
+    /// like a non-2xx status for `path`.
+    pub(crate) fn finish(&self, path: &str) -> Result<(), PveError> {
+        if self.in_data || self.in_string {
+            return Err(PveError::Decode(serde_json::Error::io(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "response ended inside the data array",
+            ))));
+        }
+        let envelope = String::from_utf8_lossy(&self.envelope);
+        if let Some(status) = extjs_failure_status(&envelope) {
+            return Err(PveError::from_status(path, status, envelope.into_owned()));
+        }
+        if !self.closed {
+            return Err(malformed("response has no data array"));
+        }
+        Ok(())
+    }
+
+    fn push_string_byte(&mut self, byte: u8) {
+        if self.in_data {
+            self.item.push(byte);
+        }
+        if self.escaped {
+            self.escaped = false;
+        } else if byte == b'\\' {
+            self.escaped = true;
+        } else if byte == b'"' {
+            self.in_string = false;
+            if let Some(key) = self.key.take() {
+                self.last_key = key;
+            }
+            return;
+        }
+        if let Some(key) = &mut self.key {
+            key.push(byte);
+        }
+    }
+}
+
+fn malformed(reason: &str) -> PveError {
+    PveError::Decode(serde_json::Error::io(io::Error::new(
+        io::ErrorKind::InvalidData,
+        reason.to_string(),
+    )))
+}
+
+enum State<F> {
+    Connecting(F),
+    Reading {
+        body: BoxStream<'static, Result<Vec<u8>, reqwest::Error>>,
+        splitter: DataArraySplitter,
+        pending: VecDeque<Vec<u8>>,
+    },
+    Done,
+}
+
+/// Yields each element of the response's `data` array as soon as it has been received.
+/// `path` names the request in errors reported by `DataArraySplitter::finish`.
+pub(crate) fn data_array_stream<'a, T, F>(
+    path: String,
+    response: F,
+) -> impl Stream<Item = Result<T, PveError>> + 'a
+where
+    T: DeserializeOwned + 'a,
+    F: Future<Output = Result<reqwest::Response, PveError>> + 'a,
+{
+    stream::unfold(State::Connecting(response), move |state| {
+        let path = path.clone();
+        async move {
+            let mut state = state;
+            loop {
+                match state {
+                    State::Connecting(response) => match response.await {
+                        Ok(response) => {
+                            state = State::Reading {
+                                body: response
+                                    .bytes_stream()
+                                    .map(|chunk| chunk.map(|bytes| bytes.to_vec()))
+                                    .boxed(),
+                                splitter: DataArraySplitter::default(),
+                                pending: VecDeque::new(),
+                            };
+                        }
+                        Err(err) => return Some((Err(err), State::Done)),
+                    },
+                    State::Reading {
+                        mut body,
+                        mut splitter,
+                        mut pending,
+                    } => {
+                        if let Some(item) = pending.pop_front() {
+                            let decoded = serde_json::from_slice(&item).map_err(PveError::from);
+                            return Some((
+                                decoded,
+                                State::Reading {
+                                    body,
+                                    splitter,
+                                    pending,
+                                },
+                            ));
+                        }
+                        match body.next().await {
+                            Some(Ok(chunk)) => {
+                                match splitter.push(&chunk) {
+                                    Ok(items) => pending.extend(items),
+                                    Err(err) => return Some((Err(err), State::Done)),
+                                }
+                                state = State::Reading {
+                                    body,
+                                    splitter,
+                                    pending,
+                                };
+                            }
+                            Some(Err(err)) => return Some((Err(err.into()), State::Done)),
+                            None => {
+                                return splitter
+                                    .finish(&path)
+                                    .err()
+                                    .map(|err| (Err(err), State::Done));
+                            }
+                        }
+                    }
+                    State::Done => return None,
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DataArraySplitter;
+
+    fn split_in_chunks(body: &[u8], chunk: usize) -> (Vec<String>, DataArraySplitter) {
+        let mut splitter = DataArraySplitter::default();
+        let mut items = Vec::new();
+        for part in body.chunks(chunk) {
+            items.extend(
+                splitter
+                    .push(part)
+                    .expect("balanced")
+                    .into_iter()
+                    .map(|item| String::from_utf8(item).expect("utf8")),
+            );
+        }
+        (items, splitter)
+    }
+
+    #[test]
+    fn splitter_handles_arbitrary_chunk_boundaries() {
+        let body = br#"{"meta":{"data":[0]},"data": [ {"volid":"a:iso/x]y.iso","n":[1,2]}, {"volid":"b\"c"} ]}"#;
+        for chunk in [1, 2, 3, 7, body.len()] {
+            let (items, splitter) = split_in_chunks(body, chunk);
+            assert_eq!(
+                items,
+                [
+                    r#"{"volid":"a:iso/x]y.iso","n":[1,2]}"#,
+                    r#"{"volid":"b\"c"}"#
+                ],
+                "chunk size {chunk}"
+            );
+            assert!(splitter.finish("/content").is_ok());
+        }
+    }
+
+    #[test]
+    fn splitter_reports_truncated_array() {
+        let (items, splitter) = split_in_chunks(br#"{"data":[{"volid":"a"},{"vol"#, 4);
+        assert_eq!(items, [r#"{"volid":"a"}"#]);
+        assert!(splitter.finish("/content").is_err());
+    }
+
+    #[test]
+    fn splitter_rejects_unbalanced_brackets() {
+        let mut splitter = DataArraySplitter::default();
+        assert!(splitter.push(br#"{"data":[}}}"#).is_err());
+
+        let mut splitter = DataArraySplitter::default();
+        let items = splitter
+            .push(br#"{"data":[{"a":1}]}}"#)
+            .expect("extra brace after data");
+        assert_eq!(items.len(), 1);
+    }
+
+    #[test]
+    fn splitter_requires_a_data_array() {
+        let (items, splitter) = split_in_chunks(br#"{"data":null}"#, 3);
+        assert!(items.is_empty());
+        assert!(matches!(
+            splitter.finish("/content"),
+            Err(crate::error::PveError::Decode(_))
+        ));
+
+        let (_, splitter) = split_in_chunks(br#"{"data":[]}"#, 3);
+        assert!(splitter.finish("/content").is_ok());
+
+        let (_, splitter) = split_in_chunks(
+            br#"{"success":0,"data":null,"message":"storage 'gone' does not exist"}"#,
+            5,
+        );
+        assert!(matches!(
+            splitter.finish("/content"),
+            Err(crate::error::PveError::NotFound { .. })
+        ));
+    }
+}
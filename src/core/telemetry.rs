@@ -0,0 +1,43 @@
+//! Optional `tracing` integration. Without the `tracing` feature every helper is a no-op.
+//!
+//! Span fields follow the OpenTelemetry HTTP client conventions (`http.request.method`,
+//! `url.path`, `http.response.status_code`). Headers and bodies are never recorded.
+
+use std::future::Future;
+
+use reqwest::Method;
+
+use crate::error::PveError;
+
+#[cfg(feature = "tracing")]
+pub(crate) async fn instrument<F: Future>(method: &Method, path: &str, future: F) -> F::Output {
+    use tracing::Instrument;
+
+    let span = tracing::info_span!(
+        "pve.request",
+        otel.kind = "client",
+        http.request.method = %method,
+        url.path = %path,
+        http.response.status_code = tracing::field::Empty,
+    );
+    future.instrument(span).await
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) async fn instrument<F: Future>(_method: &Method, _path: &str, future: F) -> F::Output {
+    future.await
+}
+
+pub(crate) fn record_status(status: u16) {
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record("http.response.status_code", status);
+    #[cfg(not(feature = "tracing"))]
+    let _ = status;
+}
+
+pub(crate) fn record_error(err: &PveError) {
+    #[cfg(feature = "tracing")]
+    tracing::error!(error = %err, "pve request failed");
+    #[cfg(not(feature = "tracing"))]
+    let _ = err;
+}
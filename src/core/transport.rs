@@ -1,3 +1,4 @@
+use std::net::{Ipv6Addr, SocketAddr, SocketAddrV6};
 use std::time::Duration;
 
 use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
@@ -8,15 +9,23 @@ use url::Url;
 use crate::error::PveError;
 use crate::models::ApiEnvelope;
 
-pub(crate) fn normalize_api_path(path: &str) -> String {
-    if path.starts_with("/api2/json") {
+/// Prefix prepended to API paths unless `ClientOption::api_base_path` overrides it.
+pub(crate) const DEFAULT_API_BASE_PATH: &str = "/api2/json";
+
+/// Prefixes `path` with `base` (`/api2/json`, `/api2/extjs`, or empty) unless it already
+/// starts with it.
+pub(crate) fn normalize_api_path(base: &str, path: &str) -> String {
+    if path
+        .strip_prefix(base)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    {
         return path.to_string();
     }
 
     if path.starts_with('/') {
-        format!("/api2/json{path}")
+        format!("{base}{path}")
     } else {
-        format!("/api2/json/{path}")
+        format!("{base}/{path}")
     }
 }
 
@@ -24,14 +33,101 @@ pub(crate) fn enc(value: &str) -> String {
     utf8_percent_encode(value, NON_ALPHANUMERIC).to_string()
 }
 
-pub(crate) fn join_api_url(base_url: &Url, path: &str) -> Result<Url, PveError> {
-    let normalized = normalize_api_path(path);
+pub(crate) fn join_api_url(base_url: &Url, api_base: &str, path: &str) -> Result<Url, PveError> {
+    let normalized = normalize_api_path(api_base, path);
     base_url
         .join(normalized.trim_start_matches('/'))
         .map_err(|_| PveError::InvalidBaseUrl(format!("unable to join path: {normalized}")))
 }
 
+/// Suffix of the placeholder host standing in for a zoned IPv6 address.
+const ZONED_HOST_SUFFIX: &str = "ipv6-zone.invalid";
+
+/// Maps a zoned IPv6 host to a placeholder hostname and the socket address it must resolve
+/// to. A bare address takes the zone literally (`fe80::1%eth0`, `fe80::1%3`); a bracketed
+/// one is URL syntax and needs the `%25` escape (`[fe80::1%25eth0]`). `url` rejects zone
+/// ids, so the base URL carries the placeholder and the HTTP client pins it to the scoped
+/// address. Returns `None` for hosts without a zone.
+pub(crate) fn zoned_host(host: &str, port: u16) -> Result<Option<(String, SocketAddr)>, PveError> {
+    let host = host.trim();
+    let bracketed = host.strip_prefix('[').and_then(|h| h.strip_suffix(']'));
+    let Some((addr, zone)) = bracketed.unwrap_or(host).split_once('%') else {
+        return Ok(None);
+    };
+    let zone = match bracketed {
+        Some(_) => zone.strip_prefix("25").ok_or_else(|| {
+            PveError::InvalidBaseUrl(format!(
+                "zone id in {host} must be percent-encoded as %25<zone>"
+            ))
+        })?,
+        None => zone,
+    };
+    let addr: Ipv6Addr = addr
+        .parse()
+        .map_err(|_| PveError::InvalidBaseUrl(format!("invalid zoned ipv6 address: {host}")))?;
+    let valid_zone = !zone.is_empty()
+        && zone
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid_zone {
+        return Err(PveError::InvalidBaseUrl(format!(
+            "invalid ipv6 zone id {zone:?} in {host}"
+        )));
+    }
+    let scope_id = match zone.parse::<u32>() {
+        Ok(index) => index,
+        Err(_) => interface_index(zone).ok_or_else(|| {
+            PveError::InvalidBaseUrl(format!("unknown network interface {zone:?} in {host}"))
+        })?,
+    };
+
+    let segments = addr
+        .segments()
+        .iter()
+        .map(|s| format!("{s:x}"))
+        .collect::<Vec<_>>()
+        .join("-");
+    let placeholder = format!(
+        "{segments}.{}.{ZONED_HOST_SUFFIX}",
+        zone.to_ascii_lowercase()
+    );
+    let socket = SocketAddr::V6(SocketAddrV6::new(addr, port, 0, scope_id));
+    Ok(Some((placeholder, socket)))
+}
+
+/// std has no safe way to resolve an interface name, so this is the crate's one FFI call.
+#[cfg(unix)]
+fn interface_index(name: &str) -> Option<u32> {
+    let name = std::ffi::CString::new(name).ok()?;
+    // SAFETY: `name` is a valid NUL-terminated string for the duration of the call, and
+    // `if_nametoindex` only reads it.
+    let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+    (index != 0).then_some(index)
+}
+
+#[cfg(not(unix))]
+fn interface_index(_name: &str) -> Option<u32> {
+    None
+}
+
+/// Replaces the `user:pass@` part of a URL-like string with `***@`, so the string can go
+/// into an error message. Works on malformed input, which is when it is needed.
+pub(crate) fn redact_url_credentials(url: &str) -> String {
+    let authority_start = url.find("://").map_or(0, |i| i + 3);
+    let rest = &url[authority_start..];
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    match rest[..authority_end].rfind('@') {
+        Some(at) => format!("{}***{}", &url[..authority_start], &rest[at..]),
+        None => url.to_string(),
+    }
+}
+
 pub(crate) fn build_base_url(host: &str, port: u16, https: bool) -> Result<Url, PveError> {
+    if let Some((placeholder, _)) = zoned_host(host, port)? {
+        let scheme = if https { "https" } else { "http" };
+        let base = format!("{scheme}://{placeholder}:{port}/");
+        return Url::parse(&base).map_err(|_| PveError::InvalidBaseUrl(base));
+    }
     let mut host = host.trim().to_string();
     if host.starts_with("https://") || host.starts_with("http://") {
         let parsed = Url::parse(&host).map_err(|_| {
@@ -96,10 +192,30 @@ pub(crate) fn build_base_url(host: &str, port: u16, https: bool) -> Result<Url,
     Url::parse(&base).map_err(|_| PveError::InvalidBaseUrl(base))
 }
 
+/// Optional reqwest builder settings; unset fields keep reqwest's defaults.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HttpTuning {
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout: Option<Duration>,
+    pub http1_only: bool,
+    pub http2_prior_knowledge: bool,
+    pub proxy: Option<String>,
+    pub no_proxy: bool,
+    pub root_certificates: Vec<reqwest::Certificate>,
+    /// PEM bundles or a single DER certificate, parsed when the client is built.
+    pub root_certificate_bytes: Vec<Vec<u8>>,
+    pub identity: Option<reqwest::Identity>,
+    /// PEM private key plus certificate chain, parsed when the client is built.
+    pub identity_pem: Option<Vec<u8>>,
+    /// Placeholder host pinned to a zoned IPv6 address; see `zoned_host`.
+    pub resolve: Option<(String, SocketAddr)>,
+}
+
 pub(crate) fn build_http_client(
     insecure_tls: bool,
     timeout: Option<Duration>,
     connect_timeout: Option<Duration>,
+    tuning: &HttpTuning,
 ) -> Result<reqwest::Client, PveError> {
     let mut builder = reqwest::Client::builder().danger_accept_invalid_certs(insecure_tls);
     if let Some(timeout) = timeout {
@@ -108,24 +224,204 @@ pub(crate) fn build_http_client(
     if let Some(connect_timeout) = connect_timeout {
         builder = builder.connect_timeout(connect_timeout);
     }
+    if let Some(max_idle) = tuning.pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max_idle);
+    }
+    if let Some(idle_timeout) = tuning.pool_idle_timeout {
+        builder = builder.pool_idle_timeout(idle_timeout);
+    }
+    match (tuning.http1_only, tuning.http2_prior_knowledge) {
+        (true, true) => {
+            return Err(PveError::InvalidArgument(
+                "http1_only and http2_prior_knowledge are mutually exclusive".to_string(),
+            ));
+        }
+        (true, false) => builder = builder.http1_only(),
+        (false, true) => builder = builder.http2_prior_knowledge(),
+        (false, false) => {}
+    }
+    if tuning.no_proxy {
+        builder = builder.no_proxy();
+    }
+    if let Some(proxy) = tuning.proxy.as_deref() {
+        let proxy = reqwest::Proxy::all(proxy).map_err(|_| {
+            PveError::InvalidArgument(format!(
+                "invalid proxy url: {}",
+                redact_url_credentials(proxy)
+            ))
+        })?;
+        builder = builder.proxy(proxy);
+    }
+    for cert in &tuning.root_certificates {
+        builder = builder.add_root_certificate(cert.clone());
+    }
+    for bytes in &tuning.root_certificate_bytes {
+        for cert in parse_certificates(bytes)? {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    if let Some(identity) = &tuning.identity {
+        builder = builder.identity(identity.clone());
+    }
+    if let Some(pem) = &tuning.identity_pem {
+        let identity = reqwest::Identity::from_pem(pem)
+            .map_err(|_| PveError::InvalidArgument("invalid client identity pem".to_string()))?;
+        builder = builder.identity(identity);
+    }
+    if let Some((host, addr)) = &tuning.resolve {
+        builder = builder.resolve(host, *addr);
+    }
     builder.build().map_err(PveError::from)
 }
 
-pub(crate) async fn execute<T>(request: RequestBuilder) -> Result<T, PveError>
+fn parse_certificates(bytes: &[u8]) -> Result<Vec<reqwest::Certificate>, PveError> {
+    let invalid = || PveError::InvalidArgument("invalid root certificate".to_string());
+    if bytes.trim_ascii_start().starts_with(b"-----BEGIN") {
+        let certs = reqwest::Certificate::from_pem_bundle(bytes).map_err(|_| invalid())?;
+        if certs.is_empty() {
+            return Err(invalid());
+        }
+        Ok(certs)
+    } else {
+        let cert = reqwest::Certificate::from_der(bytes).map_err(|_| invalid())?;
+        Ok(vec![cert])
+    }
+}
+
+/// Status, `Content-Type` (if any) and body text of a response.
+pub(crate) async fn read_response(
+    request: RequestBuilder,
+    limit: Option<usize>,
+) -> Result<(u16, Option<String>, String), PveError> {
+    let response = request.send().await?;
+    let status = response.status().as_u16();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = read_body_text(response, limit).await?;
+    Ok((status, content_type, body))
+}
+
+pub(crate) async fn read_body_text(
+    response: reqwest::Response,
+    limit: Option<usize>,
+) -> Result<String, PveError> {
+    match limit {
+        None => Ok(response.text().await?),
+        Some(limit) => {
+            let bytes = read_bounded(response, limit).await?;
+            Ok(String::from_utf8_lossy(&bytes).into_owned())
+        }
+    }
+}
+
+/// Reads a non-JSON body whole; the status has already been checked by the caller.
+pub(crate) async fn read_body_bytes(
+    response: reqwest::Response,
+    limit: Option<usize>,
+) -> Result<Vec<u8>, PveError> {
+    match limit {
+        None => Ok(response.bytes().await?.to_vec()),
+        Some(limit) => read_bounded(response, limit).await,
+    }
+}
+
+/// Reads chunk by chunk, failing before buffering anything past `limit`. A declared
+/// `Content-Length` over the limit is rejected without reading the body at all.
+async fn read_bounded(mut response: reqwest::Response, limit: usize) -> Result<Vec<u8>, PveError> {
+    let too_large = || PveError::ResponseTooLarge { limit };
+    if response
+        .content_length()
+        .is_some_and(|len| len > limit as u64)
+    {
+        return Err(too_large());
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Rejects a 2xx response whose `Content-Type` is not JSON before its body is streamed.
+/// The body is only read, within `limit`, to fill in `UnexpectedContentType`.
+pub(crate) async fn ensure_json_response(
+    response: reqwest::Response,
+    limit: Option<usize>,
+) -> Result<reqwest::Response, PveError> {
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .filter(|content_type| !is_json_content_type(content_type))
+        .map(str::to_string);
+    let Some(content_type) = content_type else {
+        return Ok(response);
+    };
+    let body = read_body_text(response, limit).await.unwrap_or_default();
+    Err(PveError::unexpected_content_type(&content_type, &body))
+}
+
+/// `application/json` and `+json` media types, ignoring parameters such as `charset`.
+fn is_json_content_type(content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    essence == "application/json" || essence.ends_with("+json")
+}
+
+/// Decodes the `data` envelope of a 2xx JSON response. A missing `Content-Type` is assumed
+/// to be JSON; any other non-JSON type is reported as `UnexpectedContentType`.
+pub(crate) fn decode_response<T>(
+    path: &str,
+    status: u16,
+    content_type: Option<&str>,
+    body: String,
+) -> Result<T, PveError>
 where
     T: DeserializeOwned,
 {
-    let response = request.send().await?;
-    let status = response.status();
-    let body = response.text().await?;
+    if !(200..300).contains(&status) {
+        return Err(PveError::from_status(path, status, body));
+    }
+    if let Some(content_type) = content_type
+        && !is_json_content_type(content_type)
+    {
+        return Err(PveError::unexpected_content_type(content_type, &body));
+    }
 
-    if !status.is_success() {
-        return Err(PveError::ApiStatus {
-            status: status.as_u16(),
-            body,
-        });
+    if let Some(status) = extjs_failure_status(&body) {
+        return Err(PveError::from_status(path, status, body));
     }
 
-    let payload: ApiEnvelope<T> = serde_json::from_str(&body)?;
+    let payload: ApiEnvelope<T> =
+        serde_json::from_str(&body).map_err(|err| PveError::deserialize(path, err, &body))?;
     Ok(payload.data)
 }
+
+/// `/api2/extjs` answers failures with HTTP 200 and `{"success":0,"message":...}`;
+/// returns the status to report for such a body (its `status` field, else 500).
+pub(crate) fn extjs_failure_status(body: &str) -> Option<u16> {
+    if !body.contains("\"success\"") {
+        return None;
+    }
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let success = value.get("success")?;
+    let failed = success.as_i64() == Some(0) || success.as_bool() == Some(false);
+    failed.then(|| {
+        value
+            .get("status")
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|s| u16::try_from(s).ok())
+            .filter(|s| !(200..300).contains(s))
+            .unwrap_or(500)
+    })
+}
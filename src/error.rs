@@ -1,5 +1,8 @@
 use thiserror::Error;
 
+/// Bytes of the offending body kept in `Deserialize` and `UnexpectedContentType`.
+const BODY_SNIPPET_LEN: usize = 512;
+
 #[derive(Debug, Error)]
 pub enum PveError {
     #[error("invalid base url: {0}")]
@@ -17,9 +20,59 @@ pub enum PveError {
     #[error("failed to decode api response: {0}")]
     Decode(#[from] serde_json::Error),
 
+    /// A 2xx response whose payload did not match the expected type. The body is kept
+    /// out of `Display` so logging the error cannot leak tokens it may contain.
+    #[error("failed to decode response of {path}: {source}")]
+    Deserialize {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+        /// Start of the response body, truncated to 512 bytes. Empty for `/access/ticket`,
+        /// whose body carries the ticket and CSRF token.
+        body_snippet: String,
+    },
+
+    /// The body exceeded `ClientOption::max_response_bytes`.
+    #[error("response body exceeds {limit} bytes")]
+    ResponseTooLarge { limit: usize },
+
+    /// A 2xx response that is not JSON, typically an HTML page from a misrouted proxy or
+    /// login portal. Like `Deserialize`, the body stays out of `Display`.
+    #[error("unexpected content type {content_type}")]
+    UnexpectedContentType {
+        content_type: String,
+        /// Start of the response body, truncated to 512 bytes.
+        body_snippet: String,
+    },
+
     #[error("pve api returned status {status}: {body}")]
     ApiStatus { status: u16, body: String },
 
+    #[error(
+        "permission denied for {path}{}",
+        match (.privilege, .message.is_empty()) {
+            (Some(p), _) => format!(" (needs {p})"),
+            (None, false) => format!(": {}", .message),
+            (None, true) => String::new(),
+        }
+    )]
+    PermissionDenied {
+        path: String,
+        privilege: Option<String>,
+        /// PVE's reason, e.g. `Permission check failed (user != root@pam)`.
+        message: String,
+    },
+
+    #[error(
+        "resource not found: {path}{}",
+        if .message.is_empty() { String::new() } else { format!(" ({})", .message) }
+    )]
+    NotFound {
+        path: String,
+        /// What PVE said is missing, e.g. `storage 'x' does not exist`; empty for a bare 404.
+        message: String,
+    },
+
     #[error("missing csrf token for write request in ticket mode")]
     MissingCsrfToken,
 
@@ -28,4 +81,249 @@ pub enum PveError {
 
     #[error("task {upid} timed out after {timeout_secs}s")]
     TaskTimeout { upid: String, timeout_secs: u64 },
+
+    #[error("guest agent of vm {vmid} did not respond within {timeout_secs}s")]
+    AgentTimeout { vmid: u32, timeout_secs: u64 },
+
+    #[error("operation cancelled")]
+    Cancelled,
+
+    #[error("provision {phase} phase failed: {source}")]
+    ProvisionFailed {
+        phase: String,
+        #[source]
+        source: Box<PveError>,
+    },
+}
+
+impl PveError {
+    /// Maps a non-2xx response for `path` to an error:
+    ///
+    /// - 403 becomes `PermissionDenied`.
+    /// - 404 becomes `NotFound`, as does a 500 whose message says something "does not exist"
+    ///   or starts with "no such" — PVE reports missing guest configs, storages and
+    ///   snapshots that way rather than with a 404.
+    /// - everything else stays `ApiStatus`.
+    pub(crate) fn from_status(path: &str, status: u16, body: String) -> Self {
+        let message = error_message(&body);
+        match status {
+            403 => Self::PermissionDenied {
+                path: path.to_string(),
+                privilege: missing_privilege(&message),
+                message: message.trim().to_string(),
+            },
+            404 => Self::not_found(path, &message),
+            500 if reports_missing(&message) => Self::not_found(path, &message),
+            _ => Self::ApiStatus { status, body },
+        }
+    }
+
+    fn not_found(path: &str, message: &str) -> Self {
+        Self::NotFound {
+            path: path.to_string(),
+            message: message.trim().to_string(),
+        }
+    }
+
+    pub(crate) fn deserialize(path: &str, source: serde_json::Error, body: &str) -> Self {
+        Self::Deserialize {
+            path: path.to_string(),
+            source,
+            body_snippet: if is_auth_path(path) {
+                String::new()
+            } else {
+                body_snippet(body)
+            },
+        }
+    }
+
+    pub(crate) fn unexpected_content_type(content_type: &str, body: &str) -> Self {
+        Self::UnexpectedContentType {
+            content_type: content_type.to_string(),
+            body_snippet: body_snippet(body),
+        }
+    }
+
+    pub(crate) fn is_missing_resource(&self) -> bool {
+        matches!(self, Self::NotFound { .. })
+    }
+
+    /// Create calls fail this way when the requested vmid was taken in the meantime.
+    pub(crate) fn is_already_exists(&self) -> bool {
+        matches!(self, Self::ApiStatus { body, .. } if body.contains("already exists"))
+    }
+}
+
+fn body_snippet(body: &str) -> String {
+    let mut end = body.len().min(BODY_SNIPPET_LEN);
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    let mut snippet = body[..end].to_string();
+    if end < body.len() {
+        snippet.push_str("...");
+    }
+    snippet
+}
+
+fn is_auth_path(path: &str) -> bool {
+    path.trim_end_matches('/').ends_with("/access/ticket")
+}
+
+/// PVE error bodies are either plain text or `{"data":null,"message":"..."}`.
+fn error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v.get("message")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| body.to_string())
+}
+
+fn reports_missing(message: &str) -> bool {
+    message.contains("does not exist") || message.trim_start().starts_with("no such ")
+}
+
+/// Extracts `VM.Audit` from `Permission check failed (/vms/100, VM.Audit)`. Checks on
+/// something other than a privilege (e.g. `user != root@pam`) yield `None`.
+fn missing_privilege(message: &str) -> Option<String> {
+    let (_, rest) = message.split_once("check failed (")?;
+    let (inner, _) = rest.split_once(')')?;
+    let candidate = inner.rsplit(", ").next()?.trim();
+    let is_privilege = candidate.starts_with(|c: char| c.is_ascii_uppercase())
+        && candidate.contains('.')
+        && candidate
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '|');
+    is_privilege.then(|| candidate.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BODY_SNIPPET_LEN, PveError, missing_privilege};
+
+    #[test]
+    fn missing_privilege_is_parsed_from_check_message() {
+        assert_eq!(
+            missing_privilege("Permission check failed (/vms/100, VM.Audit)\n").as_deref(),
+            Some("VM.Audit")
+        );
+        assert_eq!(
+            missing_privilege("Permission check failed (/, Sys.Audit|Sys.Modify)").as_deref(),
+            Some("Sys.Audit|Sys.Modify")
+        );
+        assert_eq!(
+            missing_privilege("Permission check failed (user != root@pam)"),
+            None
+        );
+        assert_eq!(missing_privilege("permission denied"), None);
+    }
+
+    #[test]
+    fn from_status_maps_only_403_to_permission_denied() {
+        let err = PveError::from_status(
+            "/nodes/pve1/qemu/100/config",
+            403,
+            r#"{"data":null,"message":"Permission check failed (/vms/100, VM.Config.Disk)\n"}"#
+                .to_string(),
+        );
+        assert!(matches!(
+            &err,
+            PveError::PermissionDenied { path, privilege: Some(p), .. }
+                if path == "/nodes/pve1/qemu/100/config" && p == "VM.Config.Disk"
+        ));
+        assert_eq!(
+            err.to_string(),
+            "permission denied for /nodes/pve1/qemu/100/config (needs VM.Config.Disk)"
+        );
+
+        let err = PveError::from_status(
+            "/access/users",
+            403,
+            r#"{"data":null,"message":"Permission check failed (user != root@pam)\n"}"#.to_string(),
+        );
+        assert_eq!(
+            err.to_string(),
+            "permission denied for /access/users: Permission check failed (user != root@pam)"
+        );
+
+        let err = PveError::from_status("/version", 401, "no ticket".to_string());
+        assert!(matches!(err, PveError::ApiStatus { status: 401, .. }));
+    }
+
+    #[test]
+    fn from_status_recognizes_missing_resources() {
+        let missing = [
+            (404, "".to_string()),
+            (
+                500,
+                r#"{"data":null,"message":"Configuration file 'nodes/pve1/qemu-server/101.conf' does not exist\n"}"#
+                    .to_string(),
+            ),
+            (500, "no such VM ('101')".to_string()),
+        ];
+        for (status, body) in missing {
+            let err = PveError::from_status("/nodes/pve1/qemu/101/status/current", status, body);
+            assert!(err.is_missing_resource(), "{err:?}");
+        }
+
+        let err = PveError::from_status(
+            "/nodes/pve1/qemu",
+            500,
+            r#"{"data":null,"message":"storage 'x' does not exist\n"}"#.to_string(),
+        );
+        assert_eq!(
+            err.to_string(),
+            "resource not found: /nodes/pve1/qemu (storage 'x' does not exist)"
+        );
+        let err = PveError::from_status("/nodes/pve1/missing", 404, String::new());
+        assert_eq!(err.to_string(), "resource not found: /nodes/pve1/missing");
+
+        let err = PveError::from_status("/cluster/nextid", 500, "cluster not ready".to_string());
+        assert!(matches!(err, PveError::ApiStatus { status: 500, .. }));
+    }
+
+    #[test]
+    fn deserialize_error_truncates_body_on_char_boundary() {
+        let source = serde_json::from_str::<u32>("\"x\"").expect_err("type mismatch");
+        let body = format!("{}é", "a".repeat(BODY_SNIPPET_LEN - 1));
+        let err = PveError::deserialize("/nodes", source, &body);
+        match err {
+            PveError::Deserialize {
+                path, body_snippet, ..
+            } => {
+                assert_eq!(path, "/nodes");
+                assert_eq!(body_snippet.len(), BODY_SNIPPET_LEN - 1 + 3);
+                assert!(body_snippet.ends_with("a..."));
+            }
+            other => panic!("expected Deserialize, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn unexpected_content_type_keeps_body_out_of_display() {
+        let err = PveError::unexpected_content_type("text/html", "<html>session=SECRET</html>");
+        assert_eq!(err.to_string(), "unexpected content type text/html");
+        assert!(matches!(
+            err,
+            PveError::UnexpectedContentType { ref body_snippet, .. } if body_snippet.contains("SECRET")
+        ));
+    }
+
+    #[test]
+    fn deserialize_error_keeps_ticket_bodies_out() {
+        let source = serde_json::from_str::<u32>("\"x\"").expect_err("type mismatch");
+        let body = r#"{"data":{"ticket":"PVE:root@pam:SECRET","CSRFPreventionToken":"TOKEN"}}"#;
+        let err = PveError::deserialize("/api2/json/access/ticket", source, body);
+        let shown = err.to_string();
+        assert!(
+            !shown.contains("SECRET") && !shown.contains("TOKEN"),
+            "{shown}"
+        );
+        assert!(
+            matches!(err, PveError::Deserialize { ref body_snippet, .. } if body_snippet.is_empty())
+        );
+
+        let source = serde_json::from_str::<u32>("\"x\"").expect_err("type mismatch");
+        let err = PveError::deserialize("/nodes", source, "[]");
+        assert!(!err.to_string().contains("[]"));
+    }
 }
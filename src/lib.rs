@@ -41,6 +41,8 @@
 //! # }
 //! ```
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
 mod client;
 mod client_api;
 mod client_option;
@@ -51,12 +53,20 @@ mod params;
 mod requests;
 mod services;
 pub mod types;
+pub mod validate;
 
+#[cfg(feature = "blocking")]
+pub use blocking::PveClientBlocking;
 pub use client::{Auth, PveClient};
 pub use client_api::{
-    AccessApi, BackupApi, ClusterApi, DatacenterApi, LxcApi, NodeApi, QemuApi, RawApi, StorageApi,
-    TaskApi,
+    AccessApi, BackupApi, CephApi, ClusterApi, DatacenterApi, DiskApi, HaApi, LxcApi, MetricsApi,
+    NodeApi, NotificationsApi, QemuApi, RawApi, SdnApi, StorageApi, TaskApi,
 };
 pub use client_option::{ClientAuth, ClientOption};
+pub use core::hooks::{
+    DryRunHook, DryRunRequest, RequestHook, RequestInfo, ResponseHook, ResponseInfo,
+};
 pub use error::PveError;
 pub use params::PveParams;
+#[cfg(feature = "cancellation")]
+pub use tokio_util::sync::CancellationToken;
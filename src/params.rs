@@ -1,22 +1,51 @@
-use std::collections::BTreeMap;
-
 use serde::Serialize;
 
+/// Ordered key/value pairs sent as a query string or form body.
+///
+/// Keys are unique unless added with `push`, which is how repeated keys (e.g. several
+/// `vmid`s) are expressed; everything else replaces earlier values.
 #[derive(Debug, Clone, Default, Serialize)]
 #[serde(transparent)]
-pub struct PveParams(pub BTreeMap<String, String>);
+pub struct PveParams(pub Vec<(String, String)>);
 
 impl PveParams {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Sets `key`, replacing every earlier value for it. The key keeps the position of its
+    /// first occurrence.
     pub fn insert<K, V>(&mut self, key: K, value: V)
     where
         K: Into<String>,
         V: Into<String>,
     {
-        self.0.insert(key.into(), value.into());
+        let key = key.into();
+        let value = value.into();
+        match self.0.iter().position(|(k, _)| *k == key) {
+            Some(idx) => {
+                self.0[idx].1 = value;
+                let mut seen = false;
+                self.0.retain(|(k, _)| {
+                    if *k != key {
+                        return true;
+                    }
+                    let first = !seen;
+                    seen = true;
+                    first
+                });
+            }
+            None => self.0.push((key, value)),
+        }
+    }
+
+    /// Appends a pair, keeping any existing values for `key`.
+    pub fn push<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.0.push((key.into(), value.into()));
     }
 
     pub fn with<K, V>(mut self, key: K, value: V) -> Self
@@ -57,21 +86,41 @@ impl PveParams {
         self.0.is_empty()
     }
 
+    /// Overrides keys present in `other`. Repeated keys in `other` are carried over as-is.
     pub fn extend(&mut self, other: &PveParams) {
-        for (key, value) in &other.0 {
-            self.0.insert(key.clone(), value.clone());
-        }
+        self.0
+            .retain(|(key, _)| !other.0.iter().any(|(k, _)| k == key));
+        self.0.extend(other.0.iter().cloned());
     }
 
+    /// Removes every value for `key`, returning the first.
     pub fn remove(&mut self, key: &str) -> Option<String> {
-        self.0.remove(key)
+        let first = self
+            .0
+            .iter()
+            .position(|(k, _)| k == key)
+            .map(|idx| self.0.remove(idx).1)?;
+        self.0.retain(|(k, _)| k != key);
+        Some(first)
     }
 
+    /// First value for `key`.
     pub fn get(&self, key: &str) -> Option<&str> {
-        self.0.get(key).map(String::as_str)
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
     }
 
-    pub fn into_inner(self) -> BTreeMap<String, String> {
+    /// Every value for `key`, in insertion order.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.0
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn into_inner(self) -> Vec<(String, String)> {
         self.0
     }
 }
@@ -89,3 +138,66 @@ where
         params
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PveParams;
+
+    #[test]
+    fn insert_replaces_and_push_appends() {
+        let mut params = PveParams::new();
+        params.insert("a", "1");
+        params.push("vmid", "100");
+        params.push("vmid", "101");
+        params.insert("a", "2");
+        assert_eq!(params.get("a"), Some("2"));
+        assert_eq!(params.get("vmid"), Some("100"));
+        assert_eq!(params.get_all("vmid").collect::<Vec<_>>(), ["100", "101"]);
+
+        params.insert("vmid", "102");
+        assert_eq!(params.get_all("vmid").collect::<Vec<_>>(), ["102"]);
+        assert_eq!(params.remove("vmid").as_deref(), Some("102"));
+        assert_eq!(params.get("vmid"), None);
+    }
+
+    #[test]
+    fn duplicate_keys_survive_query_and_form_encoding() {
+        let mut params = PveParams::new();
+        params.insert("type", "vm");
+        params.push("vmid", "100");
+        params.push("vmid", "101");
+
+        let client = reqwest::Client::new();
+        let request = client
+            .post("https://pve.example.com:8006/api2/json/x")
+            .query(&params.0)
+            .form(&params.0)
+            .build()
+            .expect("request");
+        assert_eq!(request.url().query(), Some("type=vm&vmid=100&vmid=101"));
+        let body = request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .expect("form body");
+        assert_eq!(body, b"type=vm&vmid=100&vmid=101");
+
+        let decoded: PveParams =
+            url::form_urlencoded::parse(body).fold(PveParams::new(), |mut acc, (k, v)| {
+                acc.push(k, v);
+                acc
+            });
+        assert_eq!(decoded.into_inner(), params.into_inner());
+    }
+
+    #[test]
+    fn extend_overrides_keys_and_keeps_repeats() {
+        let mut base = PveParams::new().with("a", "1").with("b", "1");
+        let mut extra = PveParams::new().with("b", "2");
+        extra.push("c", "x");
+        extra.push("c", "y");
+        base.extend(&extra);
+        assert_eq!(base.get("a"), Some("1"));
+        assert_eq!(base.get("b"), Some("2"));
+        assert_eq!(base.get_all("c").count(), 2);
+    }
+}
@@ -22,7 +22,7 @@ pub use crate::types::qemu::{
     QemuSnapshotRollbackRequest,
 };
 pub use crate::types::storage::{
-    NodeStorageQuery, StorageAllocateDiskRequest, StorageContentQuery, StorageDeleteVolumeRequest,
-    StorageUploadRequest,
+    NodeStorageQuery, StorageAllocateDiskRequest, StorageContentQuery, StorageCreateRequest,
+    StorageDeleteVolumeRequest, StorageType, StorageUpdateRequest, StorageUploadRequest,
 };
 pub use crate::types::task::{TaskLogQuery, WaitTaskOptions};
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use reqwest::Method;
 use serde_json::Value;
 
@@ -7,6 +9,9 @@ use crate::error::PveError;
 use crate::models::{AccessAcl, AccessGroup, AccessRole, AccessUser, AccessUserToken};
 use crate::params::PveParams;
 use crate::requests;
+use crate::types::access::{
+    AccessDomain, DomainCreateRequest, DomainUpdateRequest, TfaDeleteRequest, TfaEntry,
+};
 
 impl PveClient {
     pub async fn access_users(&self) -> Result<Vec<AccessUser>, PveError> {
@@ -209,6 +214,99 @@ impl PveClient {
         let path = format!("/access/users/{}/token/{}", enc(userid), enc(tokenid));
         self.send(Method::DELETE, &path, None, None).await
     }
+
+    pub async fn access_domains(&self) -> Result<Vec<AccessDomain>, PveError> {
+        self.send(Method::GET, "/access/domains", None, None).await
+    }
+
+    /// Realm configuration; the typed fields cover the common keys and the
+    /// backend-specific settings land in `extra`.
+    pub async fn access_domain(&self, realm: &str) -> Result<AccessDomain, PveError> {
+        let path = format!("/access/domains/{}", enc(realm));
+        let mut value: Value = self.send(Method::GET, &path, None, None).await?;
+        // The per-realm endpoint omits the realm id in its body.
+        if let Some(map) = value.as_object_mut() {
+            map.entry("realm")
+                .or_insert_with(|| Value::String(realm.to_string()));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub async fn access_create_domain_with(
+        &self,
+        request: &DomainCreateRequest,
+    ) -> Result<(), PveError> {
+        let params = request.to_params();
+        let _: Value = self
+            .send(Method::POST, "/access/domains", None, Some(&params))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn access_update_domain_with(
+        &self,
+        realm: &str,
+        request: &DomainUpdateRequest,
+    ) -> Result<(), PveError> {
+        let path = format!("/access/domains/{}", enc(realm));
+        let params = request.to_params();
+        let _: Value = self.send(Method::PUT, &path, None, Some(&params)).await?;
+        Ok(())
+    }
+
+    pub async fn access_delete_domain(&self, realm: &str) -> Result<(), PveError> {
+        let path = format!("/access/domains/{}", enc(realm));
+        let _: Value = self.send(Method::DELETE, &path, None, None).await?;
+        Ok(())
+    }
+
+    pub async fn access_user_tfa(&self, userid: &str) -> Result<Vec<TfaEntry>, PveError> {
+        let path = format!("/access/tfa/{}", enc(userid));
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    /// Removes one second factor. `password` is the caller's own password, which
+    /// PVE requires unless the caller is root.
+    pub async fn access_delete_tfa(
+        &self,
+        userid: &str,
+        tfa_id: &str,
+        password: Option<&str>,
+    ) -> Result<(), PveError> {
+        let request = TfaDeleteRequest {
+            password: password.map(str::to_string),
+        };
+        self.access_delete_tfa_with(userid, tfa_id, &request).await
+    }
+
+    pub async fn access_delete_tfa_with(
+        &self,
+        userid: &str,
+        tfa_id: &str,
+        request: &TfaDeleteRequest,
+    ) -> Result<(), PveError> {
+        let path = format!("/access/tfa/{}/{}", enc(userid), enc(tfa_id));
+        let params = request.to_params();
+        let _: Value = self
+            .send(Method::DELETE, &path, Some(&params), None)
+            .await?;
+        Ok(())
+    }
+
+    /// Effective privileges as `path -> privilege -> propagate flag`. Without
+    /// `userid` PVE reports the authenticated user or token; without `path` it
+    /// covers every path the caller has ACLs on.
+    pub async fn access_permissions(
+        &self,
+        userid: Option<&str>,
+        path: Option<&str>,
+    ) -> Result<HashMap<String, HashMap<String, u8>>, PveError> {
+        let mut query = PveParams::new();
+        query.insert_opt("userid", userid);
+        query.insert_opt("path", path);
+        self.send(Method::GET, "/access/permissions", Some(&query), None)
+            .await
+    }
 }
 
 fn validate_acl_params(params: &PveParams) -> Result<(), PveError> {
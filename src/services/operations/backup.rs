@@ -0,0 +1,61 @@
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::client::PveClient;
+use crate::core::transport::enc;
+use crate::error::PveError;
+use crate::types::backup::{BackupJob, BackupJobRequest, BackupJobUpdate, IncludedGuest};
+
+#[derive(Deserialize)]
+struct IncludedVolumesTree {
+    #[serde(default)]
+    children: Vec<IncludedGuest>,
+}
+
+impl PveClient {
+    pub async fn backup_jobs(&self) -> Result<Vec<BackupJob>, PveError> {
+        self.send(Method::GET, "/cluster/backup", None, None).await
+    }
+
+    pub async fn backup_job(&self, id: &str) -> Result<BackupJob, PveError> {
+        let path = format!("/cluster/backup/{}", enc(id));
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    pub async fn create_backup_job_with(&self, request: &BackupJobRequest) -> Result<(), PveError> {
+        let params = request.to_params();
+        let _: Value = self
+            .send(Method::POST, "/cluster/backup", None, Some(&params))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_backup_job_with(
+        &self,
+        id: &str,
+        request: &BackupJobUpdate,
+    ) -> Result<(), PveError> {
+        let params = request.to_params();
+        let path = format!("/cluster/backup/{}", enc(id));
+        let _: Value = self.send(Method::PUT, &path, None, Some(&params)).await?;
+        Ok(())
+    }
+
+    pub async fn delete_backup_job(&self, id: &str) -> Result<(), PveError> {
+        let path = format!("/cluster/backup/{}", enc(id));
+        let _: Value = self.send(Method::DELETE, &path, None, None).await?;
+        Ok(())
+    }
+
+    /// Guests (and their volumes) the job selects once `all`, pool and exclude rules
+    /// are expanded.
+    pub async fn backup_job_included_guests(
+        &self,
+        id: &str,
+    ) -> Result<Vec<IncludedGuest>, PveError> {
+        let path = format!("/cluster/backup/{}/included_volumes", enc(id));
+        let tree: IncludedVolumesTree = self.send(Method::GET, &path, None, None).await?;
+        Ok(tree.children)
+    }
+}
@@ -0,0 +1,23 @@
+use reqwest::Method;
+
+use crate::client::PveClient;
+use crate::core::transport::enc;
+use crate::error::PveError;
+use crate::types::ceph::{CephOsdTree, CephPool, CephStatus};
+
+impl PveClient {
+    pub async fn ceph_status(&self) -> Result<CephStatus, PveError> {
+        self.send(Method::GET, "/cluster/ceph/status", None, None)
+            .await
+    }
+
+    pub async fn ceph_osds(&self, node: &str) -> Result<CephOsdTree, PveError> {
+        let path = format!("/nodes/{}/ceph/osd", enc(node));
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    pub async fn ceph_pools(&self, node: &str) -> Result<Vec<CephPool>, PveError> {
+        let path = format!("/nodes/{}/ceph/pool", enc(node));
+        self.send(Method::GET, &path, None, None).await
+    }
+}
@@ -1,12 +1,22 @@
+use futures_util::{StreamExt, TryStreamExt, stream};
 use reqwest::Method;
+use serde_json::Value;
 
 use crate::client::PveClient;
+use crate::core::transport::enc;
 use crate::error::PveError;
-use crate::models::{ClusterResource, ClusterStatusItem, NodeSummary};
+use crate::models::{ClusterResource, ClusterStatusItem, NodeSummary, NodeTask};
 use crate::params::PveParams;
 use crate::requests;
+use crate::types::cluster::{ClusterConfigNode, ClusterJoinInfo, ClusterLogEntry};
+use crate::types::common::CreateOutcome;
 
 impl PveClient {
+    /// Attempts made by `*_create_auto_id` before giving up on vmid collisions.
+    pub const DEFAULT_CREATE_ATTEMPTS: u32 = 3;
+    /// Nodes queried at once by `cluster_tasks`.
+    pub const DEFAULT_NODE_CONCURRENCY: usize = 4;
+
     pub async fn nodes(&self) -> Result<Vec<NodeSummary>, PveError> {
         self.send(Method::GET, "/nodes", None, None).await
     }
@@ -34,10 +44,178 @@ impl PveClient {
             .await
     }
 
+    /// Resources whose `pool` is `pool`, filtered client-side.
+    pub async fn cluster_resources_in_pool(
+        &self,
+        pool: &str,
+    ) -> Result<Vec<ClusterResource>, PveError> {
+        let mut resources = self.cluster_resources(None).await?;
+        resources.retain(|resource| resource.in_pool(pool));
+        Ok(resources)
+    }
+
+    /// Guests carrying `tag`, filtered client-side.
+    pub async fn cluster_resources_with_tag(
+        &self,
+        tag: &str,
+    ) -> Result<Vec<ClusterResource>, PveError> {
+        let mut resources = self
+            .cluster_resources_with(
+                &requests::ClusterResourcesQuery::new()
+                    .resource_type(requests::ClusterResourceType::Vm),
+            )
+            .await?;
+        resources.retain(|resource| resource.has_tag(tag));
+        Ok(resources)
+    }
+
     pub async fn cluster_next_id(&self, vmid: Option<u32>) -> Result<u32, PveError> {
         let mut query = PveParams::new();
         query.insert_opt("vmid", vmid.map(|v| v.to_string()));
-        self.send(Method::GET, "/cluster/nextid", Some(&query), None)
+        // PVE encodes the id as a JSON string.
+        let id: Value = self
+            .send(Method::GET, "/cluster/nextid", Some(&query), None)
+            .await?;
+        id.as_u64()
+            .or_else(|| id.as_str().and_then(|s| s.parse().ok()))
+            .and_then(|id| u32::try_from(id).ok())
+            .ok_or_else(|| {
+                PveError::Decode(serde::de::Error::custom(format!(
+                    "unexpected /cluster/nextid value: {id}"
+                )))
+            })
+    }
+
+    /// Asks PVE whether `vmid` is unused; a taken id is reported as `Ok(false)` rather than
+    /// the parameter error `cluster_next_id(Some(vmid))` returns.
+    pub async fn cluster_is_vmid_free(&self, vmid: u32) -> Result<bool, PveError> {
+        match self.cluster_next_id(Some(vmid)).await {
+            Ok(_) => Ok(true),
+            Err(err) if err.is_already_exists() => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Runs `node_tasks_with` on every online node, tagging each task with its node. Nodes
+    /// are returned in `nodes()` order; the first failing node aborts the whole call.
+    pub async fn cluster_tasks(
+        &self,
+        query: &requests::NodeTasksQuery,
+    ) -> Result<Vec<(String, NodeTask)>, PveError> {
+        self.cluster_tasks_with_concurrency(query, Self::DEFAULT_NODE_CONCURRENCY)
+            .await
+    }
+
+    /// Same as `cluster_tasks`, querying at most `max_concurrency` nodes at a time.
+    pub async fn cluster_tasks_with_concurrency(
+        &self,
+        query: &requests::NodeTasksQuery,
+        max_concurrency: usize,
+    ) -> Result<Vec<(String, NodeTask)>, PveError> {
+        let nodes = self.nodes().await?;
+        let per_node: Vec<(String, Vec<NodeTask>)> = stream::iter(
+            nodes
+                .into_iter()
+                .filter(|n| n.status.as_deref().is_none_or(|s| s == "online")),
+        )
+        .map(|n| async move {
+            let tasks = self.node_tasks_with(&n.node, query).await?;
+            Ok::<_, PveError>((n.node, tasks))
+        })
+        .buffered(max_concurrency.max(1))
+        .try_collect()
+        .await?;
+
+        Ok(per_node
+            .into_iter()
+            .flat_map(|(node, tasks)| tasks.into_iter().map(move |t| (node.clone(), t)))
+            .collect())
+    }
+
+    pub async fn cluster_log(&self, max: Option<u64>) -> Result<Vec<ClusterLogEntry>, PveError> {
+        let mut query = PveParams::new();
+        query.insert_opt("max", max.map(|v| v.to_string()));
+        self.send(Method::GET, "/cluster/log", Some(&query), None)
+            .await
+    }
+
+    pub async fn cluster_config_nodes(&self) -> Result<Vec<ClusterConfigNode>, PveError> {
+        self.send(Method::GET, "/cluster/config/nodes", None, None)
             .await
     }
+
+    pub async fn cluster_config_totem(&self) -> Result<Value, PveError> {
+        self.send(Method::GET, "/cluster/config/totem", None, None)
+            .await
+    }
+
+    pub async fn cluster_config_join_info(&self) -> Result<ClusterJoinInfo, PveError> {
+        self.send(Method::GET, "/cluster/config/join", None, None)
+            .await
+    }
+
+    /// Guest (QEMU or LXC, on any node) currently holding `vmid`.
+    pub(crate) async fn cluster_guest_resource(
+        &self,
+        vmid: u32,
+    ) -> Result<Option<ClusterResource>, PveError> {
+        let resources = self.cluster_resources(Some("vm")).await?;
+        Ok(resources.into_iter().find(|r| r.vmid == Some(vmid)))
+    }
+
+    /// Maps a create failure caused by a concurrent create of the same vmid to
+    /// `AlreadyExists`; any other error is passed through.
+    pub(crate) async fn create_conflict(
+        &self,
+        vmid: u32,
+        err: PveError,
+    ) -> Result<CreateOutcome, PveError> {
+        if err.is_already_exists()
+            && let Some(existing) = self.cluster_guest_resource(vmid).await?
+        {
+            return Ok(CreateOutcome::AlreadyExists(Box::new(existing)));
+        }
+        Err(err)
+    }
+
+    /// Moves guest `vmid` of `guest_type` (`qemu`/`lxc`) on `node` into `pool`, or out of
+    /// its current pool when `pool` is `None`. A guest can only be in one pool, so it is
+    /// removed from the old pool before being added to the new one.
+    pub(crate) async fn guest_set_pool(
+        &self,
+        node: &str,
+        guest_type: &str,
+        vmid: u32,
+        pool: Option<&str>,
+    ) -> Result<(), PveError> {
+        let resource = self
+            .cluster_guest_resource(vmid)
+            .await?
+            .filter(|r| r.resource_type == guest_type && r.node.as_deref() == Some(node))
+            .ok_or_else(|| PveError::NotFound {
+                path: format!("/nodes/{node}/{guest_type}/{vmid}"),
+                message: format!("{guest_type} {vmid} is not on node {node}"),
+            })?;
+        let current = resource.pool.as_deref().filter(|p| !p.is_empty());
+        if current == pool {
+            return Ok(());
+        }
+        if let Some(current) = current {
+            self.pool_update_vms(current, vmid, true).await?;
+        }
+        if let Some(pool) = pool {
+            self.pool_update_vms(pool, vmid, false).await?;
+        }
+        Ok(())
+    }
+
+    async fn pool_update_vms(&self, pool: &str, vmid: u32, remove: bool) -> Result<(), PveError> {
+        let mut body = PveParams::new().with("vms", vmid.to_string());
+        if remove {
+            body.insert_bool("delete", true);
+        }
+        let path = format!("/pools/{}", enc(pool));
+        let _: Value = self.send(Method::PUT, &path, None, Some(&body)).await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,67 @@
+use reqwest::Method;
+
+use crate::client::PveClient;
+use crate::core::transport::enc;
+use crate::error::PveError;
+use crate::types::disk::{
+    DirectoryCreateRequest, LvmCreateRequest, ZfsCreateRequest, ZfsPool, ZfsPoolDetail,
+};
+
+impl PveClient {
+    pub async fn node_zfs_pools(&self, node: &str) -> Result<Vec<ZfsPool>, PveError> {
+        let path = format!("/nodes/{}/disks/zfs", enc(node));
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    pub async fn node_zfs_pool_detail(
+        &self,
+        node: &str,
+        name: &str,
+    ) -> Result<ZfsPoolDetail, PveError> {
+        let path = format!("/nodes/{}/disks/zfs/{}", enc(node), enc(name));
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    /// Creates the pool and returns the UPID of the `zfscreate` task.
+    pub async fn node_create_zfs_pool_with(
+        &self,
+        node: &str,
+        request: &ZfsCreateRequest,
+    ) -> Result<String, PveError> {
+        let params = request.to_params();
+        let path = format!("/nodes/{}/disks/zfs", enc(node));
+        self.send(Method::POST, &path, None, Some(&params)).await
+    }
+
+    /// Creates a volume group on `request.device`; returns the task UPID.
+    pub async fn node_create_lvm_with(
+        &self,
+        node: &str,
+        request: &LvmCreateRequest,
+    ) -> Result<String, PveError> {
+        let params = request.to_params();
+        let path = format!("/nodes/{}/disks/lvm", enc(node));
+        self.send(Method::POST, &path, None, Some(&params)).await
+    }
+
+    /// Creates a volume group plus a thin pool spanning it; returns the task UPID.
+    pub async fn node_create_lvmthin_with(
+        &self,
+        node: &str,
+        request: &LvmCreateRequest,
+    ) -> Result<String, PveError> {
+        let params = request.to_params();
+        let path = format!("/nodes/{}/disks/lvmthin", enc(node));
+        self.send(Method::POST, &path, None, Some(&params)).await
+    }
+
+    pub async fn node_create_directory_with(
+        &self,
+        node: &str,
+        request: &DirectoryCreateRequest,
+    ) -> Result<String, PveError> {
+        let params = request.to_params();
+        let path = format!("/nodes/{}/disks/directory", enc(node));
+        self.send(Method::POST, &path, None, Some(&params)).await
+    }
+}
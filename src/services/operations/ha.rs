@@ -0,0 +1,20 @@
+use reqwest::Method;
+
+use crate::client::PveClient;
+use crate::error::PveError;
+use crate::types::ha::{HaManagerStatus, HaStatusEntry, RawManagerStatus};
+
+impl PveClient {
+    pub async fn ha_manager_status(&self) -> Result<HaManagerStatus, PveError> {
+        let raw: RawManagerStatus = self
+            .send(Method::GET, "/cluster/ha/status/manager_status", None, None)
+            .await?;
+        Ok(raw.into())
+    }
+
+    /// Quorum, CRM master, per-node LRM and per-resource entries in one listing.
+    pub async fn ha_status_current(&self) -> Result<Vec<HaStatusEntry>, PveError> {
+        self.send(Method::GET, "/cluster/ha/status/current", None, None)
+            .await
+    }
+}
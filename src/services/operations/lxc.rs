@@ -7,8 +7,34 @@ use crate::error::PveError;
 use crate::models::{LxcStatus, LxcSummary, SnapshotInfo};
 use crate::params::PveParams;
 use crate::requests;
+use crate::types::common::{
+    CreateOutcome, FeatureCheck, GuestFeature, GuestState, PendingConfigEntry, Tags,
+    description_params,
+};
+use crate::types::console::{TermProxyInfo, VncProxyInfo, VncProxyRequest};
+use crate::types::lxc::{Appliance, LxcCloneRequest, LxcDeleteRequest, LxcMoveVolumeRequest};
+use crate::types::task::TaskStatus;
 
 impl PveClient {
+    pub async fn list_appliances(&self, node: &str) -> Result<Vec<Appliance>, PveError> {
+        let path = format!("/nodes/{}/aplinfo", enc(node));
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    /// Downloads an appliance `template` (see `list_appliances`) into `storage`; returns the UPID.
+    pub async fn download_appliance(
+        &self,
+        node: &str,
+        storage: &str,
+        template: &str,
+    ) -> Result<String, PveError> {
+        let mut body = PveParams::new();
+        body.insert("storage", storage);
+        body.insert("template", template);
+        let path = format!("/nodes/{}/aplinfo", enc(node));
+        self.send(Method::POST, &path, None, Some(&body)).await
+    }
+
     pub async fn lxc_list(&self, node: &str) -> Result<Vec<LxcSummary>, PveError> {
         let path = format!("/nodes/{}/lxc", enc(node));
         self.send(Method::GET, &path, None, None).await
@@ -33,11 +59,63 @@ impl PveClient {
         node: &str,
         request: &requests::LxcCreateRequest,
     ) -> Result<String, PveError> {
+        request.validate()?;
         let body = request.to_params();
         let path = format!("/nodes/{}/lxc", enc(node));
         self.send(Method::POST, &path, None, Some(&body)).await
     }
 
+    /// Creates the guest under a fresh id from `cluster_next_id`, ignoring `request.vmid`.
+    ///
+    /// Returns the vmid actually used together with the creation UPID. PVE creates guests
+    /// in a background task, so the UPID is needed to wait for the guest to exist (e.g.
+    /// with `wait_for_task`); returning only the id would leave callers unable to tell when
+    /// the create finished or whether it failed.
+    pub async fn lxc_create_auto_id(
+        &self,
+        node: &str,
+        request: &requests::LxcCreateRequest,
+    ) -> Result<(u32, String), PveError> {
+        self.lxc_create_auto_id_with_attempts(node, request, Self::DEFAULT_CREATE_ATTEMPTS)
+            .await
+    }
+
+    /// Same as `lxc_create_auto_id`, fetching a new id and retrying up to `attempts`
+    /// times in total when a concurrent creator takes the id first.
+    pub async fn lxc_create_auto_id_with_attempts(
+        &self,
+        node: &str,
+        request: &requests::LxcCreateRequest,
+        attempts: u32,
+    ) -> Result<(u32, String), PveError> {
+        let mut request = request.clone();
+        let mut attempt = 1;
+        loop {
+            request.vmid = self.cluster_next_id(None).await?;
+            match self.lxc_create_with(node, &request).await {
+                Ok(upid) => return Ok((request.vmid, upid)),
+                Err(err) if err.is_already_exists() && attempt < attempts => attempt += 1,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Creates the container unless its vmid is already in use anywhere in the cluster.
+    pub async fn lxc_create_idempotent(
+        &self,
+        node: &str,
+        request: &requests::LxcCreateRequest,
+    ) -> Result<CreateOutcome, PveError> {
+        request.validate()?;
+        if let Some(existing) = self.cluster_guest_resource(request.vmid).await? {
+            return Ok(CreateOutcome::AlreadyExists(Box::new(existing)));
+        }
+        match self.lxc_create_with(node, request).await {
+            Ok(upid) => Ok(CreateOutcome::Created(upid)),
+            Err(err) => self.create_conflict(request.vmid, err).await,
+        }
+    }
+
     pub async fn lxc_config(
         &self,
         node: &str,
@@ -66,6 +144,38 @@ impl PveClient {
         self.send(Method::GET, &path, Some(&params), None).await
     }
 
+    /// Config keys with both their running and pending values.
+    pub async fn lxc_pending(
+        &self,
+        node: &str,
+        vmid: u32,
+    ) -> Result<Vec<PendingConfigEntry>, PveError> {
+        let path = format!("/nodes/{}/lxc/{}/pending", enc(node), vmid);
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    /// Whether config changes are waiting for a container restart to take effect.
+    pub async fn lxc_needs_reboot(&self, node: &str, vmid: u32) -> Result<bool, PveError> {
+        let pending = self.lxc_pending(node, vmid).await?;
+        Ok(pending.iter().any(PendingConfigEntry::is_pending))
+    }
+
+    /// Whether `feature` can be used on the container, or on `snapname` when given.
+    pub async fn lxc_feature(
+        &self,
+        node: &str,
+        vmid: u32,
+        feature: GuestFeature,
+        snapname: Option<&str>,
+    ) -> Result<bool, PveError> {
+        let mut query = PveParams::new();
+        query.insert("feature", feature.to_string());
+        query.insert_opt("snapname", snapname);
+        let path = format!("/nodes/{}/lxc/{}/feature", enc(node), vmid);
+        let check: FeatureCheck = self.send(Method::GET, &path, Some(&query), None).await?;
+        Ok(check.is_available())
+    }
+
     pub async fn lxc_set_config(
         &self,
         node: &str,
@@ -87,11 +197,182 @@ impl PveClient {
         self.lxc_set_config(node, vmid, &params).await
     }
 
+    /// The container's notes, or `None` when they are empty.
+    pub async fn lxc_get_description(
+        &self,
+        node: &str,
+        vmid: u32,
+    ) -> Result<Option<String>, PveError> {
+        let config = self.lxc_config(node, vmid, None, None).await?;
+        Ok(config
+            .get("description")
+            .and_then(Value::as_str)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string))
+    }
+
+    /// Replaces the container's notes; an empty `text` removes them.
+    pub async fn lxc_set_description(
+        &self,
+        node: &str,
+        vmid: u32,
+        text: &str,
+    ) -> Result<(), PveError> {
+        self.lxc_set_config(node, vmid, &description_params(text)?)
+            .await
+    }
+
+    /// The container's tags.
+    pub async fn lxc_tags(&self, node: &str, vmid: u32) -> Result<Tags, PveError> {
+        let config = self.lxc_config(node, vmid, None, None).await?;
+        Ok(config
+            .get("tags")
+            .and_then(Value::as_str)
+            .map(Tags::parse)
+            .unwrap_or_default())
+    }
+
+    /// Replaces the container's tags; an empty slice removes them all.
+    pub async fn lxc_set_tags(&self, node: &str, vmid: u32, tags: &[&str]) -> Result<(), PveError> {
+        let params = tags.iter().copied().collect::<Tags>().to_params()?;
+        self.lxc_set_config(node, vmid, &params).await
+    }
+
+    /// Adds `tags` to the existing ones and returns the result.
+    pub async fn lxc_add_tags(
+        &self,
+        node: &str,
+        vmid: u32,
+        tags: &[&str],
+    ) -> Result<Tags, PveError> {
+        self.lxc_update_tags(node, vmid, |current| {
+            for tag in tags {
+                current.insert(*tag);
+            }
+        })
+        .await
+    }
+
+    /// Removes `tags` from the existing ones and returns what is left.
+    pub async fn lxc_remove_tags(
+        &self,
+        node: &str,
+        vmid: u32,
+        tags: &[&str],
+    ) -> Result<Tags, PveError> {
+        self.lxc_update_tags(node, vmid, |current| {
+            for tag in tags {
+                current.remove(tag);
+            }
+        })
+        .await
+    }
+
+    /// Read-modify-write of the tags. The config digest is sent along, so a concurrent
+    /// config change makes PVE reject the write instead of it being lost.
+    async fn lxc_update_tags(
+        &self,
+        node: &str,
+        vmid: u32,
+        update: impl FnOnce(&mut Tags),
+    ) -> Result<Tags, PveError> {
+        let config = self.lxc_config(node, vmid, None, None).await?;
+        let mut tags = config
+            .get("tags")
+            .and_then(Value::as_str)
+            .map(Tags::parse)
+            .unwrap_or_default();
+        let before = tags.clone();
+        update(&mut tags);
+        if tags != before {
+            let mut params = tags.to_params()?;
+            params.insert_opt("digest", config.get("digest").and_then(Value::as_str));
+            self.lxc_set_config(node, vmid, &params).await?;
+        }
+        Ok(tags)
+    }
+
     pub async fn lxc_status(&self, node: &str, vmid: u32) -> Result<LxcStatus, PveError> {
         let path = format!("/nodes/{}/lxc/{}/status/current", enc(node), vmid);
         self.send(Method::GET, &path, None, None).await
     }
 
+    /// Like `lxc_status`, but returns `None` when the guest does not exist.
+    pub async fn lxc_status_opt(
+        &self,
+        node: &str,
+        vmid: u32,
+    ) -> Result<Option<LxcStatus>, PveError> {
+        match self.lxc_status(node, vmid).await {
+            Ok(status) => Ok(Some(status)),
+            Err(err) if err.is_missing_resource() => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn lxc_exists(&self, node: &str, vmid: u32) -> Result<bool, PveError> {
+        Ok(self.lxc_status_opt(node, vmid).await?.is_some())
+    }
+
+    pub async fn lxc_delete(
+        &self,
+        node: &str,
+        vmid: u32,
+        params: &PveParams,
+    ) -> Result<String, PveError> {
+        let path = format!("/nodes/{}/lxc/{}", enc(node), vmid);
+        self.send(Method::DELETE, &path, Some(params), None).await
+    }
+
+    pub async fn lxc_delete_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &LxcDeleteRequest,
+    ) -> Result<String, PveError> {
+        let params = request.to_params();
+        self.lxc_delete(node, vmid, &params).await
+    }
+
+    /// Converts the container into a template. PVE completes this synchronously.
+    pub async fn lxc_template(&self, node: &str, vmid: u32) -> Result<(), PveError> {
+        let path = format!("/nodes/{}/lxc/{}/template", enc(node), vmid);
+        let _: Value = self
+            .send(Method::POST, &path, None, Some(&PveParams::new()))
+            .await?;
+        Ok(())
+    }
+
+    /// Brings the container into `desired` and waits for the resulting task. When it is
+    /// already there nothing is sent and a synthesized successful status is returned.
+    pub async fn lxc_ensure_state(
+        &self,
+        node: &str,
+        vmid: u32,
+        desired: GuestState,
+        options: &requests::WaitTaskOptions,
+    ) -> Result<TaskStatus, PveError> {
+        let current = self.lxc_status(node, vmid).await?;
+        if current.status.as_deref() == Some(desired.target_status()) {
+            return Ok(TaskStatus::completed_without_task(node));
+        }
+        let upid = self
+            .lxc_action(node, vmid, desired.action(), &PveParams::new())
+            .await?;
+        self.wait_for_task_with_options(node, &upid, options).await
+    }
+
+    /// Adds the container to `pool`, moving it out of any other pool, or removes it from its
+    /// pool when `pool` is `None`.
+    pub async fn lxc_set_pool(
+        &self,
+        node: &str,
+        vmid: u32,
+        pool: Option<&str>,
+    ) -> Result<(), PveError> {
+        self.guest_set_pool(node, "lxc", vmid, pool).await
+    }
+
     pub async fn lxc_start(
         &self,
         node: &str,
@@ -228,6 +509,84 @@ impl PveClient {
             .await
     }
 
+    pub async fn lxc_snapshot_delete(
+        &self,
+        node: &str,
+        vmid: u32,
+        snapname: &str,
+        force: Option<bool>,
+    ) -> Result<String, PveError> {
+        let mut query = PveParams::new();
+        if let Some(force) = force {
+            query.insert_bool("force", force);
+        }
+        let path = format!(
+            "/nodes/{}/lxc/{}/snapshot/{}",
+            enc(node),
+            vmid,
+            enc(snapname)
+        );
+        self.send(Method::DELETE, &path, Some(&query), None).await
+    }
+
+    pub async fn lxc_snapshot_config(
+        &self,
+        node: &str,
+        vmid: u32,
+        snapname: &str,
+    ) -> Result<Value, PveError> {
+        let path = format!(
+            "/nodes/{}/lxc/{}/snapshot/{}/config",
+            enc(node),
+            vmid,
+            enc(snapname)
+        );
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    pub async fn lxc_snapshot_update_description(
+        &self,
+        node: &str,
+        vmid: u32,
+        snapname: &str,
+        description: &str,
+    ) -> Result<(), PveError> {
+        let mut body = PveParams::new();
+        body.insert("description", description);
+        let path = format!(
+            "/nodes/{}/lxc/{}/snapshot/{}/config",
+            enc(node),
+            vmid,
+            enc(snapname)
+        );
+        let _: Value = self.send(Method::PUT, &path, None, Some(&body)).await?;
+        Ok(())
+    }
+
+    pub async fn lxc_clone(
+        &self,
+        node: &str,
+        vmid: u32,
+        newid: u32,
+        params: &PveParams,
+    ) -> Result<String, PveError> {
+        let mut body = params.clone();
+        body.insert("newid", newid.to_string());
+        let path = format!("/nodes/{}/lxc/{}/clone", enc(node), vmid);
+        self.send(Method::POST, &path, None, Some(&body)).await
+    }
+
+    pub async fn lxc_clone_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &LxcCloneRequest,
+    ) -> Result<String, PveError> {
+        let body = request.to_params();
+        let path = format!("/nodes/{}/lxc/{}/clone", enc(node), vmid);
+        self.send(Method::POST, &path, None, Some(&body)).await
+    }
+
     pub async fn lxc_migrate(
         &self,
         node: &str,
@@ -251,6 +610,47 @@ impl PveClient {
         let path = format!("/nodes/{}/lxc/{}/migrate", enc(node), vmid);
         self.send(Method::POST, &path, None, Some(&body)).await
     }
+
+    /// Grows `disk` (`rootfs`, `mp0`, ...). `size` is absolute (`16G`) or relative (`+2G`).
+    pub async fn lxc_resize(
+        &self,
+        node: &str,
+        vmid: u32,
+        disk: &str,
+        size: &str,
+    ) -> Result<String, PveError> {
+        let body = PveParams::new().with("disk", disk).with("size", size);
+        let path = format!("/nodes/{}/lxc/{}/resize", enc(node), vmid);
+        self.send(Method::PUT, &path, None, Some(&body)).await
+    }
+
+    pub async fn lxc_move_volume_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &LxcMoveVolumeRequest,
+    ) -> Result<String, PveError> {
+        let body = request.to_params();
+        let path = format!("/nodes/{}/lxc/{}/move_volume", enc(node), vmid);
+        self.send(Method::POST, &path, None, Some(&body)).await
+    }
+
+    pub async fn lxc_termproxy(&self, node: &str, vmid: u32) -> Result<TermProxyInfo, PveError> {
+        let path = format!("/nodes/{}/lxc/{}/termproxy", enc(node), vmid);
+        self.send(Method::POST, &path, None, None).await
+    }
+
+    pub async fn lxc_vncproxy(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &VncProxyRequest,
+    ) -> Result<VncProxyInfo, PveError> {
+        let body = request.to_params();
+        let path = format!("/nodes/{}/lxc/{}/vncproxy", enc(node), vmid);
+        self.send(Method::POST, &path, None, Some(&body)).await
+    }
+
     async fn lxc_action(
         &self,
         node: &str,
@@ -0,0 +1,46 @@
+use reqwest::Method;
+use serde_json::Value;
+
+use crate::client::PveClient;
+use crate::core::transport::enc;
+use crate::error::PveError;
+use crate::types::metrics_server::{MetricsServer, MetricsServerRequest, MetricsServerUpdate};
+
+impl PveClient {
+    pub async fn metrics_servers(&self) -> Result<Vec<MetricsServer>, PveError> {
+        self.send(Method::GET, "/cluster/metrics/server", None, None)
+            .await
+    }
+
+    pub async fn metrics_server(&self, id: &str) -> Result<MetricsServer, PveError> {
+        let path = format!("/cluster/metrics/server/{}", enc(id));
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    pub async fn create_metrics_server_with(
+        &self,
+        request: &MetricsServerRequest,
+    ) -> Result<(), PveError> {
+        let params = request.to_params();
+        let path = format!("/cluster/metrics/server/{}", enc(&request.id));
+        let _: Value = self.send(Method::POST, &path, None, Some(&params)).await?;
+        Ok(())
+    }
+
+    pub async fn update_metrics_server_with(
+        &self,
+        id: &str,
+        request: &MetricsServerUpdate,
+    ) -> Result<(), PveError> {
+        let params = request.to_params();
+        let path = format!("/cluster/metrics/server/{}", enc(id));
+        let _: Value = self.send(Method::PUT, &path, None, Some(&params)).await?;
+        Ok(())
+    }
+
+    pub async fn delete_metrics_server(&self, id: &str) -> Result<(), PveError> {
+        let path = format!("/cluster/metrics/server/{}", enc(id));
+        let _: Value = self.send(Method::DELETE, &path, None, None).await?;
+        Ok(())
+    }
+}
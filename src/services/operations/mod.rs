@@ -1,10 +1,17 @@
 mod access;
+mod backup;
+mod ceph;
 mod cluster;
 mod datacenter;
+mod disk;
+mod ha;
 mod lxc;
+mod metrics;
 mod node;
+mod notifications;
 mod qemu;
 mod raw;
+mod sdn;
 mod storage;
 mod system;
 mod task;
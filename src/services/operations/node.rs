@@ -4,9 +4,13 @@ use serde_json::Value;
 use crate::client::PveClient;
 use crate::core::transport::enc;
 use crate::error::PveError;
-use crate::models::{NetworkInterface, NodeTask};
+use crate::models::{NetworkInterface, NodeTask, VersionInfo};
 use crate::params::PveParams;
 use crate::requests;
+use crate::types::hardware::{MdevType, PciDevice, UsbDevice};
+use crate::types::node::{
+    JournalQuery, NetworkCreateRequest, NetworkUpdateRequest, SyslogLine, SyslogQuery,
+};
 
 impl PveClient {
     pub async fn node_status(&self, node: &str) -> Result<Value, PveError> {
@@ -14,6 +18,13 @@ impl PveClient {
         self.send(Method::GET, &path, None, None).await
     }
 
+    /// PVE version running on `node`. Unlike `version`, this is answered by that node, so
+    /// comparing it across nodes reveals skew during rolling upgrades.
+    pub async fn node_version(&self, node: &str) -> Result<VersionInfo, PveError> {
+        let path = format!("/nodes/{}/version", enc(node));
+        self.send(Method::GET, &path, None, None).await
+    }
+
     pub async fn node_tasks(
         &self,
         node: &str,
@@ -52,4 +63,86 @@ impl PveClient {
         let path = format!("/nodes/{}/network", enc(node));
         self.send(Method::GET, &path, Some(&params), None).await
     }
+
+    pub async fn node_create_network_with(
+        &self,
+        node: &str,
+        request: &NetworkCreateRequest,
+    ) -> Result<(), PveError> {
+        let params = request.to_params();
+        let path = format!("/nodes/{}/network", enc(node));
+        let _: Value = self.send(Method::POST, &path, None, Some(&params)).await?;
+        Ok(())
+    }
+
+    pub async fn node_update_network_with(
+        &self,
+        node: &str,
+        iface: &str,
+        request: &NetworkUpdateRequest,
+    ) -> Result<(), PveError> {
+        let params = request.to_params();
+        let path = format!("/nodes/{}/network/{}", enc(node), enc(iface));
+        let _: Value = self.send(Method::PUT, &path, None, Some(&params)).await?;
+        Ok(())
+    }
+
+    pub async fn node_delete_network(&self, node: &str, iface: &str) -> Result<(), PveError> {
+        let path = format!("/nodes/{}/network/{}", enc(node), enc(iface));
+        let _: Value = self.send(Method::DELETE, &path, None, None).await?;
+        Ok(())
+    }
+
+    /// Applies the staged interface changes (`ifreload`). Returns the task UPID.
+    pub async fn node_apply_network(&self, node: &str) -> Result<String, PveError> {
+        let path = format!("/nodes/{}/network", enc(node));
+        self.send(Method::PUT, &path, None, None).await
+    }
+
+    /// Discards interface changes that have not been applied yet.
+    pub async fn node_revert_network(&self, node: &str) -> Result<(), PveError> {
+        let path = format!("/nodes/{}/network", enc(node));
+        let _: Value = self.send(Method::DELETE, &path, None, None).await?;
+        Ok(())
+    }
+
+    pub async fn node_syslog(
+        &self,
+        node: &str,
+        query: &SyslogQuery,
+    ) -> Result<Vec<SyslogLine>, PveError> {
+        let params = query.to_params();
+        let path = format!("/nodes/{}/syslog", enc(node));
+        self.send(Method::GET, &path, Some(&params), None).await
+    }
+
+    pub async fn node_journal(
+        &self,
+        node: &str,
+        query: &JournalQuery,
+    ) -> Result<Vec<String>, PveError> {
+        let params = query.to_params();
+        let path = format!("/nodes/{}/journal", enc(node));
+        self.send(Method::GET, &path, Some(&params), None).await
+    }
+
+    pub async fn node_pci_devices(&self, node: &str) -> Result<Vec<PciDevice>, PveError> {
+        let path = format!("/nodes/{}/hardware/pci", enc(node));
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    /// `pci_id` is the bus address from `node_pci_devices`, e.g. `0000:01:00.0`.
+    pub async fn node_pci_mdev_types(
+        &self,
+        node: &str,
+        pci_id: &str,
+    ) -> Result<Vec<MdevType>, PveError> {
+        let path = format!("/nodes/{}/hardware/pci/{}/mdev", enc(node), enc(pci_id));
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    pub async fn node_usb_devices(&self, node: &str) -> Result<Vec<UsbDevice>, PveError> {
+        let path = format!("/nodes/{}/hardware/usb", enc(node));
+        self.send(Method::GET, &path, None, None).await
+    }
 }
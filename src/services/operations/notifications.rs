@@ -0,0 +1,80 @@
+use reqwest::Method;
+use serde_json::Value;
+
+use crate::client::PveClient;
+use crate::core::transport::enc;
+use crate::error::PveError;
+use crate::types::notifications::{
+    NotificationEndpoint, NotificationEndpointRequest, NotificationEndpointType,
+    NotificationMatcher, NotificationMatcherRequest, NotificationTarget,
+};
+
+impl PveClient {
+    pub async fn notification_targets(&self) -> Result<Vec<NotificationTarget>, PveError> {
+        self.send(Method::GET, "/cluster/notifications/targets", None, None)
+            .await
+    }
+
+    /// Sends a test notification through `target`.
+    pub async fn test_notification_target(&self, target: &str) -> Result<(), PveError> {
+        let path = format!("/cluster/notifications/targets/{}/test", enc(target));
+        let _: Value = self.send(Method::POST, &path, None, None).await?;
+        Ok(())
+    }
+
+    pub async fn notification_endpoints(
+        &self,
+        type_: NotificationEndpointType,
+    ) -> Result<Vec<NotificationEndpoint>, PveError> {
+        let path = format!("/cluster/notifications/endpoints/{type_}");
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    pub async fn create_notification_endpoint_with(
+        &self,
+        request: &NotificationEndpointRequest,
+    ) -> Result<(), PveError> {
+        request.validate()?;
+        let params = request.to_params();
+        let path = format!("/cluster/notifications/endpoints/{}", request.type_);
+        let _: Value = self.send(Method::POST, &path, None, Some(&params)).await?;
+        Ok(())
+    }
+
+    pub async fn delete_notification_endpoint(
+        &self,
+        type_: NotificationEndpointType,
+        name: &str,
+    ) -> Result<(), PveError> {
+        let path = format!("/cluster/notifications/endpoints/{type_}/{}", enc(name));
+        let _: Value = self.send(Method::DELETE, &path, None, None).await?;
+        Ok(())
+    }
+
+    pub async fn notification_matchers(&self) -> Result<Vec<NotificationMatcher>, PveError> {
+        self.send(Method::GET, "/cluster/notifications/matchers", None, None)
+            .await
+    }
+
+    pub async fn create_notification_matcher_with(
+        &self,
+        request: &NotificationMatcherRequest,
+    ) -> Result<(), PveError> {
+        let params = request.to_params();
+        let _: Value = self
+            .send(
+                Method::POST,
+                "/cluster/notifications/matchers",
+                None,
+                Some(&params),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_notification_matcher(&self, name: &str) -> Result<(), PveError> {
+        let path = format!("/cluster/notifications/matchers/{}", enc(name));
+        let _: Value = self.send(Method::DELETE, &path, None, None).await?;
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
+use std::time::Duration;
+
 use reqwest::Method;
 use serde_json::Value;
+use tokio::time::{Instant, sleep};
 
 use crate::client::PveClient;
 use crate::core::transport::enc;
@@ -7,6 +10,18 @@ use crate::error::PveError;
 use crate::models::{QemuStatus, QemuVmSummary, SnapshotInfo};
 use crate::params::PveParams;
 use crate::requests;
+use crate::types::common::{
+    CreateOutcome, FeatureCheck, GuestFeature, GuestState, PendingConfigEntry, Tags,
+    description_params,
+};
+use crate::types::qemu::{
+    AgentFstrimResult, AgentResult, MigratePreconditions, QemuConfig, QemuDeleteRequest,
+    QemuEnsureStateOptions, QemuProvisionRequest, QemuProvisionResult,
+};
+use crate::types::task::{TaskStatus, TransferProgress, Upid, parse_transfer_progress};
+
+/// Delay between guest agent pings in `qemu_wait_for_agent`.
+const AGENT_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 impl PveClient {
     pub async fn qemu_list(
@@ -40,11 +55,63 @@ impl PveClient {
         node: &str,
         request: &requests::QemuCreateRequest,
     ) -> Result<String, PveError> {
+        request.validate()?;
         let body = request.to_params();
         let path = format!("/nodes/{}/qemu", enc(node));
         self.send(Method::POST, &path, None, Some(&body)).await
     }
 
+    /// Creates the guest under a fresh id from `cluster_next_id`, ignoring `request.vmid`.
+    ///
+    /// Returns the vmid actually used together with the creation UPID. PVE creates guests
+    /// in a background task, so the UPID is needed to wait for the guest to exist (e.g.
+    /// with `wait_for_task`); returning only the id would leave callers unable to tell when
+    /// the create finished or whether it failed.
+    pub async fn qemu_create_auto_id(
+        &self,
+        node: &str,
+        request: &requests::QemuCreateRequest,
+    ) -> Result<(u32, String), PveError> {
+        self.qemu_create_auto_id_with_attempts(node, request, Self::DEFAULT_CREATE_ATTEMPTS)
+            .await
+    }
+
+    /// Same as `qemu_create_auto_id`, fetching a new id and retrying up to `attempts`
+    /// times in total when a concurrent creator takes the id first.
+    pub async fn qemu_create_auto_id_with_attempts(
+        &self,
+        node: &str,
+        request: &requests::QemuCreateRequest,
+        attempts: u32,
+    ) -> Result<(u32, String), PveError> {
+        let mut request = request.clone();
+        let mut attempt = 1;
+        loop {
+            request.vmid = self.cluster_next_id(None).await?;
+            match self.qemu_create_with(node, &request).await {
+                Ok(upid) => return Ok((request.vmid, upid)),
+                Err(err) if err.is_already_exists() && attempt < attempts => attempt += 1,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Creates the VM unless its vmid is already in use anywhere in the cluster.
+    pub async fn qemu_create_idempotent(
+        &self,
+        node: &str,
+        request: &requests::QemuCreateRequest,
+    ) -> Result<CreateOutcome, PveError> {
+        request.validate()?;
+        if let Some(existing) = self.cluster_guest_resource(request.vmid).await? {
+            return Ok(CreateOutcome::AlreadyExists(Box::new(existing)));
+        }
+        match self.qemu_create_with(node, request).await {
+            Ok(upid) => Ok(CreateOutcome::Created(upid)),
+            Err(err) => self.create_conflict(request.vmid, err).await,
+        }
+    }
+
     pub async fn qemu_config(
         &self,
         node: &str,
@@ -73,6 +140,50 @@ impl PveClient {
         self.send(Method::GET, &path, Some(&params), None).await
     }
 
+    /// Same as `qemu_config_with`, decoded into `QemuConfig`.
+    pub async fn qemu_config_typed(
+        &self,
+        node: &str,
+        vmid: u32,
+        query: &requests::QemuConfigQuery,
+    ) -> Result<QemuConfig, PveError> {
+        let params = query.to_params();
+        let path = format!("/nodes/{}/qemu/{}/config", enc(node), vmid);
+        self.send(Method::GET, &path, Some(&params), None).await
+    }
+
+    /// Config keys with both their running and pending values.
+    pub async fn qemu_pending(
+        &self,
+        node: &str,
+        vmid: u32,
+    ) -> Result<Vec<PendingConfigEntry>, PveError> {
+        let path = format!("/nodes/{}/qemu/{}/pending", enc(node), vmid);
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    /// Whether config changes are waiting for a guest restart to take effect.
+    pub async fn qemu_needs_reboot(&self, node: &str, vmid: u32) -> Result<bool, PveError> {
+        let pending = self.qemu_pending(node, vmid).await?;
+        Ok(pending.iter().any(PendingConfigEntry::is_pending))
+    }
+
+    /// Whether `feature` can be used on the VM, or on `snapname` when given.
+    pub async fn qemu_feature(
+        &self,
+        node: &str,
+        vmid: u32,
+        feature: GuestFeature,
+        snapname: Option<&str>,
+    ) -> Result<bool, PveError> {
+        let mut query = PveParams::new();
+        query.insert("feature", feature.to_string());
+        query.insert_opt("snapname", snapname);
+        let path = format!("/nodes/{}/qemu/{}/feature", enc(node), vmid);
+        let check: FeatureCheck = self.send(Method::GET, &path, Some(&query), None).await?;
+        Ok(check.is_available())
+    }
+
     pub async fn qemu_set_config_async(
         &self,
         node: &str,
@@ -114,11 +225,307 @@ impl PveClient {
         self.qemu_set_config_sync(node, vmid, &params).await
     }
 
+    /// Applies `request`, then re-reads the config so callers can assert the new state.
+    /// The returned config already carries pending values; keys that wait for a restart
+    /// are listed in `reboot_pending`.
+    pub async fn qemu_set_config_verified(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuSetConfigRequest,
+    ) -> Result<QemuConfig, PveError> {
+        self.qemu_set_config_sync_with(node, vmid, request).await?;
+        let mut config = self
+            .qemu_config_typed(node, vmid, &requests::QemuConfigQuery::default())
+            .await?;
+        config.reboot_pending = self
+            .qemu_pending(node, vmid)
+            .await?
+            .into_iter()
+            .filter(PendingConfigEntry::is_pending)
+            .map(|entry| entry.key)
+            .collect();
+        Ok(config)
+    }
+
+    /// The VM's notes, or `None` when they are empty.
+    pub async fn qemu_get_description(
+        &self,
+        node: &str,
+        vmid: u32,
+    ) -> Result<Option<String>, PveError> {
+        let config = self
+            .qemu_config_typed(node, vmid, &requests::QemuConfigQuery::default())
+            .await?;
+        Ok(config.description.filter(|text| !text.is_empty()))
+    }
+
+    /// Replaces the VM's notes; an empty `text` removes them. Only `description` is sent.
+    pub async fn qemu_set_description(
+        &self,
+        node: &str,
+        vmid: u32,
+        text: &str,
+    ) -> Result<(), PveError> {
+        self.qemu_set_config_sync(node, vmid, &description_params(text)?)
+            .await
+    }
+
+    /// The VM's tags.
+    pub async fn qemu_tags(&self, node: &str, vmid: u32) -> Result<Tags, PveError> {
+        let config = self
+            .qemu_config_typed(node, vmid, &requests::QemuConfigQuery::default())
+            .await?;
+        Ok(config.tags.as_deref().map(Tags::parse).unwrap_or_default())
+    }
+
+    /// Replaces the VM's tags; an empty slice removes them all.
+    pub async fn qemu_set_tags(
+        &self,
+        node: &str,
+        vmid: u32,
+        tags: &[&str],
+    ) -> Result<(), PveError> {
+        let params = tags.iter().copied().collect::<Tags>().to_params()?;
+        self.qemu_set_config_sync(node, vmid, &params).await
+    }
+
+    /// Adds `tags` to the existing ones and returns the result.
+    pub async fn qemu_add_tags(
+        &self,
+        node: &str,
+        vmid: u32,
+        tags: &[&str],
+    ) -> Result<Tags, PveError> {
+        self.qemu_update_tags(node, vmid, |current| {
+            for tag in tags {
+                current.insert(*tag);
+            }
+        })
+        .await
+    }
+
+    /// Removes `tags` from the existing ones and returns what is left.
+    pub async fn qemu_remove_tags(
+        &self,
+        node: &str,
+        vmid: u32,
+        tags: &[&str],
+    ) -> Result<Tags, PveError> {
+        self.qemu_update_tags(node, vmid, |current| {
+            for tag in tags {
+                current.remove(tag);
+            }
+        })
+        .await
+    }
+
+    /// Read-modify-write of the tags. The config digest is sent along, so a concurrent
+    /// config change makes PVE reject the write instead of it being lost.
+    async fn qemu_update_tags(
+        &self,
+        node: &str,
+        vmid: u32,
+        update: impl FnOnce(&mut Tags),
+    ) -> Result<Tags, PveError> {
+        let config = self
+            .qemu_config_typed(node, vmid, &requests::QemuConfigQuery::default())
+            .await?;
+        let mut tags = config.tags.as_deref().map(Tags::parse).unwrap_or_default();
+        let before = tags.clone();
+        update(&mut tags);
+        if tags != before {
+            let mut params = tags.to_params()?;
+            params.insert_opt("digest", config.digest);
+            self.qemu_set_config_sync(node, vmid, &params).await?;
+        }
+        Ok(tags)
+    }
+
     pub async fn qemu_status(&self, node: &str, vmid: u32) -> Result<QemuStatus, PveError> {
         let path = format!("/nodes/{}/qemu/{}/status/current", enc(node), vmid);
         self.send(Method::GET, &path, None, None).await
     }
 
+    /// Like `qemu_status`, but returns `None` when the guest does not exist.
+    pub async fn qemu_status_opt(
+        &self,
+        node: &str,
+        vmid: u32,
+    ) -> Result<Option<QemuStatus>, PveError> {
+        match self.qemu_status(node, vmid).await {
+            Ok(status) => Ok(Some(status)),
+            Err(err) if err.is_missing_resource() => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn qemu_exists(&self, node: &str, vmid: u32) -> Result<bool, PveError> {
+        Ok(self.qemu_status_opt(node, vmid).await?.is_some())
+    }
+
+    pub async fn qemu_delete(
+        &self,
+        node: &str,
+        vmid: u32,
+        params: &PveParams,
+    ) -> Result<String, PveError> {
+        let path = format!("/nodes/{}/qemu/{}", enc(node), vmid);
+        self.send(Method::DELETE, &path, Some(params), None).await
+    }
+
+    pub async fn qemu_delete_with(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &QemuDeleteRequest,
+    ) -> Result<String, PveError> {
+        let params = request.to_params();
+        self.qemu_delete(node, vmid, &params).await
+    }
+
+    /// Converts the VM into a template; `disk` limits the conversion to a single disk.
+    pub async fn qemu_template(
+        &self,
+        node: &str,
+        vmid: u32,
+        disk: Option<&str>,
+    ) -> Result<String, PveError> {
+        let mut body = PveParams::new();
+        body.insert_opt("disk", disk);
+        let path = format!("/nodes/{}/qemu/{}/template", enc(node), vmid);
+        self.send(Method::POST, &path, None, Some(&body)).await
+    }
+
+    /// Brings the guest into `desired` and waits for the resulting task. When it is
+    /// already there nothing is sent and a synthesized successful status is returned.
+    /// A VM that reports `running` but is paused or suspended (per `qmpstatus`) is resumed
+    /// for `Running`.
+    ///
+    /// With `options.wait_for_agent` and a desired state of `Running`, the call also waits
+    /// for the guest agent to answer pings, even if the VM was already running since it may
+    /// still be booting. The agent wait gets what is left of `options.wait.timeout`, or
+    /// `options.agent_timeout` when the wait has no timeout.
+    pub async fn qemu_ensure_state(
+        &self,
+        node: &str,
+        vmid: u32,
+        desired: GuestState,
+        options: &QemuEnsureStateOptions,
+    ) -> Result<TaskStatus, PveError> {
+        let started = Instant::now();
+        let current = self.qemu_status(node, vmid).await?;
+        let action = if current.status.as_deref() != Some(desired.target_status()) {
+            Some(desired.action())
+        } else if desired == GuestState::Running
+            && matches!(current.qmpstatus.as_deref(), Some("paused" | "suspended"))
+        {
+            Some("resume")
+        } else {
+            None
+        };
+        let status = match action {
+            Some(action) => {
+                let upid = self
+                    .qemu_action(node, vmid, action, &PveParams::new())
+                    .await?;
+                self.wait_for_task_with_options(node, &upid, &options.wait)
+                    .await?
+            }
+            None => TaskStatus::completed_without_task(node),
+        };
+        if options.wait_for_agent && desired == GuestState::Running {
+            let timeout = match options.wait.timeout {
+                Some(timeout) => timeout.saturating_sub(started.elapsed()),
+                None => options.agent_timeout,
+            };
+            self.qemu_wait_for_agent(node, vmid, timeout).await?;
+        }
+        Ok(status)
+    }
+
+    pub async fn qemu_agent_ping(&self, node: &str, vmid: u32) -> Result<(), PveError> {
+        let path = format!("/nodes/{}/qemu/{}/agent/ping", enc(node), vmid);
+        let _: Value = self.send(Method::POST, &path, None, None).await?;
+        Ok(())
+    }
+
+    /// Discards unused blocks in every guest filesystem that supports it.
+    pub async fn qemu_agent_fstrim(
+        &self,
+        node: &str,
+        vmid: u32,
+    ) -> Result<AgentFstrimResult, PveError> {
+        let path = format!("/nodes/{}/qemu/{}/agent/fstrim", enc(node), vmid);
+        let reply: AgentResult<AgentFstrimResult> =
+            self.send(Method::POST, &path, None, None).await?;
+        Ok(reply.result)
+    }
+
+    /// Freezes guest filesystems and returns how many were frozen. Writes inside the guest
+    /// block until `qemu_agent_fsfreeze_thaw`, so always pair the two.
+    pub async fn qemu_agent_fsfreeze_freeze(&self, node: &str, vmid: u32) -> Result<u32, PveError> {
+        let path = format!("/nodes/{}/qemu/{}/agent/fsfreeze-freeze", enc(node), vmid);
+        let reply: AgentResult<u32> = self.send(Method::POST, &path, None, None).await?;
+        Ok(reply.result)
+    }
+
+    /// Thaws frozen guest filesystems and returns how many were thawed.
+    pub async fn qemu_agent_fsfreeze_thaw(&self, node: &str, vmid: u32) -> Result<u32, PveError> {
+        let path = format!("/nodes/{}/qemu/{}/agent/fsfreeze-thaw", enc(node), vmid);
+        let reply: AgentResult<u32> = self.send(Method::POST, &path, None, None).await?;
+        Ok(reply.result)
+    }
+
+    /// `frozen` or `thawed`.
+    pub async fn qemu_agent_fsfreeze_status(
+        &self,
+        node: &str,
+        vmid: u32,
+    ) -> Result<String, PveError> {
+        let path = format!("/nodes/{}/qemu/{}/agent/fsfreeze-status", enc(node), vmid);
+        let reply: AgentResult<String> = self.send(Method::POST, &path, None, None).await?;
+        Ok(reply.result)
+    }
+
+    /// Pings the guest agent until it answers. PVE reports an agent that is not up yet (or
+    /// a VM still booting) as a 500, which is retried. A VM that is not running or has no
+    /// agent configured fails at once, as do other errors.
+    pub async fn qemu_wait_for_agent(
+        &self,
+        node: &str,
+        vmid: u32,
+        timeout: Duration,
+    ) -> Result<(), PveError> {
+        let started = Instant::now();
+        loop {
+            match self.qemu_agent_ping(node, vmid).await {
+                Ok(()) => return Ok(()),
+                Err(PveError::ApiStatus { status: 500, body })
+                    if !agent_never_answers(vmid, &body) => {}
+                Err(err) => return Err(err),
+            }
+            if started.elapsed() + AGENT_POLL_INTERVAL > timeout {
+                return Err(PveError::AgentTimeout {
+                    vmid,
+                    timeout_secs: timeout.as_secs(),
+                });
+            }
+            sleep(AGENT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Adds the VM to `pool`, moving it out of any other pool, or removes it from its
+    /// pool when `pool` is `None`.
+    pub async fn qemu_set_pool(
+        &self,
+        node: &str,
+        vmid: u32,
+        pool: Option<&str>,
+    ) -> Result<(), PveError> {
+        self.guest_set_pool(node, "qemu", vmid, pool).await
+    }
+
     pub async fn qemu_start(
         &self,
         node: &str,
@@ -138,6 +545,16 @@ impl PveClient {
         self.qemu_start(node, vmid, &params).await
     }
 
+    /// Like `qemu_start_with`, with the returned UPID parsed.
+    pub async fn qemu_start_upid(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuActionRequest,
+    ) -> Result<Upid, PveError> {
+        Upid::parse(&self.qemu_start_with(node, vmid, request).await?)
+    }
+
     pub async fn qemu_shutdown(
         &self,
         node: &str,
@@ -157,6 +574,15 @@ impl PveClient {
         self.qemu_shutdown(node, vmid, &params).await
     }
 
+    pub async fn qemu_shutdown_upid(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuActionRequest,
+    ) -> Result<Upid, PveError> {
+        Upid::parse(&self.qemu_shutdown_with(node, vmid, request).await?)
+    }
+
     pub async fn qemu_stop(
         &self,
         node: &str,
@@ -176,6 +602,15 @@ impl PveClient {
         self.qemu_stop(node, vmid, &params).await
     }
 
+    pub async fn qemu_stop_upid(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuActionRequest,
+    ) -> Result<Upid, PveError> {
+        Upid::parse(&self.qemu_stop_with(node, vmid, request).await?)
+    }
+
     pub async fn qemu_reboot(
         &self,
         node: &str,
@@ -195,6 +630,15 @@ impl PveClient {
         self.qemu_reboot(node, vmid, &params).await
     }
 
+    pub async fn qemu_reboot_upid(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuActionRequest,
+    ) -> Result<Upid, PveError> {
+        Upid::parse(&self.qemu_reboot_with(node, vmid, request).await?)
+    }
+
     pub async fn qemu_suspend(
         &self,
         node: &str,
@@ -293,6 +737,60 @@ impl PveClient {
             .await
     }
 
+    pub async fn qemu_snapshot_delete(
+        &self,
+        node: &str,
+        vmid: u32,
+        snapname: &str,
+        force: Option<bool>,
+    ) -> Result<String, PveError> {
+        let mut query = PveParams::new();
+        if let Some(force) = force {
+            query.insert_bool("force", force);
+        }
+        let path = format!(
+            "/nodes/{}/qemu/{}/snapshot/{}",
+            enc(node),
+            vmid,
+            enc(snapname)
+        );
+        self.send(Method::DELETE, &path, Some(&query), None).await
+    }
+
+    pub async fn qemu_snapshot_config(
+        &self,
+        node: &str,
+        vmid: u32,
+        snapname: &str,
+    ) -> Result<Value, PveError> {
+        let path = format!(
+            "/nodes/{}/qemu/{}/snapshot/{}/config",
+            enc(node),
+            vmid,
+            enc(snapname)
+        );
+        self.send(Method::GET, &path, None, None).await
+    }
+
+    pub async fn qemu_snapshot_update_description(
+        &self,
+        node: &str,
+        vmid: u32,
+        snapname: &str,
+        description: &str,
+    ) -> Result<(), PveError> {
+        let mut body = PveParams::new();
+        body.insert("description", description);
+        let path = format!(
+            "/nodes/{}/qemu/{}/snapshot/{}/config",
+            enc(node),
+            vmid,
+            enc(snapname)
+        );
+        let _: Value = self.send(Method::PUT, &path, None, Some(&body)).await?;
+        Ok(())
+    }
+
     pub async fn qemu_clone(
         &self,
         node: &str,
@@ -317,6 +815,19 @@ impl PveClient {
         self.send(Method::POST, &path, None, Some(&body)).await
     }
 
+    /// Checks whether `vmid` can be migrated, optionally to a specific `target`.
+    pub async fn qemu_migrate_preconditions(
+        &self,
+        node: &str,
+        vmid: u32,
+        target: Option<&str>,
+    ) -> Result<MigratePreconditions, PveError> {
+        let mut query = PveParams::new();
+        query.insert_opt("target", target);
+        let path = format!("/nodes/{}/qemu/{}/migrate", enc(node), vmid);
+        self.send(Method::GET, &path, Some(&query), None).await
+    }
+
     pub async fn qemu_migrate(
         &self,
         node: &str,
@@ -340,6 +851,75 @@ impl PveClient {
         let path = format!("/nodes/{}/qemu/{}/migrate", enc(node), vmid);
         self.send(Method::POST, &path, None, Some(&body)).await
     }
+
+    /// Starts a migration and follows its task log, calling `on_progress` for every
+    /// recognized transfer line until the task finishes. The log is polled every
+    /// `wait.poll_interval`; past `wait.timeout` this fails with `TaskTimeout` while the
+    /// migration keeps running on PVE.
+    pub async fn qemu_migrate_and_track<F>(
+        &self,
+        node: &str,
+        vmid: u32,
+        request: &requests::QemuMigrateRequest,
+        wait: &requests::WaitTaskOptions,
+        mut on_progress: F,
+    ) -> Result<TaskStatus, PveError>
+    where
+        F: FnMut(TransferProgress),
+    {
+        let upid = self.qemu_migrate_with(node, vmid, request).await?;
+        self.follow_task(node, &upid, wait, |line| {
+            if let Some(progress) = parse_transfer_progress(line) {
+                on_progress(progress);
+            }
+        })
+        .await
+    }
+
+    /// Clone -> wait -> configure -> (start -> wait). Configure/start run on `clone.target`
+    /// when set; failures are wrapped in `PveError::ProvisionFailed` with the phase name.
+    pub async fn qemu_provision_from_template(
+        &self,
+        node: &str,
+        template_vmid: u32,
+        request: &QemuProvisionRequest,
+    ) -> Result<QemuProvisionResult, PveError> {
+        let vmid = request.clone.newid;
+        let target = request.clone.target.as_deref().unwrap_or(node);
+
+        let upid = self
+            .qemu_clone_with(node, template_vmid, &request.clone)
+            .await
+            .map_err(|err| provision_error("clone", err))?;
+        let mut status = self
+            .wait_for_task_with_options(node, &upid, &request.wait)
+            .await
+            .map_err(|err| provision_error("clone", err))?;
+
+        if let Some(config) = &request.config {
+            self.qemu_set_config_sync_with(target, vmid, config)
+                .await
+                .map_err(|err| provision_error("configure", err))?;
+        }
+
+        if request.start {
+            let upid = self
+                .qemu_start(target, vmid, &PveParams::new())
+                .await
+                .map_err(|err| provision_error("start", err))?;
+            status = self
+                .wait_for_task_with_options(target, &upid, &request.wait)
+                .await
+                .map_err(|err| provision_error("start", err))?;
+        }
+
+        Ok(QemuProvisionResult {
+            vmid,
+            node: target.to_string(),
+            status,
+        })
+    }
+
     async fn qemu_action(
         &self,
         node: &str,
@@ -351,3 +931,17 @@ impl PveClient {
         self.send(Method::POST, &path, None, Some(params)).await
     }
 }
+
+fn provision_error(phase: &str, source: PveError) -> PveError {
+    PveError::ProvisionFailed {
+        phase: phase.to_string(),
+        source: Box::new(source),
+    }
+}
+
+/// 500s from the agent ping that waiting cannot fix, unlike "QEMU guest agent is not
+/// running" while the guest boots.
+fn agent_never_answers(vmid: u32, body: &str) -> bool {
+    body.contains("No QEMU guest agent configured")
+        || body.contains(&format!("VM {vmid} is not running"))
+}
@@ -1,5 +1,9 @@
+use std::path::Path;
+
+use futures_util::StreamExt;
 use reqwest::Method;
 use serde_json::Value;
+use tokio::io::AsyncWriteExt;
 
 use crate::client::PveClient;
 use crate::error::PveError;
@@ -35,4 +39,65 @@ impl PveClient {
     ) -> Result<Value, PveError> {
         self.raw_json(Method::DELETE, path, query, None).await
     }
+
+    /// Returns the response body as-is, without the `{"data": ...}` envelope, for endpoints
+    /// serving files or plain text. Non-2xx statuses still map to errors as for JSON calls.
+    pub async fn raw_bytes(
+        &self,
+        method: Method,
+        path: &str,
+        query: Option<&PveParams>,
+    ) -> Result<Vec<u8>, PveError> {
+        self.send_bytes(method, path, query).await
+    }
+
+    /// `raw_bytes` for bodies that must be UTF-8.
+    pub async fn raw_text(
+        &self,
+        method: Method,
+        path: &str,
+        query: Option<&PveParams>,
+    ) -> Result<String, PveError> {
+        let bytes = self.send_bytes(method, path, query).await?;
+        String::from_utf8(bytes).map_err(|err| {
+            PveError::Decode(serde::de::Error::custom(format!(
+                "response body is not utf-8: {err}"
+            )))
+        })
+    }
+
+    /// Streams a `GET` response body into `dest` chunk by chunk, calling `progress` with the
+    /// cumulative byte count after each write. Returns the total size. A partially written
+    /// file is removed if the transfer fails.
+    pub async fn raw_download(
+        &self,
+        path: &str,
+        query: Option<&PveParams>,
+        dest: impl AsRef<Path>,
+        mut progress: impl FnMut(u64),
+    ) -> Result<u64, PveError> {
+        let dest = dest.as_ref();
+        let response = self.send_raw(Method::GET, path, query).await?;
+        let mut file = tokio::fs::File::create(dest).await?;
+
+        let result = async {
+            let mut written = 0u64;
+            let mut body = response.bytes_stream();
+            while let Some(chunk) = body.next().await {
+                let chunk = chunk?;
+                file.write_all(&chunk).await?;
+                written += chunk.len() as u64;
+                progress(written);
+            }
+            file.flush().await?;
+            Ok(written)
+        }
+        .await;
+
+        if result.is_err() {
+            drop(file);
+            let _ = tokio::fs::remove_file(dest).await;
+        }
+        result
+    }
 }
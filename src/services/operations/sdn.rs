@@ -0,0 +1,22 @@
+use reqwest::Method;
+
+use crate::client::PveClient;
+use crate::error::PveError;
+use crate::types::sdn::{SdnVnet, SdnZone};
+
+impl PveClient {
+    pub async fn sdn_zones(&self) -> Result<Vec<SdnZone>, PveError> {
+        self.send(Method::GET, "/cluster/sdn/zones", None, None)
+            .await
+    }
+
+    pub async fn sdn_vnets(&self) -> Result<Vec<SdnVnet>, PveError> {
+        self.send(Method::GET, "/cluster/sdn/vnets", None, None)
+            .await
+    }
+
+    /// Commits pending SDN changes cluster-wide and returns the reload task UPID.
+    pub async fn sdn_apply(&self) -> Result<String, PveError> {
+        self.send(Method::PUT, "/cluster/sdn", None, None).await
+    }
+}
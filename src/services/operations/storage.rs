@@ -1,15 +1,19 @@
 use std::path::Path;
 
+use futures_util::{Stream, StreamExt};
 use reqwest::{Method, multipart};
+use serde_json::Value;
 use tokio::fs::File;
 use tokio_util::io::ReaderStream;
 
 use crate::client::PveClient;
+use crate::core::stream::data_array_stream;
 use crate::core::transport::enc;
 use crate::error::PveError;
 use crate::models::{NodeStorageStatus, StorageContentItem, StorageIndexItem};
 use crate::params::PveParams;
 use crate::requests;
+use crate::types::backup::BackupFile;
 
 impl PveClient {
     pub async fn storage_index(
@@ -55,10 +59,42 @@ impl PveClient {
         storage: &str,
         query: &requests::StorageContentQuery,
     ) -> Result<Vec<StorageContentItem>, PveError> {
+        query.validate()?;
         let params = query.to_params();
         self.storage_content(node, storage, &params).await
     }
 
+    pub async fn storage_content_filtered<F>(
+        &self,
+        node: &str,
+        storage: &str,
+        query: &requests::StorageContentQuery,
+        predicate: F,
+    ) -> Result<Vec<StorageContentItem>, PveError>
+    where
+        F: Fn(&StorageContentItem) -> bool,
+    {
+        let items = self.storage_content_with(node, storage, query).await?;
+        Ok(items.into_iter().filter(|item| predicate(item)).collect())
+    }
+
+    /// Streams storage content, decoding items as they arrive instead of buffering the
+    /// whole listing. Dropping the stream early stops reading the response.
+    pub fn storage_content_stream<'a>(
+        &'a self,
+        node: &str,
+        storage: &str,
+        query: &requests::StorageContentQuery,
+    ) -> impl Stream<Item = Result<StorageContentItem, PveError>> + 'a {
+        let path = format!("/nodes/{}/storage/{}/content", enc(node), enc(storage));
+        let query = query.clone();
+        data_array_stream(path.clone(), async move {
+            query.validate()?;
+            let params = query.to_params();
+            self.send_raw_json(Method::GET, &path, Some(&params)).await
+        })
+    }
+
     pub async fn storage_allocate_disk(
         &self,
         node: &str,
@@ -96,6 +132,32 @@ impl PveClient {
         file_path: impl AsRef<Path>,
         checksum: Option<&str>,
         checksum_algorithm: Option<&str>,
+    ) -> Result<String, PveError> {
+        self.storage_upload_file_with_progress(
+            node,
+            storage,
+            content,
+            file_path,
+            checksum,
+            checksum_algorithm,
+            |_, _| {},
+        )
+        .await
+    }
+
+    /// Same as `storage_upload_file`, calling `on_progress(bytes_sent, total)` as each chunk
+    /// of the file is handed to the HTTP body. The last call reports `total` once the file
+    /// has been read completely; the server may still be processing it.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn storage_upload_file_with_progress(
+        &self,
+        node: &str,
+        storage: &str,
+        content: &str,
+        file_path: impl AsRef<Path>,
+        checksum: Option<&str>,
+        checksum_algorithm: Option<&str>,
+        mut on_progress: impl FnMut(u64, u64) + Send + Sync + 'static,
     ) -> Result<String, PveError> {
         let file_path = file_path.as_ref();
         let file_name = file_path
@@ -105,7 +167,13 @@ impl PveClient {
 
         let file_size = tokio::fs::metadata(file_path).await?.len();
         let file = File::open(file_path).await?;
-        let stream = ReaderStream::new(file);
+        let mut sent = 0u64;
+        let stream = ReaderStream::new(file).inspect(move |chunk| {
+            if let Ok(chunk) = chunk {
+                sent += chunk.len() as u64;
+                on_progress(sent, file_size);
+            }
+        });
         let body = reqwest::Body::wrap_stream(stream);
         let file_part =
             multipart::Part::stream_with_length(body, file_size).file_name(file_name.to_string());
@@ -151,6 +219,35 @@ impl PveClient {
         .await
     }
 
+    pub async fn storage_create_with(
+        &self,
+        request: &requests::StorageCreateRequest,
+    ) -> Result<(), PveError> {
+        let params = request.to_params();
+        let _: Value = self
+            .send(Method::POST, "/storage", None, Some(&params))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn storage_update_with(
+        &self,
+        storage: &str,
+        request: &requests::StorageUpdateRequest,
+    ) -> Result<(), PveError> {
+        let params = request.to_params();
+        let path = format!("/storage/{}", enc(storage));
+        let _: Value = self.send(Method::PUT, &path, None, Some(&params)).await?;
+        Ok(())
+    }
+
+    /// Removes the storage definition; data on the backing storage is left alone.
+    pub async fn storage_delete(&self, storage: &str) -> Result<(), PveError> {
+        let path = format!("/storage/{}", enc(storage));
+        let _: Value = self.send(Method::DELETE, &path, None, None).await?;
+        Ok(())
+    }
+
     pub async fn storage_delete_volume(
         &self,
         node: &str,
@@ -195,4 +292,82 @@ impl PveClient {
         let params = request.to_params();
         self.vzdump_backup(node, &params).await
     }
+
+    pub async fn list_backups(
+        &self,
+        node: &str,
+        storage: &str,
+        vmid: Option<u32>,
+    ) -> Result<Vec<BackupFile>, PveError> {
+        let items = self
+            .storage_content_of_type(node, storage, "backup", vmid)
+            .await?;
+        Ok(items
+            .into_iter()
+            .map(BackupFile::from_content_item)
+            .collect())
+    }
+
+    pub async fn list_ct_templates(
+        &self,
+        node: &str,
+        storage: &str,
+    ) -> Result<Vec<StorageContentItem>, PveError> {
+        self.storage_content_of_type(node, storage, "vztmpl", None)
+            .await
+    }
+
+    /// Lists ISO images on `storage`.
+    ///
+    /// Volids have the form `<storage>:iso/<file>`, which can be used directly as a cdrom
+    /// drive, e.g. `ide2=local:iso/debian-12.iso,media=cdrom`.
+    pub async fn list_isos(
+        &self,
+        node: &str,
+        storage: &str,
+    ) -> Result<Vec<StorageContentItem>, PveError> {
+        self.storage_content_of_type(node, storage, "iso", None)
+            .await
+    }
+
+    pub async fn backup_protect(
+        &self,
+        node: &str,
+        storage: &str,
+        volume: &str,
+        protected: bool,
+    ) -> Result<(), PveError> {
+        let path = format!(
+            "/nodes/{}/storage/{}/content/{}",
+            enc(node),
+            enc(storage),
+            enc(volume)
+        );
+        let body = PveParams::new().with_bool("protected", protected);
+        let _: Value = self.send(Method::PUT, &path, None, Some(&body)).await?;
+        Ok(())
+    }
+
+    async fn storage_content_of_type(
+        &self,
+        node: &str,
+        storage: &str,
+        content: &str,
+        vmid: Option<u32>,
+    ) -> Result<Vec<StorageContentItem>, PveError> {
+        let query = requests::StorageContentQuery {
+            content: Some(content.to_string()),
+            vmid,
+        };
+        let items = self.storage_content_with(node, storage, &query).await?;
+        Ok(items
+            .into_iter()
+            .filter(|item| {
+                item.extra
+                    .get("content")
+                    .and_then(Value::as_str)
+                    .is_none_or(|item_content| item_content == content)
+            })
+            .collect())
+    }
 }
@@ -3,9 +3,15 @@ use reqwest::Method;
 use crate::client::PveClient;
 use crate::error::PveError;
 use crate::models::VersionInfo;
+use crate::types::common::ApiIndexItem;
 
 impl PveClient {
     pub async fn version(&self) -> Result<VersionInfo, PveError> {
         self.send(Method::GET, "/version", None, None).await
     }
+
+    /// Lists the child endpoints of an API namespace such as `/` or `/nodes/pve1`.
+    pub async fn api_index(&self, path: &str) -> Result<Vec<ApiIndexItem>, PveError> {
+        self.send(Method::GET, path, None, None).await
+    }
 }
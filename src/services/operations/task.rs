@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use reqwest::Method;
+use serde_json::Value;
 use tokio::time::{Instant, sleep};
 
 use crate::client::PveClient;
@@ -9,6 +10,10 @@ use crate::error::PveError;
 use crate::models::{TaskLogLine, TaskStatus};
 use crate::params::PveParams;
 use crate::requests;
+use crate::types::task::Upid;
+
+/// Lines requested per `task_log` call in `task_output`.
+const TASK_LOG_PAGE: u64 = 500;
 
 impl PveClient {
     pub async fn task_status(&self, node: &str, upid: &str) -> Result<TaskStatus, PveError> {
@@ -16,6 +21,16 @@ impl PveClient {
         self.send(Method::GET, &path, None, None).await
     }
 
+    /// Asks PVE to stop a running task.
+    ///
+    /// Not every task type can be interrupted; PVE answers those (and already finished tasks)
+    /// with an `ApiStatus` error.
+    pub async fn task_stop(&self, node: &str, upid: &str) -> Result<(), PveError> {
+        let path = format!("/nodes/{}/tasks/{}", enc(node), enc(upid));
+        let _: Value = self.send(Method::DELETE, &path, None, None).await?;
+        Ok(())
+    }
+
     pub async fn task_log(
         &self,
         node: &str,
@@ -42,29 +57,39 @@ impl PveClient {
         self.send(Method::GET, &path, Some(&params), None).await
     }
 
+    /// Whole task log as one string, one line per entry, fetched page by page.
+    pub async fn task_output(&self, node: &str, upid: &str) -> Result<String, PveError> {
+        let mut lines = Vec::new();
+        loop {
+            let page = self
+                .task_log(node, upid, Some(lines.len() as u64), Some(TASK_LOG_PAGE))
+                .await?;
+            let done = (page.len() as u64) < TASK_LOG_PAGE;
+            lines.extend(page.into_iter().map(|line| line.t));
+            if done {
+                break;
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+
     pub async fn wait_for_task(
         &self,
         node: &str,
-        upid: &str,
+        upid: impl AsRef<str>,
         poll_interval: Duration,
         timeout: Option<Duration>,
     ) -> Result<TaskStatus, PveError> {
+        let upid = upid.as_ref();
+        if let Some(status) = self.dry_run_task(upid) {
+            return Ok(status);
+        }
         let started = Instant::now();
 
         loop {
             let status = self.task_status(node, upid).await?;
             if status.status == "stopped" {
-                if status.exitstatus.as_deref() == Some("OK") {
-                    return Ok(status);
-                }
-
-                return Err(PveError::TaskFailed {
-                    upid: upid.to_string(),
-                    exitstatus: status
-                        .exitstatus
-                        .clone()
-                        .unwrap_or_else(|| "UNKNOWN".to_string()),
-                });
+                return finished_task(upid, status);
             }
 
             if let Some(timeout) = timeout
@@ -83,10 +108,97 @@ impl PveClient {
     pub async fn wait_for_task_with_options(
         &self,
         node: &str,
-        upid: &str,
+        upid: impl AsRef<str>,
         options: &requests::WaitTaskOptions,
     ) -> Result<TaskStatus, PveError> {
         self.wait_for_task(node, upid, options.poll_interval, options.timeout)
             .await
     }
+
+    /// Same as `wait_for_task_with_options`, but gives up with `PveError::Cancelled` as soon
+    /// as `token` is cancelled, including while a status request is in flight. The task
+    /// itself keeps running on PVE.
+    #[cfg(feature = "cancellation")]
+    pub async fn wait_for_task_cancellable(
+        &self,
+        node: &str,
+        upid: impl AsRef<str>,
+        options: &requests::WaitTaskOptions,
+        token: &tokio_util::sync::CancellationToken,
+    ) -> Result<TaskStatus, PveError> {
+        tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(PveError::Cancelled),
+            result = self.wait_for_task_with_options(node, upid, options) => result,
+        }
+    }
+
+    /// Polls the task like `wait_for_task_with_options`, handing each new log line to
+    /// `on_line` as it appears. The log is drained once more after the task stops.
+    pub(crate) async fn follow_task<F>(
+        &self,
+        node: &str,
+        upid: &str,
+        options: &requests::WaitTaskOptions,
+        mut on_line: F,
+    ) -> Result<TaskStatus, PveError>
+    where
+        F: FnMut(&str),
+    {
+        if let Some(status) = self.dry_run_task(upid) {
+            return Ok(status);
+        }
+        let started = Instant::now();
+        let mut seen = 0u64;
+        loop {
+            let status = self.task_status(node, upid).await?;
+            loop {
+                let page = self
+                    .task_log(node, upid, Some(seen), Some(TASK_LOG_PAGE))
+                    .await?;
+                seen += page.len() as u64;
+                for line in &page {
+                    on_line(&line.t);
+                }
+                if (page.len() as u64) < TASK_LOG_PAGE {
+                    break;
+                }
+            }
+            if status.status == "stopped" {
+                return finished_task(upid, status);
+            }
+
+            if let Some(timeout) = options.timeout
+                && started.elapsed() > timeout
+            {
+                return Err(PveError::TaskTimeout {
+                    upid: upid.to_string(),
+                    timeout_secs: timeout.as_secs(),
+                });
+            }
+
+            sleep(options.poll_interval).await;
+        }
+    }
+
+    /// Same as `wait_for_task_with_options`, using the node embedded in the UPID.
+    pub async fn wait_for_upid(
+        &self,
+        upid: &Upid,
+        options: &requests::WaitTaskOptions,
+    ) -> Result<TaskStatus, PveError> {
+        self.wait_for_task_with_options(upid.node(), upid, options)
+            .await
+    }
+}
+
+/// Maps a stopped task to `Ok` or `PveError::TaskFailed` by its exit status.
+fn finished_task(upid: &str, status: TaskStatus) -> Result<TaskStatus, PveError> {
+    if status.exitstatus.as_deref() == Some("OK") {
+        return Ok(status);
+    }
+    Err(PveError::TaskFailed {
+        upid: upid.to_string(),
+        exitstatus: status.exitstatus.unwrap_or_else(|| "UNKNOWN".to_string()),
+    })
 }
@@ -1,11 +1,13 @@
 //! Access/auth related types.
 
 use std::collections::HashMap;
+use std::fmt;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::params::PveParams;
+use crate::types::serde_helpers::opt_bool_int;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TicketInfo {
@@ -81,6 +83,7 @@ impl TicketRequest {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AccessUser {
     pub userid: String,
+    #[serde(default, deserialize_with = "opt_bool_int")]
     pub enable: Option<u8>,
     pub expire: Option<u64>,
     pub firstname: Option<String>,
@@ -91,6 +94,13 @@ pub struct AccessUser {
     pub extra: HashMap<String, Value>,
 }
 
+impl AccessUser {
+    /// Users are enabled unless `enable` is explicitly `0`.
+    pub fn is_enabled(&self) -> bool {
+        self.enable != Some(0)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AccessGroup {
     pub groupid: String,
@@ -112,22 +122,122 @@ pub struct AccessAcl {
     pub path: Option<String>,
     pub ugid: Option<String>,
     pub roleid: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
     pub propagate: Option<u8>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
+impl AccessAcl {
+    /// ACLs propagate to sub-paths unless `propagate` is explicitly `0`.
+    pub fn propagates(&self) -> bool {
+        self.propagate != Some(0)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AccessUserToken {
     pub tokenid: String,
     pub comment: Option<String>,
     pub expire: Option<u64>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
     pub enable: Option<u8>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
     pub privsep: Option<u8>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
+impl AccessUserToken {
+    pub fn is_enabled(&self) -> bool {
+        self.enable != Some(0)
+    }
+
+    /// Privilege separation defaults to on: the token only gets its own ACLs.
+    pub fn has_privsep(&self) -> bool {
+        self.privsep != Some(0)
+    }
+}
+
+/// Authentication realm backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RealmType {
+    Pam,
+    Pve,
+    Ldap,
+    Ad,
+    Openid,
+    /// Backend type not known to this SDK version.
+    #[serde(other)]
+    Unknown,
+}
+
+impl RealmType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pam => "pam",
+            Self::Pve => "pve",
+            Self::Ldap => "ldap",
+            Self::Ad => "ad",
+            Self::Openid => "openid",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for RealmType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Authentication realm from `/access/domains`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AccessDomain {
+    pub realm: String,
+    #[serde(rename = "type")]
+    pub type_: RealmType,
+    pub comment: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub default: Option<u8>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl AccessDomain {
+    pub fn is_default(&self) -> bool {
+        self.default == Some(1)
+    }
+}
+
+/// Second-factor kind registered for a user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TfaType {
+    Totp,
+    U2f,
+    Webauthn,
+    Recovery,
+    Yubico,
+    /// Factor type not known to this SDK version.
+    #[serde(other)]
+    Unknown,
+}
+
+/// Entry of `/access/tfa/{userid}`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TfaEntry {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub type_: TfaType,
+    pub description: Option<String>,
+    pub created: Option<u64>,
+    pub enable: Option<bool>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct AccessAclQuery {
     pub path: Option<String>,
@@ -376,11 +486,142 @@ impl AccessDeleteAclRequest {
     }
 }
 
+/// Realm creation request. Backend-specific keys not modelled here go in `extra`.
+#[derive(Debug, Clone)]
+pub struct DomainCreateRequest {
+    pub realm: String,
+    pub type_: RealmType,
+    pub comment: Option<String>,
+    pub default: Option<bool>,
+    pub server1: Option<String>,
+    pub server2: Option<String>,
+    pub port: Option<u16>,
+    pub base_dn: Option<String>,
+    pub user_attr: Option<String>,
+    pub domain: Option<String>,
+    pub issuer_url: Option<String>,
+    pub client_id: Option<String>,
+    pub client_key: Option<String>,
+    pub extra: PveParams,
+}
+
+impl DomainCreateRequest {
+    pub fn new(realm: impl Into<String>, type_: RealmType) -> Self {
+        Self {
+            realm: realm.into(),
+            type_,
+            comment: None,
+            default: None,
+            server1: None,
+            server2: None,
+            port: None,
+            base_dn: None,
+            user_attr: None,
+            domain: None,
+            issuer_url: None,
+            client_id: None,
+            client_key: None,
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("realm", self.realm.clone());
+        params.insert("type", self.type_.as_str());
+        params.insert_opt("comment", self.comment.clone());
+        if let Some(default) = self.default {
+            params.insert_bool("default", default);
+        }
+        params.insert_opt("server1", self.server1.clone());
+        params.insert_opt("server2", self.server2.clone());
+        params.insert_opt("port", self.port.map(|v| v.to_string()));
+        params.insert_opt("base_dn", self.base_dn.clone());
+        params.insert_opt("user_attr", self.user_attr.clone());
+        params.insert_opt("domain", self.domain.clone());
+        params.insert_opt("issuer-url", self.issuer_url.clone());
+        params.insert_opt("client-id", self.client_id.clone());
+        params.insert_opt("client-key", self.client_key.clone());
+        params.extend(&self.extra);
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DomainUpdateRequest {
+    pub comment: Option<String>,
+    pub default: Option<bool>,
+    pub server1: Option<String>,
+    pub server2: Option<String>,
+    pub port: Option<u16>,
+    pub base_dn: Option<String>,
+    pub user_attr: Option<String>,
+    pub domain: Option<String>,
+    pub issuer_url: Option<String>,
+    pub client_id: Option<String>,
+    pub client_key: Option<String>,
+    pub delete: Option<String>,
+    pub digest: Option<String>,
+    pub extra: PveParams,
+}
+
+impl DomainUpdateRequest {
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert_opt("comment", self.comment.clone());
+        if let Some(default) = self.default {
+            params.insert_bool("default", default);
+        }
+        params.insert_opt("server1", self.server1.clone());
+        params.insert_opt("server2", self.server2.clone());
+        params.insert_opt("port", self.port.map(|v| v.to_string()));
+        params.insert_opt("base_dn", self.base_dn.clone());
+        params.insert_opt("user_attr", self.user_attr.clone());
+        params.insert_opt("domain", self.domain.clone());
+        params.insert_opt("issuer-url", self.issuer_url.clone());
+        params.insert_opt("client-id", self.client_id.clone());
+        params.insert_opt("client-key", self.client_key.clone());
+        params.insert_opt("delete", self.delete.clone());
+        params.insert_opt("digest", self.digest.clone());
+        params.extend(&self.extra);
+        params
+    }
+}
+
+/// Removal of a second factor. PVE asks for the caller's password as confirmation;
+/// it is kept out of the `Debug` output.
+#[derive(Clone, Default)]
+pub struct TfaDeleteRequest {
+    pub password: Option<String>,
+}
+
+impl TfaDeleteRequest {
+    pub fn password(mut self, password: impl Into<String>) -> Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert_opt("password", self.password.clone());
+        params
+    }
+}
+
+impl fmt::Debug for TfaDeleteRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TfaDeleteRequest")
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
         AccessAclQuery, AccessCreateTokenRequest, AccessCreateUserRequest, AccessDeleteAclRequest,
-        AccessSetAclRequest, AccessUpdateTokenRequest, AccessUpdateUserRequest,
+        AccessDomain, AccessSetAclRequest, AccessUpdateTokenRequest, AccessUpdateUserRequest,
+        DomainCreateRequest, DomainUpdateRequest, RealmType, TfaDeleteRequest, TfaEntry, TfaType,
     };
 
     #[test]
@@ -471,4 +712,70 @@ mod tests {
         assert_eq!(params.get("users"), Some("dev@pve"));
         assert_eq!(params.get("delete"), Some("1"));
     }
+
+    #[test]
+    fn access_domain_decodes_known_and_unknown_types() {
+        let domains: Vec<AccessDomain> = serde_json::from_value(serde_json::json!([
+            {"realm": "pam", "type": "pam", "comment": "Linux PAM"},
+            {"realm": "corp", "type": "ldap", "default": 1},
+            {"realm": "future", "type": "saml"}
+        ]))
+        .expect("domains");
+        assert_eq!(domains[0].type_, RealmType::Pam);
+        assert!(!domains[0].is_default());
+        assert_eq!(domains[1].type_, RealmType::Ldap);
+        assert!(domains[1].is_default());
+        assert_eq!(domains[2].type_, RealmType::Unknown);
+    }
+
+    #[test]
+    fn domain_create_maps_type_and_backend_keys() {
+        let mut req = DomainCreateRequest::new("corp", RealmType::Openid);
+        req.issuer_url = Some("https://idp.example".to_string());
+        req.client_id = Some("pve".to_string());
+        req.default = Some(true);
+        let params = req.to_params();
+        assert_eq!(params.get("realm"), Some("corp"));
+        assert_eq!(params.get("type"), Some("openid"));
+        assert_eq!(params.get("issuer-url"), Some("https://idp.example"));
+        assert_eq!(params.get("client-id"), Some("pve"));
+        assert_eq!(params.get("default"), Some("1"));
+    }
+
+    #[test]
+    fn domain_update_maps_optional_fields() {
+        let req = DomainUpdateRequest {
+            base_dn: Some("dc=example,dc=com".to_string()),
+            port: Some(636),
+            delete: Some("server2".to_string()),
+            ..Default::default()
+        };
+        let params = req.to_params();
+        assert_eq!(params.get("base_dn"), Some("dc=example,dc=com"));
+        assert_eq!(params.get("port"), Some("636"));
+        assert_eq!(params.get("delete"), Some("server2"));
+        assert_eq!(params.get("type"), None);
+    }
+
+    #[test]
+    fn tfa_entries_decode_types() {
+        let entries: Vec<TfaEntry> = serde_json::from_value(serde_json::json!([
+            {"id": "totp-1", "type": "totp", "description": "phone", "created": 1700000000, "enable": true},
+            {"id": "recovery", "type": "recovery", "created": 1700000001}
+        ]))
+        .expect("tfa entries");
+        assert_eq!(entries[0].type_, TfaType::Totp);
+        assert_eq!(entries[0].enable, Some(true));
+        assert_eq!(entries[1].type_, TfaType::Recovery);
+        assert_eq!(entries[1].enable, None);
+    }
+
+    #[test]
+    fn tfa_delete_redacts_password_in_debug() {
+        let req = TfaDeleteRequest::default().password("hunter2");
+        assert_eq!(req.to_params().get("password"), Some("hunter2"));
+        let debug = format!("{req:?}");
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains("<redacted>"));
+    }
 }
@@ -1,8 +1,15 @@
-//! Backup (`vzdump`) related request types.
+//! Backup (`vzdump`) and scheduled backup job types.
 
+use std::collections::HashMap;
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
 use crate::params::PveParams;
+use crate::types::common::Bandwidth;
+use crate::types::serde_helpers::opt_bool_int;
+use crate::types::storage::StorageContentItem;
 
 #[derive(Debug, Clone, Copy)]
 pub enum VzdumpMode {
@@ -85,10 +92,20 @@ pub struct VzdumpRequest {
     pub notes_template: Option<String>,
     pub remove: Option<bool>,
     pub stopwait: Option<u64>,
+    pub protected: Option<bool>,
+    pub fleecing: Option<String>,
+    pub performance: Option<String>,
+    /// KiB/s; see `bandwidth` for a unit-checked setter.
+    pub bwlimit: Option<u64>,
     pub extra: PveParams,
 }
 
 impl VzdumpRequest {
+    pub fn bandwidth(mut self, bandwidth: Bandwidth) -> Self {
+        self.bwlimit = Some(bandwidth.to_kib());
+        self
+    }
+
     pub fn to_params(&self) -> PveParams {
         let mut params = PveParams::new();
 
@@ -110,8 +127,393 @@ impl VzdumpRequest {
             params.insert_bool("remove", remove);
         }
         params.insert_opt("stopwait", self.stopwait.map(|v| v.to_string()));
+        if let Some(protected) = self.protected {
+            params.insert_bool("protected", protected);
+        }
+        params.insert_opt("fleecing", self.fleecing.clone());
+        params.insert_opt("performance", self.performance.clone());
+        params.insert_opt("bwlimit", self.bwlimit.map(|v| v.to_string()));
 
         params.extend(&self.extra);
         params
     }
 }
+
+/// Scheduled backup job from `/cluster/backup`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BackupJob {
+    pub id: String,
+    /// systemd calendar event, e.g. `sat 02:00`.
+    pub schedule: Option<String>,
+    pub storage: Option<String>,
+    pub mode: Option<String>,
+    /// Comma-separated vmids; empty when the job selects by `all` or `pool`.
+    pub vmid: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub all: Option<u8>,
+    pub pool: Option<String>,
+    pub exclude: Option<String>,
+    pub node: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub enabled: Option<u8>,
+    pub mailto: Option<String>,
+    pub mailnotification: Option<String>,
+    pub compress: Option<String>,
+    pub comment: Option<String>,
+    #[serde(rename = "next-run")]
+    pub next_run: Option<u64>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl BackupJob {
+    pub fn selects_all(&self) -> bool {
+        self.all == Some(1)
+    }
+
+    /// PVE treats a missing `enabled` as enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != Some(0)
+    }
+
+    pub fn vmids(&self) -> Vec<u32> {
+        self.vmid
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|v| v.trim().parse().ok())
+            .collect()
+    }
+}
+
+/// Guest a backup job would cover, from `/cluster/backup/{id}/included_volumes`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IncludedGuest {
+    /// The guest's vmid.
+    pub id: u32,
+    pub name: Option<String>,
+    /// `qemu` or `lxc`.
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    #[serde(rename = "children", default)]
+    pub volumes: Vec<IncludedVolume>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IncludedVolume {
+    /// Config key, e.g. `scsi0` or `rootfs`.
+    pub id: String,
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub included: Option<u8>,
+    /// Why the volume is included or skipped (e.g. `backup=0`).
+    pub reason: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl IncludedVolume {
+    pub fn is_included(&self) -> bool {
+        self.included == Some(1)
+    }
+}
+
+/// Job settings shared by `BackupJobRequest` and `BackupJobUpdate`.
+#[derive(Debug, Clone, Default)]
+pub struct BackupJobOptions {
+    pub storage: Option<String>,
+    pub mode: Option<VzdumpMode>,
+    pub vmid: Option<String>,
+    pub all: Option<bool>,
+    pub pool: Option<String>,
+    pub exclude: Option<String>,
+    pub node: Option<String>,
+    pub enabled: Option<bool>,
+    pub mailto: Option<String>,
+    pub mailnotification: Option<MailNotification>,
+    pub compress: Option<VzdumpCompress>,
+    pub comment: Option<String>,
+}
+
+impl BackupJobOptions {
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert_opt("storage", self.storage.clone());
+        params.insert_opt("mode", self.mode.map(|v| v.to_string()));
+        params.insert_opt("vmid", self.vmid.clone());
+        if let Some(all) = self.all {
+            params.insert_bool("all", all);
+        }
+        params.insert_opt("pool", self.pool.clone());
+        params.insert_opt("exclude", self.exclude.clone());
+        params.insert_opt("node", self.node.clone());
+        if let Some(enabled) = self.enabled {
+            params.insert_bool("enabled", enabled);
+        }
+        params.insert_opt("mailto", self.mailto.clone());
+        params.insert_opt(
+            "mailnotification",
+            self.mailnotification.map(|v| v.to_string()),
+        );
+        params.insert_opt("compress", self.compress.map(|v| v.to_string()));
+        params.insert_opt("comment", self.comment.clone());
+        params
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BackupJobRequest {
+    pub schedule: String,
+    /// Job id; PVE generates one when omitted.
+    pub id: Option<String>,
+    pub options: BackupJobOptions,
+    pub extra: PveParams,
+}
+
+impl BackupJobRequest {
+    pub fn new(schedule: impl Into<String>) -> Self {
+        Self {
+            schedule: schedule.into(),
+            id: None,
+            options: BackupJobOptions::default(),
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("schedule", self.schedule.clone());
+        params.insert_opt("id", self.id.clone());
+        params.extend(&self.options.to_params());
+        params.extend(&self.extra);
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BackupJobUpdate {
+    pub schedule: Option<String>,
+    pub options: BackupJobOptions,
+    pub delete: Option<String>,
+    /// Digest of the job config; the update fails if the job changed since.
+    pub digest: Option<String>,
+    pub extra: PveParams,
+}
+
+impl BackupJobUpdate {
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert_opt("schedule", self.schedule.clone());
+        params.extend(&self.options.to_params());
+        params.insert_opt("delete", self.delete.clone());
+        params.insert_opt("digest", self.digest.clone());
+        params.extend(&self.extra);
+        params
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BackupFile {
+    pub volid: String,
+    pub guest_type: Option<String>,
+    pub vmid: Option<u32>,
+    pub ctime: Option<u64>,
+    pub format: Option<String>,
+    pub notes: Option<String>,
+    pub size: Option<u64>,
+    pub protected: bool,
+}
+
+impl BackupFile {
+    /// Builds a backup entry from a `content=backup` storage item, falling back to the volid
+    /// for guest type and vmid when PVE omits them.
+    pub fn from_content_item(item: StorageContentItem) -> Self {
+        let parsed = parse_backup_volid(&item.volid);
+        let protected = item.extra.get("protected").is_some_and(|v| match v {
+            Value::Bool(b) => *b,
+            Value::Number(n) => n.as_u64() == Some(1),
+            Value::String(s) => s == "1",
+            _ => false,
+        });
+        Self {
+            guest_type: parsed.as_ref().map(|(guest_type, _)| guest_type.clone()),
+            vmid: item.vmid.or(parsed.map(|(_, vmid)| vmid)),
+            volid: item.volid,
+            ctime: item.ctime,
+            format: item.format,
+            notes: item.notes,
+            size: item.size,
+            protected,
+        }
+    }
+}
+
+/// Extracts `(guest_type, vmid)` from a backup volid.
+///
+/// Handles vzdump archives (`local:backup/vzdump-qemu-100-2024_01_15-10_23_45.vma.zst`) and
+/// PBS snapshots (`pbs:backup/vm/100/2024-01-15T10:23:45Z`, `ct/` for containers).
+pub fn parse_backup_volid(volid: &str) -> Option<(String, u32)> {
+    let (_, path) = volid.split_once(':')?;
+    let name = path.strip_prefix("backup/").unwrap_or(path);
+
+    if let Some(rest) = name
+        .rsplit('/')
+        .next()
+        .and_then(|f| f.strip_prefix("vzdump-"))
+    {
+        let mut parts = rest.splitn(3, '-');
+        let guest_type = parts.next()?;
+        let vmid = parts.next()?.parse().ok()?;
+        return Some((guest_type.to_string(), vmid));
+    }
+
+    let mut parts = name.split('/');
+    let guest_type = match parts.next()? {
+        "vm" => "qemu",
+        "ct" => "lxc",
+        _ => return None,
+    };
+    let vmid = parts.next()?.parse().ok()?;
+    Some((guest_type.to_string(), vmid))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::{
+        BackupFile, BackupJob, BackupJobOptions, BackupJobRequest, BackupJobUpdate, IncludedGuest,
+        VzdumpMode, VzdumpRequest, parse_backup_volid,
+    };
+    use crate::types::common::Bandwidth;
+    use crate::types::storage::StorageContentItem;
+
+    #[test]
+    fn vzdump_request_maps_pbs_tuning_params() {
+        let request = VzdumpRequest {
+            vmid: Some("100".to_string()),
+            protected: Some(true),
+            fleecing: Some("enabled=1,storage=local-lvm".to_string()),
+            performance: Some("max-workers=8".to_string()),
+            ..Default::default()
+        }
+        .bandwidth(Bandwidth::kbytes_per_sec(51_200));
+
+        let params = request.to_params();
+        assert_eq!(params.get("protected"), Some("1"));
+        assert_eq!(params.get("fleecing"), Some("enabled=1,storage=local-lvm"));
+        assert_eq!(params.get("performance"), Some("max-workers=8"));
+        assert_eq!(params.get("bwlimit"), Some("51200"));
+    }
+
+    #[test]
+    fn parse_backup_volid_handles_vzdump_archives() {
+        assert_eq!(
+            parse_backup_volid("local:backup/vzdump-qemu-100-2024_01_15-10_23_45.vma.zst"),
+            Some(("qemu".to_string(), 100))
+        );
+        assert_eq!(
+            parse_backup_volid("nfs:backup/vzdump-lxc-201-2024_01_15-10_23_45.tar.zst"),
+            Some(("lxc".to_string(), 201))
+        );
+    }
+
+    #[test]
+    fn parse_backup_volid_handles_pbs_snapshots() {
+        assert_eq!(
+            parse_backup_volid("pbs:backup/vm/100/2024-01-15T10:23:45Z"),
+            Some(("qemu".to_string(), 100))
+        );
+        assert_eq!(
+            parse_backup_volid("pbs:backup/ct/105/2024-01-15T10:23:45Z"),
+            Some(("lxc".to_string(), 105))
+        );
+        assert_eq!(parse_backup_volid("local:iso/debian.iso"), None);
+    }
+
+    #[test]
+    fn backup_file_reads_protected_flag_and_volid_fallbacks() {
+        let mut extra = HashMap::new();
+        extra.insert("protected".to_string(), json!(1));
+        let item = StorageContentItem {
+            volid: "local:backup/vzdump-qemu-100-2024_01_15-10_23_45.vma.zst".to_string(),
+            format: Some("vma.zst".to_string()),
+            size: Some(1024),
+            used: None,
+            vmid: None,
+            ctime: Some(1_705_314_225),
+            notes: Some("nightly".to_string()),
+            extra,
+        };
+
+        let backup = BackupFile::from_content_item(item);
+        assert_eq!(backup.vmid, Some(100));
+        assert_eq!(backup.guest_type.as_deref(), Some("qemu"));
+        assert!(backup.protected);
+        assert_eq!(backup.notes.as_deref(), Some("nightly"));
+    }
+
+    #[test]
+    fn backup_job_request_maps_selection() {
+        let mut request = BackupJobRequest::new("sat 02:00");
+        request.options.pool = Some("prod".to_string());
+        request.options.mode = Some(VzdumpMode::Snapshot);
+        request.options.enabled = Some(false);
+        let params = request.to_params();
+        assert_eq!(params.get("schedule"), Some("sat 02:00"));
+        assert_eq!(params.get("pool"), Some("prod"));
+        assert_eq!(params.get("mode"), Some("snapshot"));
+        assert_eq!(params.get("enabled"), Some("0"));
+        assert_eq!(params.get("id"), None);
+
+        let update = BackupJobUpdate {
+            options: BackupJobOptions {
+                enabled: Some(true),
+                ..Default::default()
+            },
+            delete: Some("pool".to_string()),
+            ..Default::default()
+        };
+        let params = update.to_params();
+        assert_eq!(params.get("enabled"), Some("1"));
+        assert_eq!(params.get("delete"), Some("pool"));
+        assert_eq!(params.get("schedule"), None);
+    }
+
+    #[test]
+    fn backup_job_decodes_vmids_and_enabled_default() {
+        let job: BackupJob = serde_json::from_value(json!({
+            "id": "backup-1a2b3c4d-5e6f",
+            "schedule": "21:00",
+            "vmid": "100,101",
+            "next-run": 1_705_352_400u64,
+            "type": "vzdump",
+        }))
+        .expect("job");
+        assert!(job.is_enabled());
+        assert_eq!(job.vmids(), [100, 101]);
+        assert_eq!(job.next_run, Some(1_705_352_400));
+    }
+
+    #[test]
+    fn included_guest_decodes_volume_children() {
+        let guest: IncludedGuest = serde_json::from_value(json!({
+            "id": 100,
+            "name": "web",
+            "type": "qemu",
+            "children": [
+                {"id": "scsi0", "name": "local-lvm:vm-100-disk-0", "included": 1, "reason": "default"},
+                {"id": "scsi1", "name": "local-lvm:vm-100-disk-1", "included": 0, "reason": "backup=0"}
+            ]
+        }))
+        .expect("guest");
+        assert_eq!(guest.id, 100);
+        assert_eq!(guest.volumes.len(), 2);
+        assert!(guest.volumes[0].is_included());
+        assert!(!guest.volumes[1].is_included());
+    }
+}
@@ -0,0 +1,219 @@
+//! Ceph (hyperconverged storage) read-only types.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::serde_helpers::{opt_bool_int, opt_number};
+
+/// Output of `ceph status` as relayed by `/cluster/ceph/status`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CephStatus {
+    pub fsid: Option<String>,
+    pub health: CephHealth,
+    #[serde(default)]
+    pub quorum_names: Vec<String>,
+    pub osdmap: Option<CephOsdMap>,
+    pub pgmap: Option<CephPgMap>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CephHealth {
+    /// `HEALTH_OK`, `HEALTH_WARN` or `HEALTH_ERR`.
+    pub status: String,
+    /// Active health checks keyed by code, e.g. `OSD_DOWN`.
+    #[serde(default)]
+    pub checks: HashMap<String, Value>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl CephHealth {
+    pub fn is_ok(&self) -> bool {
+        self.status == "HEALTH_OK"
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CephOsdMap {
+    #[serde(default, deserialize_with = "opt_number")]
+    pub num_osds: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub num_up_osds: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub num_in_osds: Option<u32>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CephPgMap {
+    #[serde(default, deserialize_with = "opt_number")]
+    pub num_pgs: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub bytes_used: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub bytes_avail: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub bytes_total: Option<u64>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// CRUSH hierarchy from `/nodes/{node}/ceph/osd`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CephOsdTree {
+    pub root: CephOsdNode,
+    /// Cluster-wide OSD flags, e.g. `noout,sortbitwise`.
+    pub flags: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl CephOsdTree {
+    /// Every OSD leaf, in tree order.
+    pub fn osds(&self) -> Vec<&CephOsdNode> {
+        let mut osds = Vec::new();
+        collect_osds(&self.root, &mut osds);
+        osds
+    }
+
+    pub fn down_osds(&self) -> Vec<&CephOsdNode> {
+        self.osds().into_iter().filter(|osd| !osd.is_up()).collect()
+    }
+}
+
+fn collect_osds<'a>(node: &'a CephOsdNode, osds: &mut Vec<&'a CephOsdNode>) {
+    if node.is_osd() {
+        osds.push(node);
+    }
+    for child in &node.children {
+        collect_osds(child, osds);
+    }
+}
+
+/// Bucket (`root`, `host`, ...) or OSD in the CRUSH tree; buckets have negative ids.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CephOsdNode {
+    pub id: i64,
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    /// `up` or `down` for OSDs.
+    pub status: Option<String>,
+    #[serde(rename = "in", default, deserialize_with = "opt_bool_int")]
+    pub in_: Option<u8>,
+    pub host: Option<String>,
+    pub device_class: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub crush_weight: Option<f64>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub percent_used: Option<f64>,
+    #[serde(default)]
+    pub children: Vec<CephOsdNode>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl CephOsdNode {
+    pub fn is_osd(&self) -> bool {
+        self.type_.as_deref() == Some("osd")
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.status.as_deref() == Some("up")
+    }
+
+    pub fn is_in(&self) -> bool {
+        self.in_ == Some(1)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CephPool {
+    pub pool: u32,
+    pub pool_name: String,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub size: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub min_size: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub pg_num: Option<u32>,
+    pub pg_autoscale_mode: Option<String>,
+    pub crush_rule_name: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub bytes_used: Option<u64>,
+    /// Fraction of the pool's capacity in use, `0.0..=1.0`.
+    #[serde(default, deserialize_with = "opt_number")]
+    pub percent_used: Option<f64>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CephOsdTree, CephPool, CephStatus};
+
+    #[test]
+    fn ceph_status_decodes_health_and_maps() {
+        let status: CephStatus = serde_json::from_str(
+            r#"{
+                "fsid": "6f3c0d4e",
+                "health": {"status": "HEALTH_WARN", "checks": {"OSD_DOWN": {"severity": "HEALTH_WARN"}}},
+                "quorum_names": ["pve1", "pve2", "pve3"],
+                "osdmap": {"epoch": 120, "num_osds": 6, "num_up_osds": 5, "num_in_osds": 6},
+                "pgmap": {"num_pgs": 129, "bytes_used": 104857600, "bytes_total": "1099511627776"}
+            }"#,
+        )
+        .expect("status");
+        assert!(!status.health.is_ok());
+        assert!(status.health.checks.contains_key("OSD_DOWN"));
+        assert_eq!(status.osdmap.and_then(|m| m.num_up_osds), Some(5));
+        assert_eq!(
+            status.pgmap.and_then(|m| m.bytes_total),
+            Some(1_099_511_627_776)
+        );
+    }
+
+    #[test]
+    fn osd_tree_flattens_leaves() {
+        let tree: CephOsdTree = serde_json::from_str(
+            r#"{
+                "flags": "sortbitwise,noout",
+                "root": {"id": -1, "name": "default", "type": "root", "children": [
+                    {"id": -3, "name": "pve1", "type": "host", "children": [
+                        {"id": 0, "name": "osd.0", "type": "osd", "status": "up", "in": 1, "crush_weight": 0.9},
+                        {"id": 1, "name": "osd.1", "type": "osd", "status": "down", "in": 0}
+                    ]},
+                    {"id": -5, "name": "pve2", "type": "host", "children": [
+                        {"id": 2, "name": "osd.2", "type": "osd", "status": "up", "in": 1}
+                    ]}
+                ]}
+            }"#,
+        )
+        .expect("tree");
+        let names: Vec<_> = tree
+            .osds()
+            .iter()
+            .filter_map(|o| o.name.as_deref())
+            .collect();
+        assert_eq!(names, ["osd.0", "osd.1", "osd.2"]);
+        let down = tree.down_osds();
+        assert_eq!(down.len(), 1);
+        assert!(!down[0].is_in());
+    }
+
+    #[test]
+    fn ceph_pool_decodes_usage() {
+        let pool: CephPool = serde_json::from_str(
+            r#"{"pool":2,"pool_name":"vm-pool","size":3,"min_size":2,"pg_num":32,"percent_used":0.125,"bytes_used":"4096","application_metadata":{"rbd":{}}}"#,
+        )
+        .expect("pool");
+        assert_eq!(pool.bytes_used, Some(4096));
+        assert_eq!(pool.percent_used, Some(0.125));
+        assert!(pool.extra.contains_key("application_metadata"));
+    }
+}
@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::params::PveParams;
+use crate::types::common::Tags;
+use crate::types::serde_helpers::opt_number;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ClusterStatusItem {
@@ -30,11 +32,73 @@ pub struct ClusterResource {
     pub vmid: Option<u32>,
     pub status: Option<String>,
     pub name: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub cpu: Option<f64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub mem: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub maxmem: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub disk: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub maxdisk: Option<u64>,
+    pub pool: Option<String>,
+    /// Guest tags as PVE stores them; see `tag_list`.
+    pub tags: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl ClusterResource {
+    pub fn tag_list(&self) -> Tags {
+        self.tags.as_deref().map(Tags::parse).unwrap_or_default()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tag_list().contains(tag)
+    }
+
+    /// Whether this is a member of `pool`; the `pool` entry itself does not count.
+    pub fn in_pool(&self, pool: &str) -> bool {
+        self.resource_type != "pool" && self.pool.as_deref() == Some(pool)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ClusterLogEntry {
+    pub uid: Option<u64>,
+    pub time: Option<u64>,
+    pub node: Option<String>,
+    pub user: Option<String>,
+    pub tag: Option<String>,
+    pub pri: Option<u8>,
+    pub pid: Option<u64>,
+    pub msg: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Corosync node entry, as listed by `/cluster/config/nodes` and the join info `nodelist`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ClusterConfigNode {
+    pub node: Option<String>,
+    pub name: Option<String>,
+    pub nodeid: Option<String>,
+    pub quorum_votes: Option<String>,
+    pub ring0_addr: Option<String>,
+    pub pve_addr: Option<String>,
+    pub pve_fp: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ClusterJoinInfo {
+    pub config_digest: Option<String>,
+    pub preferred_node: Option<String>,
+    #[serde(default)]
+    pub nodelist: Vec<ClusterConfigNode>,
+    pub totem: Option<Value>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
@@ -89,3 +153,49 @@ impl ClusterResourcesQuery {
         params
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ClusterJoinInfo, ClusterResource};
+
+    #[test]
+    fn join_info_decodes_nodelist() {
+        let info: ClusterJoinInfo = serde_json::from_value(serde_json::json!({
+            "config_digest": "abc",
+            "preferred_node": "pve1",
+            "nodelist": [{
+                "name": "pve1",
+                "nodeid": "1",
+                "quorum_votes": "1",
+                "ring0_addr": "10.0.0.1",
+                "pve_addr": "10.0.0.1",
+                "pve_fp": "AA:BB"
+            }],
+            "totem": {"cluster_name": "lab", "version": "2"}
+        }))
+        .expect("decode join info");
+
+        assert_eq!(info.preferred_node.as_deref(), Some("pve1"));
+        assert_eq!(info.nodelist.len(), 1);
+        assert_eq!(info.nodelist[0].ring0_addr.as_deref(), Some("10.0.0.1"));
+        assert_eq!(info.nodelist[0].pve_fp.as_deref(), Some("AA:BB"));
+    }
+
+    #[test]
+    fn resource_pool_and_tags_are_typed() {
+        let resources: Vec<ClusterResource> = serde_json::from_str(
+            r#"[
+                {"id":"qemu/100","type":"qemu","vmid":100,"pool":"tenant-a","tags":"prod;web"},
+                {"id":"/pool/tenant-a","type":"pool","pool":"tenant-a"},
+                {"id":"lxc/200","type":"lxc","vmid":200}
+            ]"#,
+        )
+        .expect("resources");
+        assert!(resources[0].in_pool("tenant-a"));
+        assert!(resources[0].has_tag("web"));
+        assert!(!resources[0].has_tag("we"));
+        assert!(!resources[1].in_pool("tenant-a"));
+        assert!(resources[2].tag_list().is_empty());
+        assert!(!resources[0].extra.contains_key("tags"));
+    }
+}
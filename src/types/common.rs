@@ -1,10 +1,15 @@
 //! Common/shared SDK types.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::error::PveError;
+use crate::types::cluster::ClusterResource;
+use crate::types::serde_helpers::{opt_bool_int, opt_number};
+
 #[derive(Debug, Clone, Deserialize)]
 pub(crate) struct ApiEnvelope<T> {
     pub data: T,
@@ -18,15 +23,632 @@ pub struct VersionInfo {
     pub console: Option<String>,
 }
 
+/// Child entry of a namespace listing, e.g. `GET /` or `GET /nodes/{node}`.
+///
+/// Most namespaces name children via `subdir`; some (such as node indexes) use `name`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApiIndexItem {
+    pub subdir: Option<String>,
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl ApiIndexItem {
+    pub fn entry(&self) -> Option<&str> {
+        self.subdir.as_deref().or(self.name.as_deref())
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SnapshotInfo {
     pub name: String,
     pub description: Option<String>,
     pub parent: Option<String>,
     pub snaptime: Option<u64>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
     pub vmstate: Option<u8>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
+impl SnapshotInfo {
+    /// Whether the snapshot includes the guest's RAM state.
+    pub fn has_vmstate(&self) -> bool {
+        self.vmstate == Some(1)
+    }
+
+    /// PVE lists the running state as a pseudo-snapshot named `current`.
+    pub fn is_current(&self) -> bool {
+        self.name == "current"
+    }
+}
+
+/// Entry of a guest's `pending` listing: the running `value` next to the `pending` one that
+/// applies on the next restart.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PendingConfigEntry {
+    pub key: String,
+    pub value: Option<Value>,
+    pub pending: Option<Value>,
+    /// `1` when the key is removed on restart, `2` when the removal is forced.
+    #[serde(default, deserialize_with = "opt_number")]
+    pub delete: Option<u8>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl PendingConfigEntry {
+    /// Whether the entry still differs from the running config. Values are compared as
+    /// strings, since PVE may report `2` on one side and `"2"` on the other.
+    pub fn is_pending(&self) -> bool {
+        let deleted = self.delete.is_some_and(|delete| delete > 0);
+        let changed = self.pending.as_ref().is_some_and(|pending| {
+            self.value.as_ref().map(config_value_string) != Some(config_value_string(pending))
+        });
+        deleted || changed
+    }
+}
+
+fn config_value_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Operation checked by the guest `feature` endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuestFeature {
+    Snapshot,
+    Clone,
+    Copy,
+}
+
+impl GuestFeature {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Snapshot => "snapshot",
+            Self::Clone => "clone",
+            Self::Copy => "copy",
+        }
+    }
+}
+
+impl fmt::Display for GuestFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Reply of `GET .../{vmid}/feature`; QEMU also lists the nodes the feature is usable on.
+#[derive(Debug, Deserialize)]
+pub(crate) struct FeatureCheck {
+    #[serde(rename = "hasFeature", default, deserialize_with = "opt_bool_int")]
+    pub has_feature: Option<u8>,
+}
+
+impl FeatureCheck {
+    pub fn is_available(&self) -> bool {
+        self.has_feature.is_some_and(|flag| flag != 0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotNode {
+    pub info: SnapshotInfo,
+    pub children: Vec<SnapshotNode>,
+}
+
+/// Rebuilds the parent/child hierarchy from a flat snapshot listing.
+///
+/// Entries without a parent, or whose parent is missing from `snaps`, become roots, as does
+/// the oldest entry of any parent cycle (which cannot be reached from a root otherwise).
+/// Siblings are ordered by `snaptime`, with `current` always last.
+pub fn build_snapshot_tree(snaps: &[SnapshotInfo]) -> Vec<SnapshotNode> {
+    let names: HashSet<&str> = snaps.iter().map(|snap| snap.name.as_str()).collect();
+    let mut children: HashMap<Option<&str>, Vec<&SnapshotInfo>> = HashMap::new();
+    for snap in snaps {
+        let parent = snap
+            .parent
+            .as_deref()
+            .filter(|parent| *parent != snap.name && names.contains(parent));
+        children.entry(parent).or_default().push(snap);
+    }
+
+    fn sibling_order(snap: &&SnapshotInfo) -> (bool, u64) {
+        (snap.is_current(), snap.snaptime.unwrap_or(0))
+    }
+
+    fn build_level(
+        mut level: Vec<&SnapshotInfo>,
+        children: &HashMap<Option<&str>, Vec<&SnapshotInfo>>,
+        seen: &mut HashSet<String>,
+    ) -> Vec<SnapshotNode> {
+        level.sort_by_key(sibling_order);
+        let mut nodes = Vec::with_capacity(level.len());
+        for snap in level {
+            if !seen.insert(snap.name.clone()) {
+                continue;
+            }
+            let below = children
+                .get(&Some(snap.name.as_str()))
+                .cloned()
+                .unwrap_or_default();
+            nodes.push(SnapshotNode {
+                info: snap.clone(),
+                children: build_level(below, children, seen),
+            });
+        }
+        nodes
+    }
+
+    let mut seen = HashSet::new();
+    let roots = children.get(&None).cloned().unwrap_or_default();
+    let mut tree = build_level(roots, &children, &mut seen);
+
+    // Whatever is left hangs off a parent cycle. Break each cycle at its oldest entry.
+    let mut unreached: Vec<&SnapshotInfo> = snaps
+        .iter()
+        .filter(|snap| !seen.contains(&snap.name))
+        .collect();
+    unreached.sort_by_key(sibling_order);
+    for snap in unreached {
+        if !seen.contains(&snap.name) {
+            tree.extend(build_level(vec![snap], &children, &mut seen));
+        }
+    }
+    tree.sort_by_key(|node| sibling_order(&&node.info));
+    tree
+}
+
+/// Bandwidth limit in the KiB/s unit PVE's `bwlimit` parameters expect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bandwidth {
+    kib: u64,
+}
+
+impl Bandwidth {
+    /// No limit; PVE treats `bwlimit=0` as unlimited.
+    pub fn unlimited() -> Self {
+        Self { kib: 0 }
+    }
+
+    pub fn kbytes_per_sec(kib: u64) -> Self {
+        Self { kib }
+    }
+
+    /// MiB/s as shown in the PVE UI. Tiny positive values round up to 1 KiB/s rather than
+    /// silently becoming "unlimited".
+    pub fn mbytes_per_sec(mib: f64) -> Result<Self, PveError> {
+        if !mib.is_finite() || mib < 0.0 {
+            return Err(PveError::InvalidArgument(format!(
+                "invalid bandwidth: {mib} MiB/s"
+            )));
+        }
+        let kib = (mib * 1024.0).round() as u64;
+        Ok(Self {
+            kib: if mib > 0.0 { kib.max(1) } else { 0 },
+        })
+    }
+
+    pub fn to_kib(self) -> u64 {
+        self.kib
+    }
+}
+
+/// Result of a create-if-missing call.
+#[derive(Debug, Clone)]
+pub enum CreateOutcome {
+    /// The guest was created; carries the creation task UPID.
+    Created(String),
+    /// The vmid is already taken, possibly by a guest of the other kind or on another node.
+    AlreadyExists(Box<ClusterResource>),
+}
+
+impl CreateOutcome {
+    pub fn upid(&self) -> Option<&str> {
+        match self {
+            Self::Created(upid) => Some(upid),
+            Self::AlreadyExists(_) => None,
+        }
+    }
+
+    pub fn is_created(&self) -> bool {
+        matches!(self, Self::Created(_))
+    }
+}
+
+/// Guest `lock` value: set while PVE runs an operation that must not overlap with
+/// others. Unknown values are kept as `Other`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuestLock {
+    Backup,
+    Migrate,
+    Snapshot,
+    Rollback,
+    Clone,
+    Other(String),
+}
+
+impl GuestLock {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Backup => "backup",
+            Self::Migrate => "migrate",
+            Self::Snapshot => "snapshot",
+            Self::Rollback => "rollback",
+            Self::Clone => "clone",
+            Self::Other(raw) => raw,
+        }
+    }
+}
+
+impl From<&str> for GuestLock {
+    fn from(raw: &str) -> Self {
+        match raw {
+            "backup" => Self::Backup,
+            "migrate" => Self::Migrate,
+            "snapshot" => Self::Snapshot,
+            "rollback" => Self::Rollback,
+            "clone" => Self::Clone,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for GuestLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Desired power state for `*_ensure_state`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuestState {
+    /// Started, and for QEMU also not paused or suspended.
+    Running,
+    /// Stopped via a hard `stop`.
+    Stopped,
+    /// Stopped via a graceful `shutdown`.
+    ShutDown,
+}
+
+impl GuestState {
+    /// The guest `status` value reached once this state is applied.
+    pub fn target_status(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Stopped | Self::ShutDown => "stopped",
+        }
+    }
+
+    pub(crate) fn action(self) -> &'static str {
+        match self {
+            Self::Running => "start",
+            Self::Stopped => "stop",
+            Self::ShutDown => "shutdown",
+        }
+    }
+}
+
+/// Longest guest `description` PVE accepts, in bytes.
+pub const MAX_DESCRIPTION_LEN: usize = 8192;
+
+/// Config update that sets the guest `description`, or deletes it when `text` is empty.
+pub(crate) fn description_params(text: &str) -> Result<PveParams, PveError> {
+    if text.len() > MAX_DESCRIPTION_LEN {
+        return Err(PveError::InvalidArgument(format!(
+            "description is {} bytes, PVE accepts at most {MAX_DESCRIPTION_LEN}",
+            text.len()
+        )));
+    }
+    let mut params = PveParams::new();
+    if text.is_empty() {
+        params.insert("delete", "description");
+    } else {
+        params.insert("description", text);
+    }
+    Ok(params)
+}
+
+/// Guest tag list. PVE accepts `;`, `,` and spaces between tags and writes them back
+/// `;`-separated, which is what `Display` produces. Duplicates are dropped, first
+/// occurrence wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tags(Vec<String>);
+
+impl Tags {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Splits a `tags` config value; empty segments are ignored.
+    pub fn parse(value: &str) -> Self {
+        value
+            .split([';', ',', ' '])
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+
+    /// Checks a tag against PVE's `[a-z0-9_][a-z0-9_+.-]*` pattern (case-insensitive).
+    pub fn validate(tag: &str) -> Result<(), PveError> {
+        let mut chars = tag.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+' | '.'));
+        if !valid {
+            return Err(PveError::InvalidArgument(format!("invalid tag {tag:?}")));
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.iter().any(|t| t == tag)
+    }
+
+    /// Adds `tag` unless already present; returns whether it was added.
+    pub fn insert(&mut self, tag: impl Into<String>) -> bool {
+        let tag = tag.into();
+        if self.contains(&tag) {
+            return false;
+        }
+        self.0.push(tag);
+        true
+    }
+
+    /// Removes `tag`; returns whether it was present.
+    pub fn remove(&mut self, tag: &str) -> bool {
+        let before = self.0.len();
+        self.0.retain(|t| t != tag);
+        self.0.len() != before
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    /// Config update writing these tags, or deleting the key when there are none.
+    pub(crate) fn to_params(&self) -> Result<PveParams, PveError> {
+        self.0.iter().try_for_each(|tag| Self::validate(tag))?;
+        let mut params = PveParams::new();
+        if self.is_empty() {
+            params.insert("delete", "tags");
+        } else {
+            params.insert("tags", self.to_string());
+        }
+        Ok(params)
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Tags {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        let mut tags = Self::new();
+        for tag in iter {
+            tags.insert(tag);
+        }
+        tags
+    }
+}
+
+impl fmt::Display for Tags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.join(";"))
+    }
+}
+
+/// Formats a byte count with binary (IEC) units: `512 B`, `1.0 KiB`, `3.5 GiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    // Compare what will be printed, so 1048575 becomes `1.0 MiB` rather than `1024.0 KiB`.
+    while (value * 10.0).round() / 10.0 >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
 pub use crate::params::PveParams;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{
+        Bandwidth, FeatureCheck, GuestLock, MAX_DESCRIPTION_LEN, PendingConfigEntry, SnapshotInfo,
+        Tags, build_snapshot_tree, description_params, format_bytes,
+    };
+
+    fn snap(name: &str, parent: Option<&str>, snaptime: Option<u64>) -> SnapshotInfo {
+        SnapshotInfo {
+            name: name.to_string(),
+            description: None,
+            parent: parent.map(str::to_string),
+            snaptime,
+            vmstate: None,
+            extra: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn build_snapshot_tree_links_children_and_keeps_current_last() {
+        let snaps = vec![
+            snap("current", Some("after-upgrade"), None),
+            snap("after-upgrade", Some("base"), Some(200)),
+            snap("experiment", Some("base"), Some(300)),
+            snap("base", None, Some(100)),
+        ];
+
+        let tree = build_snapshot_tree(&snaps);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].info.name, "base");
+
+        let children: Vec<&str> = tree[0]
+            .children
+            .iter()
+            .map(|node| node.info.name.as_str())
+            .collect();
+        assert_eq!(children, ["after-upgrade", "experiment"]);
+
+        let current = &tree[0].children[0].children[0];
+        assert!(current.info.is_current());
+        assert!(current.children.is_empty());
+    }
+
+    #[test]
+    fn build_snapshot_tree_treats_orphans_as_roots() {
+        let snaps = vec![
+            snap("orphan", Some("deleted"), Some(10)),
+            snap("current", None, None),
+        ];
+
+        let tree = build_snapshot_tree(&snaps);
+        let roots: Vec<&str> = tree.iter().map(|node| node.info.name.as_str()).collect();
+        assert_eq!(roots, ["orphan", "current"]);
+    }
+
+    #[test]
+    fn build_snapshot_tree_breaks_parent_cycles_into_roots() {
+        let snaps = vec![
+            snap("base", None, Some(100)),
+            snap("a", Some("b"), Some(10)),
+            snap("b", Some("a"), Some(20)),
+            snap("c", Some("b"), Some(30)),
+        ];
+
+        let tree = build_snapshot_tree(&snaps);
+        let roots: Vec<&str> = tree.iter().map(|node| node.info.name.as_str()).collect();
+        assert_eq!(roots, ["a", "base"]);
+
+        let b = &tree[0].children[0];
+        assert_eq!(b.info.name, "b");
+        let below_b: Vec<&str> = b.children.iter().map(|n| n.info.name.as_str()).collect();
+        assert_eq!(below_b, ["c"]);
+    }
+
+    #[test]
+    fn bandwidth_converts_to_kib() {
+        assert_eq!(Bandwidth::kbytes_per_sec(512).to_kib(), 512);
+        assert_eq!(
+            Bandwidth::mbytes_per_sec(100.0).expect("bw").to_kib(),
+            102_400
+        );
+        assert_eq!(Bandwidth::mbytes_per_sec(0.0001).expect("bw").to_kib(), 1);
+        assert!(Bandwidth::mbytes_per_sec(-1.0).is_err());
+        assert!(Bandwidth::mbytes_per_sec(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn format_bytes_uses_iec_units() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(4 << 30), "4.0 GiB");
+        assert_eq!(format_bytes(3 << 40), "3.0 TiB");
+        assert_eq!(format_bytes(u64::MAX), "16.0 EiB");
+    }
+
+    #[test]
+    fn format_bytes_picks_the_unit_after_rounding() {
+        assert_eq!(format_bytes(1_048_575), "1.0 MiB");
+        assert_eq!(format_bytes(1_048_474), "1023.9 KiB");
+        assert_eq!(format_bytes((1 << 30) - 1), "1.0 GiB");
+    }
+
+    #[test]
+    fn pending_entry_detects_changes_and_deletions() {
+        let entries: Vec<PendingConfigEntry> = serde_json::from_str(
+            r#"[
+                {"key":"cores","value":2,"pending":4},
+                {"key":"memory","value":"2048","pending":"2048"},
+                {"key":"sockets","value":2,"pending":"2"},
+                {"key":"name","value":"web01"},
+                {"key":"net1","value":"virtio,bridge=vmbr1","delete":1}
+            ]"#,
+        )
+        .expect("pending");
+        let pending: Vec<_> = entries
+            .iter()
+            .filter(|e| e.is_pending())
+            .map(|e| e.key.as_str())
+            .collect();
+        assert_eq!(pending, ["cores", "net1"]);
+    }
+
+    #[test]
+    fn feature_check_accepts_bool_and_int() {
+        let check: FeatureCheck =
+            serde_json::from_str(r#"{"hasFeature":true,"nodes":["pve1"]}"#).expect("check");
+        assert!(check.is_available());
+        let check: FeatureCheck = serde_json::from_str(r#"{"hasFeature":0}"#).expect("check");
+        assert!(!check.is_available());
+    }
+
+    #[test]
+    fn description_params_set_delete_and_limit() {
+        let params = description_params("owner=ops\nrole=db").expect("params");
+        assert_eq!(params.get("description"), Some("owner=ops\nrole=db"));
+        assert_eq!(params.get("delete"), None);
+
+        let params = description_params("").expect("params");
+        assert_eq!(params.get("delete"), Some("description"));
+
+        assert!(description_params(&"x".repeat(MAX_DESCRIPTION_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn tags_parse_dedupe_and_format() {
+        let mut tags = Tags::parse("prod;web, db  web;");
+        assert_eq!(tags.iter().collect::<Vec<_>>(), ["prod", "web", "db"]);
+        assert!(!tags.insert("db"));
+        assert!(tags.insert("backup"));
+        assert!(tags.remove("web"));
+        assert!(!tags.remove("web"));
+        assert_eq!(tags.to_string(), "prod;db;backup");
+        assert_eq!(
+            tags.to_params().expect("params").get("tags"),
+            Some("prod;db;backup")
+        );
+        assert_eq!(
+            Tags::new().to_params().expect("params").get("delete"),
+            Some("tags")
+        );
+
+        assert!(Tags::validate("k8s-node_1.a+b").is_ok());
+        assert!(Tags::validate("-lead").is_err());
+        assert!(Tags::validate("has space").is_err());
+        assert!(Tags::from_iter(["ok", "bad/tag"]).to_params().is_err());
+    }
+
+    #[test]
+    fn guest_lock_maps_known_values() {
+        for (raw, lock) in [
+            ("backup", GuestLock::Backup),
+            ("migrate", GuestLock::Migrate),
+            ("snapshot", GuestLock::Snapshot),
+            ("rollback", GuestLock::Rollback),
+            ("clone", GuestLock::Clone),
+        ] {
+            assert_eq!(GuestLock::from(raw), lock);
+            assert_eq!(lock.as_str(), raw);
+        }
+        let other = GuestLock::from("snapshot-delete");
+        assert_eq!(other, GuestLock::Other("snapshot-delete".to_string()));
+        assert_eq!(other.to_string(), "snapshot-delete");
+    }
+}
@@ -0,0 +1,95 @@
+//! Console ticket types for termproxy/vncproxy sessions.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::params::PveParams;
+use crate::types::serde_helpers::number;
+
+/// Result of `.../termproxy`. Open the terminal websocket at `.../vncwebsocket`
+/// with `port` and `vncticket=<ticket>`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TermProxyInfo {
+    pub ticket: String,
+    /// PVE has returned the port both as a number and as a string across releases.
+    #[serde(deserialize_with = "number")]
+    pub port: u16,
+    pub user: Option<String>,
+    pub upid: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Result of `.../vncproxy`. `password` is only set when one was generated.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct VncProxyInfo {
+    pub ticket: String,
+    #[serde(deserialize_with = "number")]
+    pub port: u16,
+    pub user: Option<String>,
+    pub upid: Option<String>,
+    pub cert: Option<String>,
+    pub password: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VncProxyRequest {
+    /// Prepare the session for a websocket client (noVNC) instead of raw VNC.
+    pub websocket: Option<bool>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub extra: PveParams,
+}
+
+impl VncProxyRequest {
+    pub fn websocket() -> Self {
+        Self {
+            websocket: Some(true),
+            ..Self::default()
+        }
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        if let Some(websocket) = self.websocket {
+            params.insert_bool("websocket", websocket);
+        }
+        params.insert_opt("width", self.width.map(|v| v.to_string()));
+        params.insert_opt("height", self.height.map(|v| v.to_string()));
+        params.extend(&self.extra);
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TermProxyInfo, VncProxyInfo, VncProxyRequest};
+
+    #[test]
+    fn proxy_port_accepts_number_or_string() {
+        let term: TermProxyInfo = serde_json::from_str(
+            r#"{"ticket":"PVEVNC:abc","port":5900,"user":"root@pam","upid":"UPID:pve1:1:2:3:vncproxy:101:root@pam:"}"#,
+        )
+        .expect("termproxy");
+        assert_eq!(term.port, 5900);
+
+        let vnc: VncProxyInfo =
+            serde_json::from_str(r#"{"ticket":"PVEVNC:abc","port":"5901","cert":"-----BEGIN"}"#)
+                .expect("vncproxy");
+        assert_eq!(vnc.port, 5901);
+        assert!(vnc.password.is_none());
+
+        assert!(serde_json::from_str::<TermProxyInfo>(r#"{"ticket":"x","port":"vnc"}"#).is_err());
+    }
+
+    #[test]
+    fn vnc_request_maps_websocket() {
+        let params = VncProxyRequest::websocket().to_params();
+        assert_eq!(params.get("websocket"), Some("1"));
+        assert_eq!(params.get("width"), None);
+    }
+}
@@ -18,6 +18,9 @@ pub struct DatacenterConfigUpdateRequest {
     pub keyboard: Option<String>,
     pub language: Option<String>,
     pub migration: Option<String>,
+    pub ha: Option<String>,
+    pub mac_prefix: Option<String>,
+    pub bwlimit: Option<String>,
     pub console: Option<String>,
     pub email_from: Option<String>,
     pub max_workers: Option<u32>,
@@ -31,6 +34,9 @@ impl DatacenterConfigUpdateRequest {
         params.insert_opt("keyboard", self.keyboard.clone());
         params.insert_opt("language", self.language.clone());
         params.insert_opt("migration", self.migration.clone());
+        params.insert_opt("ha", self.ha.clone());
+        params.insert_opt("mac_prefix", self.mac_prefix.clone());
+        params.insert_opt("bwlimit", self.bwlimit.clone());
         params.insert_opt("console", self.console.clone());
         params.insert_opt("email-from", self.email_from.clone());
         params.insert_opt("max_workers", self.max_workers.map(|v| v.to_string()));
@@ -50,6 +56,9 @@ mod tests {
             keyboard: Some("en-us".to_string()),
             language: Some("en".to_string()),
             migration: None,
+            ha: None,
+            mac_prefix: None,
+            bwlimit: None,
             console: None,
             email_from: Some("noreply@example.com".to_string()),
             max_workers: Some(8),
@@ -63,4 +72,26 @@ mod tests {
         assert_eq!(params.get("max_workers"), Some("8"));
         assert_eq!(params.get("next-id"), Some("200"));
     }
+
+    #[test]
+    fn datacenter_update_config_maps_migration_and_bwlimit() {
+        let req = DatacenterConfigUpdateRequest {
+            migration: Some("secure,network=10.10.10.0/24".to_string()),
+            bwlimit: Some("migration=102400,restore=51200".to_string()),
+            ha: Some("shutdown_policy=migrate".to_string()),
+            mac_prefix: Some("BC:24:11".to_string()),
+            ..Default::default()
+        };
+        let params = req.to_params();
+        assert_eq!(
+            params.get("migration"),
+            Some("secure,network=10.10.10.0/24")
+        );
+        assert_eq!(
+            params.get("bwlimit"),
+            Some("migration=102400,restore=51200")
+        );
+        assert_eq!(params.get("ha"), Some("shutdown_policy=migrate"));
+        assert_eq!(params.get("mac_prefix"), Some("BC:24:11"));
+    }
 }
@@ -0,0 +1,363 @@
+//! Node-local disk and storage-backend types (`/nodes/{node}/disks/...`).
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::params::PveParams;
+use crate::types::serde_helpers::{opt_bool_int, opt_number};
+
+/// Entry of `GET /nodes/{node}/disks/zfs`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ZfsPool {
+    pub name: String,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub size: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub free: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub alloc: Option<u64>,
+    /// `ONLINE`, `DEGRADED`, `FAULTED`, ...
+    pub health: Option<String>,
+    /// Deduplication ratio, e.g. `1.0`.
+    #[serde(default, deserialize_with = "opt_number")]
+    pub dedup: Option<f64>,
+    /// Fragmentation in percent.
+    #[serde(default, deserialize_with = "opt_number")]
+    pub frag: Option<u32>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl ZfsPool {
+    pub fn is_online(&self) -> bool {
+        self.health.as_deref() == Some("ONLINE")
+    }
+}
+
+/// `zpool status` as returned by `GET /nodes/{node}/disks/zfs/{name}`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ZfsPoolDetail {
+    pub name: String,
+    pub state: Option<String>,
+    pub status: Option<String>,
+    pub action: Option<String>,
+    pub scan: Option<String>,
+    pub errors: Option<String>,
+    /// Top-level vdevs; the pool itself is usually the single root entry.
+    #[serde(default)]
+    pub children: Vec<ZfsVdev>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl ZfsPoolDetail {
+    /// Leaf devices that are not `ONLINE`, in tree order.
+    pub fn unhealthy_devices(&self) -> Vec<&ZfsVdev> {
+        let mut out = Vec::new();
+        for vdev in &self.children {
+            collect_unhealthy(vdev, &mut out);
+        }
+        out
+    }
+}
+
+fn collect_unhealthy<'a>(vdev: &'a ZfsVdev, out: &mut Vec<&'a ZfsVdev>) {
+    if vdev.children.is_empty() {
+        if vdev.state.as_deref() != Some("ONLINE") {
+            out.push(vdev);
+        }
+        return;
+    }
+    for child in &vdev.children {
+        collect_unhealthy(child, out);
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ZfsVdev {
+    pub name: String,
+    pub state: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub read: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub write: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub cksum: Option<u64>,
+    pub msg: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub leaf: Option<u8>,
+    #[serde(default)]
+    pub children: Vec<ZfsVdev>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZfsRaidLevel {
+    Single,
+    Mirror,
+    Raid10,
+    RaidZ,
+    RaidZ2,
+    RaidZ3,
+    DRaid,
+    DRaid2,
+    DRaid3,
+}
+
+impl ZfsRaidLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Single => "single",
+            Self::Mirror => "mirror",
+            Self::Raid10 => "raid10",
+            Self::RaidZ => "raidz",
+            Self::RaidZ2 => "raidz2",
+            Self::RaidZ3 => "raidz3",
+            Self::DRaid => "draid",
+            Self::DRaid2 => "draid2",
+            Self::DRaid3 => "draid3",
+        }
+    }
+}
+
+impl fmt::Display for ZfsRaidLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZfsCompression {
+    On,
+    Off,
+    Gzip,
+    Lz4,
+    Lzjb,
+    Zle,
+    Zstd,
+}
+
+impl ZfsCompression {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::On => "on",
+            Self::Off => "off",
+            Self::Gzip => "gzip",
+            Self::Lz4 => "lz4",
+            Self::Lzjb => "lzjb",
+            Self::Zle => "zle",
+            Self::Zstd => "zstd",
+        }
+    }
+}
+
+impl fmt::Display for ZfsCompression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `POST /nodes/{node}/disks/zfs`.
+#[derive(Debug, Clone)]
+pub struct ZfsCreateRequest {
+    pub name: String,
+    /// Whole disks to use, e.g. `/dev/sdb`. They must be unused.
+    pub devices: Vec<String>,
+    pub raidlevel: ZfsRaidLevel,
+    pub ashift: Option<u8>,
+    pub compression: Option<ZfsCompression>,
+    /// Also register the pool as a `zfspool` storage.
+    pub add_storage: Option<bool>,
+    pub extra: PveParams,
+}
+
+impl ZfsCreateRequest {
+    pub fn new<I, S>(name: impl Into<String>, raidlevel: ZfsRaidLevel, devices: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            devices: devices.into_iter().map(Into::into).collect(),
+            raidlevel,
+            ashift: None,
+            compression: None,
+            add_storage: None,
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("name", self.name.clone());
+        params.insert("devices", self.devices.join(","));
+        params.insert("raidlevel", self.raidlevel.to_string());
+        params.insert_opt("ashift", self.ashift.map(|v| v.to_string()));
+        params.insert_opt("compression", self.compression.map(|v| v.to_string()));
+        if let Some(add_storage) = self.add_storage {
+            params.insert_bool("add_storage", add_storage);
+        }
+        params.extend(&self.extra);
+        params
+    }
+}
+
+/// `POST /nodes/{node}/disks/lvm` and `/disks/lvmthin`; both take the same options.
+#[derive(Debug, Clone)]
+pub struct LvmCreateRequest {
+    /// Volume group (LVM) or thin pool (LVM-thin) name.
+    pub name: String,
+    /// Unused block device, e.g. `/dev/sdb`.
+    pub device: String,
+    pub add_storage: Option<bool>,
+    pub extra: PveParams,
+}
+
+impl LvmCreateRequest {
+    pub fn new(name: impl Into<String>, device: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            device: device.into(),
+            add_storage: None,
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("name", self.name.clone());
+        params.insert("device", self.device.clone());
+        if let Some(add_storage) = self.add_storage {
+            params.insert_bool("add_storage", add_storage);
+        }
+        params.extend(&self.extra);
+        params
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectoryFilesystem {
+    Ext4,
+    Xfs,
+}
+
+impl DirectoryFilesystem {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ext4 => "ext4",
+            Self::Xfs => "xfs",
+        }
+    }
+}
+
+impl fmt::Display for DirectoryFilesystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `POST /nodes/{node}/disks/directory`: formats `device` and mounts it under
+/// `/mnt/pve/{name}`.
+#[derive(Debug, Clone)]
+pub struct DirectoryCreateRequest {
+    pub name: String,
+    pub device: String,
+    /// PVE defaults to `ext4`.
+    pub filesystem: Option<DirectoryFilesystem>,
+    pub add_storage: Option<bool>,
+    pub extra: PveParams,
+}
+
+impl DirectoryCreateRequest {
+    pub fn new(name: impl Into<String>, device: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            device: device.into(),
+            filesystem: None,
+            add_storage: None,
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("name", self.name.clone());
+        params.insert("device", self.device.clone());
+        params.insert_opt("filesystem", self.filesystem.map(|v| v.to_string()));
+        if let Some(add_storage) = self.add_storage {
+            params.insert_bool("add_storage", add_storage);
+        }
+        params.extend(&self.extra);
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DirectoryCreateRequest, DirectoryFilesystem, LvmCreateRequest, ZfsCompression,
+        ZfsCreateRequest, ZfsPool, ZfsPoolDetail, ZfsRaidLevel,
+    };
+
+    #[test]
+    fn zfs_create_request_joins_devices() {
+        let mut req = ZfsCreateRequest::new("tank", ZfsRaidLevel::Mirror, ["/dev/sdb", "/dev/sdc"]);
+        req.ashift = Some(12);
+        req.compression = Some(ZfsCompression::Lz4);
+        req.add_storage = Some(true);
+
+        let params = req.to_params();
+        assert_eq!(params.get("devices"), Some("/dev/sdb,/dev/sdc"));
+        assert_eq!(params.get("raidlevel"), Some("mirror"));
+        assert_eq!(params.get("ashift"), Some("12"));
+        assert_eq!(params.get("compression"), Some("lz4"));
+        assert_eq!(params.get("add_storage"), Some("1"));
+    }
+
+    #[test]
+    fn lvm_and_directory_requests_map_options() {
+        let mut lvm = LvmCreateRequest::new("data", "/dev/sdd");
+        lvm.add_storage = Some(false);
+        let params = lvm.to_params();
+        assert_eq!(params.get("name"), Some("data"));
+        assert_eq!(params.get("device"), Some("/dev/sdd"));
+        assert_eq!(params.get("add_storage"), Some("0"));
+
+        let mut dir = DirectoryCreateRequest::new("backup", "/dev/sde");
+        dir.filesystem = Some(DirectoryFilesystem::Xfs);
+        let params = dir.to_params();
+        assert_eq!(params.get("filesystem"), Some("xfs"));
+        assert_eq!(params.get("add_storage"), None);
+    }
+
+    #[test]
+    fn zfs_pool_detail_finds_unhealthy_leaves() {
+        let pool: ZfsPool = serde_json::from_str(
+            r#"{"name":"tank","size":1000,"free":400,"alloc":600,"health":"DEGRADED","dedup":1.0,"frag":3}"#,
+        )
+        .expect("pool");
+        assert!(!pool.is_online());
+        assert_eq!(pool.frag, Some(3));
+
+        let detail: ZfsPoolDetail = serde_json::from_str(
+            r#"{"name":"tank","state":"DEGRADED","errors":"No known data errors","children":[
+                {"name":"tank","state":"DEGRADED","read":0,"write":0,"cksum":0,"children":[
+                    {"name":"mirror-0","state":"DEGRADED","children":[
+                        {"name":"sdb","state":"ONLINE","leaf":1},
+                        {"name":"sdc","state":"FAULTED","leaf":1,"msg":"too many errors","cksum":"12"}
+                    ]}
+                ]}
+            ]}"#,
+        )
+        .expect("detail");
+        let bad = detail.unhealthy_devices();
+        assert_eq!(bad.len(), 1);
+        assert_eq!(bad[0].name, "sdc");
+        assert_eq!(bad[0].cksum, Some(12));
+    }
+}
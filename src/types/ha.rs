@@ -0,0 +1,264 @@
+//! High-availability (HA) manager types.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::serde_helpers::{opt_bool_int, opt_number};
+
+/// Cluster HA state from `/cluster/ha/status/manager_status`, with the CRM and LRM views
+/// of each node merged into `node_status`.
+#[derive(Debug, Clone)]
+pub struct HaManagerStatus {
+    /// Node currently running the active CRM (HA master); `None` while no manager is elected.
+    pub manager_node: Option<String>,
+    pub quorate: bool,
+    /// Last manager status update (Unix seconds).
+    pub timestamp: Option<u64>,
+    pub node_status: HashMap<String, HaNodeState>,
+    /// Per-resource state as tracked by the manager, keyed by sid (e.g. `vm:100`).
+    pub service_status: HashMap<String, Value>,
+}
+
+impl HaManagerStatus {
+    /// Nodes the manager does not consider `online` (fenced, unknown, in maintenance, ...).
+    pub fn unhealthy_nodes(&self) -> impl Iterator<Item = (&str, &HaNodeState)> {
+        self.node_status
+            .iter()
+            .filter(|(_, state)| state.crm.as_deref() != Some("online"))
+            .map(|(node, state)| (node.as_str(), state))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HaNodeState {
+    /// Node state as seen by the CRM: `online`, `maintenance`, `unknown`, `fence`, `gone`.
+    pub crm: Option<String>,
+    /// LRM mode, e.g. `active` or `restart`.
+    pub lrm_mode: Option<String>,
+    /// LRM state, e.g. `active`, `wait_for_agent_lock`, `lost_agent_lock`.
+    pub lrm_state: Option<String>,
+    pub lrm_timestamp: Option<u64>,
+}
+
+/// Entry of `/cluster/ha/status/current`, tagged by its `type` field.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum HaStatusEntry {
+    Quorum(HaQuorumStatus),
+    /// The active CRM.
+    Master(HaMasterStatus),
+    /// Local resource manager of one node.
+    #[serde(rename = "lrm")]
+    Node(HaNodeStatus),
+    Service(HaServiceStatus),
+    /// Entry types added by newer PVE releases.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HaQuorumStatus {
+    pub node: Option<String>,
+    pub status: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub quorate: Option<u8>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl HaQuorumStatus {
+    pub fn is_quorate(&self) -> bool {
+        self.quorate == Some(1)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HaMasterStatus {
+    pub node: Option<String>,
+    /// Human-readable summary, e.g. `pve1 (active, Mon Jan 15 10:23:45 2024)`.
+    pub status: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub timestamp: Option<u64>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HaNodeStatus {
+    /// `lrm:{node}`.
+    pub id: Option<String>,
+    pub node: Option<String>,
+    /// Human-readable summary, e.g. `pve2 (active, Mon Jan 15 10:23:41 2024)`.
+    pub status: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub timestamp: Option<u64>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HaServiceStatus {
+    /// Resource id such as `vm:100` or `ct:200`.
+    pub sid: String,
+    pub node: Option<String>,
+    /// Requested state: `started`, `stopped`, `disabled`, `ignored`.
+    pub request_state: Option<String>,
+    /// State as tracked by the CRM, e.g. `started`, `migrate`, `fence`, `error`.
+    pub crm_state: Option<String>,
+    pub state: Option<String>,
+    pub group: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub max_restart: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub max_relocate: Option<u32>,
+    pub status: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl HaServiceStatus {
+    pub fn is_error(&self) -> bool {
+        self.state.as_deref() == Some("error") || self.crm_state.as_deref() == Some("error")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct RawManagerStatus {
+    #[serde(default)]
+    manager_status: RawCrmStatus,
+    #[serde(default)]
+    quorum: RawQuorum,
+    #[serde(default)]
+    lrm_status: HashMap<String, RawLrmStatus>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct RawCrmStatus {
+    master_node: Option<String>,
+    timestamp: Option<u64>,
+    #[serde(default)]
+    node_status: HashMap<String, String>,
+    #[serde(default)]
+    service_status: HashMap<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct RawQuorum {
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    quorate: Option<u8>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RawLrmStatus {
+    mode: Option<String>,
+    state: Option<String>,
+    timestamp: Option<u64>,
+}
+
+impl From<RawManagerStatus> for HaManagerStatus {
+    fn from(raw: RawManagerStatus) -> Self {
+        let mut node_status: HashMap<String, HaNodeState> = raw
+            .manager_status
+            .node_status
+            .into_iter()
+            .map(|(node, crm)| {
+                let state = HaNodeState {
+                    crm: Some(crm),
+                    ..HaNodeState::default()
+                };
+                (node, state)
+            })
+            .collect();
+        for (node, lrm) in raw.lrm_status {
+            let state = node_status.entry(node).or_default();
+            state.lrm_mode = lrm.mode;
+            state.lrm_state = lrm.state;
+            state.lrm_timestamp = lrm.timestamp;
+        }
+        Self {
+            manager_node: raw.manager_status.master_node,
+            quorate: raw.quorum.quorate == Some(1),
+            timestamp: raw.manager_status.timestamp,
+            node_status,
+            service_status: raw.manager_status.service_status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HaManagerStatus, HaStatusEntry, RawManagerStatus};
+
+    #[test]
+    fn manager_status_merges_crm_and_lrm_views() {
+        let raw: RawManagerStatus = serde_json::from_str(
+            r#"{
+                "manager_status": {
+                    "master_node": "pve1",
+                    "timestamp": 1705314225,
+                    "node_status": {"pve1": "online", "pve2": "online", "pve3": "fence"},
+                    "service_status": {"vm:100": {"node": "pve2", "state": "started"}}
+                },
+                "quorum": {"node": "pve1", "quorate": "1"},
+                "lrm_status": {
+                    "pve1": {"mode": "active", "state": "wait_for_agent_lock", "timestamp": 1705314220},
+                    "pve2": {"mode": "active", "state": "active", "timestamp": 1705314221}
+                }
+            }"#,
+        )
+        .expect("manager status");
+        let status = HaManagerStatus::from(raw);
+
+        assert_eq!(status.manager_node.as_deref(), Some("pve1"));
+        assert!(status.quorate);
+        assert_eq!(
+            status.node_status["pve2"].lrm_state.as_deref(),
+            Some("active")
+        );
+        assert_eq!(status.node_status["pve3"].lrm_state, None);
+        assert_eq!(
+            status.unhealthy_nodes().map(|(n, _)| n).collect::<Vec<_>>(),
+            ["pve3"]
+        );
+        assert!(status.service_status.contains_key("vm:100"));
+    }
+
+    #[test]
+    fn manager_status_tolerates_empty_cluster() {
+        let raw: RawManagerStatus =
+            serde_json::from_str(r#"{"quorum":{"quorate":0}}"#).expect("empty");
+        let status = HaManagerStatus::from(raw);
+        assert!(!status.quorate);
+        assert!(status.manager_node.is_none());
+        assert!(status.node_status.is_empty());
+    }
+
+    #[test]
+    fn status_current_entries_decode_by_type() {
+        let entries: Vec<HaStatusEntry> = serde_json::from_str(
+            r#"[
+                {"id":"quorum","type":"quorum","node":"pve1","status":"OK","quorate":1},
+                {"id":"master","type":"master","node":"pve1","status":"pve1 (active, Mon Jan 15 10:23:45 2024)","timestamp":1705314225},
+                {"id":"lrm:pve2","type":"lrm","node":"pve2","status":"pve2 (active, Mon Jan 15 10:23:41 2024)","timestamp":"1705314221"},
+                {"id":"service:vm:100","type":"service","sid":"vm:100","node":"pve2","state":"started","crm_state":"started","request_state":"started","max_restart":1,"max_relocate":1,"status":"vm:100 (pve2, started)"},
+                {"id":"fencing","type":"fencing","node":"pve1"}
+            ]"#,
+        )
+        .expect("status entries");
+        assert_eq!(entries.len(), 5);
+        assert!(matches!(&entries[0], HaStatusEntry::Quorum(q) if q.is_quorate()));
+        assert!(
+            matches!(&entries[1], HaStatusEntry::Master(m) if m.node.as_deref() == Some("pve1"))
+        );
+        assert!(matches!(&entries[2], HaStatusEntry::Node(n) if n.timestamp == Some(1705314221)));
+        let HaStatusEntry::Service(service) = &entries[3] else {
+            panic!("expected service entry");
+        };
+        assert_eq!(service.sid, "vm:100");
+        assert!(!service.is_error());
+        assert!(service.extra.contains_key("id"));
+        assert!(matches!(entries[4], HaStatusEntry::Unknown));
+    }
+}
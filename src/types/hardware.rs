@@ -0,0 +1,130 @@
+//! Host hardware types for PCI/USB passthrough discovery.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::serde_helpers::opt_bool_int;
+
+/// Entry of `/nodes/{node}/hardware/pci`. Ids are hex strings such as `0x10de`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PciDevice {
+    /// Bus address, e.g. `0000:01:00.0`.
+    pub id: String,
+    pub vendor: Option<String>,
+    pub device: Option<String>,
+    pub class: Option<String>,
+    pub vendor_name: Option<String>,
+    pub device_name: Option<String>,
+    pub subsystem_vendor: Option<String>,
+    pub subsystem_device: Option<String>,
+    /// `-1` when the host has no IOMMU enabled.
+    pub iommugroup: Option<i32>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub mdev: Option<u8>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl PciDevice {
+    /// Whether the device can be split into mediated devices (vGPU and similar).
+    pub fn supports_mdev(&self) -> bool {
+        self.mdev == Some(1)
+    }
+
+    /// Display controllers (PCI class `0x03xxxx`).
+    pub fn is_gpu(&self) -> bool {
+        self.class.as_deref().is_some_and(|c| c.starts_with("0x03"))
+    }
+}
+
+/// Mediated device type offered by a PCI device (`/nodes/{node}/hardware/pci/{id}/mdev`).
+/// `type_` is the value for a guest's `hostpciN: ...,mdev=<type>` option.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MdevType {
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Instances that can still be created.
+    pub available: u32,
+    pub description: Option<String>,
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Entry of `/nodes/{node}/hardware/usb`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UsbDevice {
+    pub busnum: Option<u32>,
+    pub devnum: Option<u32>,
+    pub port: Option<u32>,
+    pub level: Option<u32>,
+    pub class: Option<u32>,
+    pub vendid: Option<String>,
+    pub prodid: Option<String>,
+    pub speed: Option<String>,
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    pub serial: Option<String>,
+    pub usbpath: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl UsbDevice {
+    /// `vendor:product` id as accepted by a guest's `usbN: host=...` option.
+    pub fn id(&self) -> Option<String> {
+        let vendor = self.vendid.as_deref()?.trim_start_matches("0x");
+        let product = self.prodid.as_deref()?.trim_start_matches("0x");
+        Some(format!("{vendor}:{product}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MdevType, PciDevice, UsbDevice};
+
+    #[test]
+    fn pci_device_decodes_gpu_with_mdev() {
+        let dev: PciDevice = serde_json::from_value(serde_json::json!({
+            "id": "0000:01:00.0",
+            "class": "0x030000",
+            "vendor": "0x10de",
+            "device": "0x1eb8",
+            "vendor_name": "NVIDIA Corporation",
+            "iommugroup": 14,
+            "mdev": 1
+        }))
+        .expect("pci device");
+        assert!(dev.is_gpu());
+        assert!(dev.supports_mdev());
+        assert_eq!(dev.iommugroup, Some(14));
+    }
+
+    #[test]
+    fn usb_device_id_matches_host_option_format() {
+        let dev: UsbDevice = serde_json::from_value(serde_json::json!({
+            "busnum": 1,
+            "devnum": 3,
+            "vendid": "0x046d",
+            "prodid": "0xc52b",
+            "product": "USB Receiver",
+            "usbpath": "1-2"
+        }))
+        .expect("usb device");
+        assert_eq!(dev.id().as_deref(), Some("046d:c52b"));
+    }
+
+    #[test]
+    fn mdev_types_decode_type_and_availability() {
+        let types: Vec<MdevType> = serde_json::from_value(serde_json::json!([
+            {"type": "nvidia-256", "available": 4, "description": "num_heads=4, frl_config=60"},
+            {"type": "nvidia-257", "available": 0}
+        ]))
+        .expect("mdev types");
+        assert_eq!(types[0].type_, "nvidia-256");
+        assert_eq!(types[0].available, 4);
+        assert_eq!(types[1].description, None);
+    }
+}
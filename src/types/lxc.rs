@@ -5,37 +5,96 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::error::PveError;
 use crate::params::PveParams;
 pub use crate::types::common::SnapshotInfo;
+use crate::types::common::{Bandwidth, GuestLock, format_bytes};
+use crate::types::serde_helpers::opt_number;
+use crate::validate;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct LxcSummary {
     pub vmid: u32,
     pub name: Option<String>,
     pub status: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub cpu: Option<f64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub mem: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub maxmem: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub maxdisk: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub uptime: Option<u64>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
+impl LxcSummary {
+    pub fn mem_human(&self) -> Option<String> {
+        self.mem.map(format_bytes)
+    }
+
+    pub fn maxmem_human(&self) -> Option<String> {
+        self.maxmem.map(format_bytes)
+    }
+
+    pub fn maxdisk_human(&self) -> Option<String> {
+        self.maxdisk.map(format_bytes)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct LxcStatus {
     pub vmid: Option<u32>,
     pub name: Option<String>,
     pub status: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub cpu: Option<f64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub mem: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub maxmem: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub maxdisk: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub netin: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub netout: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub diskread: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub diskwrite: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub uptime: Option<u64>,
+    /// Raw `lock` value; see `lock_state`.
+    pub lock: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl LxcStatus {
+    /// The operation holding the container lock, if any.
+    pub fn lock_state(&self) -> Option<GuestLock> {
+        self.lock.as_deref().map(GuestLock::from)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.lock.is_some()
+    }
+}
+
+/// Entry of the appliance template index (`/nodes/{node}/aplinfo`).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Appliance {
+    pub template: String,
+    pub os: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub sha512sum: Option<String>,
+    pub section: Option<String>,
+    pub headline: Option<String>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
@@ -74,6 +133,18 @@ impl LxcCreateRequest {
         }
     }
 
+    /// Checks the vmid range, the hostname and any `hwaddr` in `net0`.
+    pub fn validate(&self) -> Result<(), PveError> {
+        validate::validate_vmid(self.vmid)?;
+        if let Some(hostname) = self.hostname.as_deref() {
+            validate::validate_hostname(hostname)?;
+        }
+        if let Some(net0) = self.net0.as_deref() {
+            validate::validate_net_mac(net0, &["hwaddr"], false)?;
+        }
+        Ok(())
+    }
+
     pub fn to_params(&self) -> PveParams {
         let mut params = PveParams::new();
         params.insert("vmid", self.vmid.to_string());
@@ -154,6 +225,32 @@ impl LxcSetConfigRequest {
     }
 }
 
+/// Options for destroying a container. `force` destroys it even while running.
+#[derive(Debug, Clone, Default)]
+pub struct LxcDeleteRequest {
+    pub purge: Option<bool>,
+    pub destroy_unreferenced_disks: Option<bool>,
+    pub force: Option<bool>,
+    pub extra: PveParams,
+}
+
+impl LxcDeleteRequest {
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        if let Some(purge) = self.purge {
+            params.insert_bool("purge", purge);
+        }
+        if let Some(destroy) = self.destroy_unreferenced_disks {
+            params.insert_bool("destroy-unreferenced-disks", destroy);
+        }
+        if let Some(force) = self.force {
+            params.insert_bool("force", force);
+        }
+        params.extend(&self.extra);
+        params
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct LxcActionRequest {
     pub timeout: Option<u64>,
@@ -256,6 +353,11 @@ impl LxcMigrateRequest {
         }
     }
 
+    pub fn bandwidth(mut self, bandwidth: Bandwidth) -> Self {
+        self.bwlimit = Some(bandwidth.to_kib());
+        self
+    }
+
     pub fn to_params(&self) -> PveParams {
         let mut params = PveParams::new();
         params.insert("target", self.target.clone());
@@ -272,3 +374,150 @@ impl LxcMigrateRequest {
         params
     }
 }
+
+/// Clones a container or container template. Linked clones (`full: Some(false)`) are only
+/// possible from templates.
+#[derive(Debug, Clone)]
+pub struct LxcCloneRequest {
+    pub newid: u32,
+    pub hostname: Option<String>,
+    /// Target node; requires shared storage for the container volumes.
+    pub target: Option<String>,
+    pub storage: Option<String>,
+    pub full: Option<bool>,
+    pub pool: Option<String>,
+    pub snapname: Option<String>,
+    /// KiB/s; see `bandwidth` for a unit-checked setter.
+    pub bwlimit: Option<u64>,
+    pub description: Option<String>,
+    pub extra: PveParams,
+}
+
+impl LxcCloneRequest {
+    pub fn new(newid: u32) -> Self {
+        Self {
+            newid,
+            hostname: None,
+            target: None,
+            storage: None,
+            full: None,
+            pool: None,
+            snapname: None,
+            bwlimit: None,
+            description: None,
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn bandwidth(mut self, bandwidth: Bandwidth) -> Self {
+        self.bwlimit = Some(bandwidth.to_kib());
+        self
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("newid", self.newid.to_string());
+        params.insert_opt("hostname", self.hostname.clone());
+        params.insert_opt("target", self.target.clone());
+        params.insert_opt("storage", self.storage.clone());
+        if let Some(full) = self.full {
+            params.insert_bool("full", full);
+        }
+        params.insert_opt("pool", self.pool.clone());
+        params.insert_opt("snapname", self.snapname.clone());
+        params.insert_opt("bwlimit", self.bwlimit.map(|v| v.to_string()));
+        params.insert_opt("description", self.description.clone());
+        params.extend(&self.extra);
+        params
+    }
+}
+
+/// Moves a rootfs or mount point to another storage, or to another container via
+/// `target_vmid`.
+#[derive(Debug, Clone)]
+pub struct LxcMoveVolumeRequest {
+    /// Volume key, e.g. `rootfs` or `mp0`.
+    pub volume: String,
+    pub storage: Option<String>,
+    /// Remove the source volume after a successful copy.
+    pub delete: Option<bool>,
+    pub bwlimit: Option<u64>,
+    pub target_vmid: Option<u32>,
+    pub target_volume: Option<String>,
+    pub digest: Option<String>,
+    pub extra: PveParams,
+}
+
+impl LxcMoveVolumeRequest {
+    pub fn new(volume: impl Into<String>) -> Self {
+        Self {
+            volume: volume.into(),
+            storage: None,
+            delete: None,
+            bwlimit: None,
+            target_vmid: None,
+            target_volume: None,
+            digest: None,
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn to_storage(volume: impl Into<String>, storage: impl Into<String>) -> Self {
+        let mut request = Self::new(volume);
+        request.storage = Some(storage.into());
+        request
+    }
+
+    pub fn bandwidth(mut self, bandwidth: Bandwidth) -> Self {
+        self.bwlimit = Some(bandwidth.to_kib());
+        self
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("volume", self.volume.clone());
+        params.insert_opt("storage", self.storage.clone());
+        if let Some(delete) = self.delete {
+            params.insert_bool("delete", delete);
+        }
+        params.insert_opt("bwlimit", self.bwlimit.map(|v| v.to_string()));
+        params.insert_opt("target-vmid", self.target_vmid.map(|v| v.to_string()));
+        params.insert_opt("target-volume", self.target_volume.clone());
+        params.insert_opt("digest", self.digest.clone());
+        params.extend(&self.extra);
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LxcCloneRequest, LxcMoveVolumeRequest};
+    use crate::types::common::Bandwidth;
+
+    #[test]
+    fn move_volume_maps_target_keys() {
+        let mut req = LxcMoveVolumeRequest::to_storage("mp0", "fast")
+            .bandwidth(Bandwidth::kbytes_per_sec(10240));
+        req.delete = Some(true);
+        req.target_vmid = Some(201);
+        let params = req.to_params();
+        assert_eq!(params.get("volume"), Some("mp0"));
+        assert_eq!(params.get("storage"), Some("fast"));
+        assert_eq!(params.get("delete"), Some("1"));
+        assert_eq!(params.get("bwlimit"), Some("10240"));
+        assert_eq!(params.get("target-vmid"), Some("201"));
+    }
+
+    #[test]
+    fn clone_maps_hostname_and_full() {
+        let mut req = LxcCloneRequest::new(301);
+        req.hostname = Some("ct-301".to_string());
+        req.full = Some(true);
+        req.target = Some("pve2".to_string());
+        let params = req.to_params();
+        assert_eq!(params.get("newid"), Some("301"));
+        assert_eq!(params.get("hostname"), Some("ct-301"));
+        assert_eq!(params.get("full"), Some("1"));
+        assert_eq!(params.get("target"), Some("pve2"));
+    }
+}
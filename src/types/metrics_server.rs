@@ -0,0 +1,264 @@
+//! External metrics server (`/cluster/metrics/server`) types.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::params::PveParams;
+use crate::types::serde_helpers::{opt_bool_int, opt_number};
+
+#[derive(Debug, Clone, Copy)]
+pub enum MetricsServerType {
+    Influxdb,
+    Graphite,
+}
+
+impl MetricsServerType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Influxdb => "influxdb",
+            Self::Graphite => "graphite",
+        }
+    }
+}
+
+impl fmt::Display for MetricsServerType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Transport for InfluxDB (`influxdbproto`) and Graphite (`proto`) servers.
+#[derive(Debug, Clone, Copy)]
+pub enum MetricsProtocol {
+    Udp,
+    Tcp,
+    Http,
+    Https,
+}
+
+impl MetricsProtocol {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Udp => "udp",
+            Self::Tcp => "tcp",
+            Self::Http => "http",
+            Self::Https => "https",
+        }
+    }
+}
+
+impl fmt::Display for MetricsProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MetricsServer {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub server: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub port: Option<u16>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub disable: Option<u8>,
+    pub digest: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl MetricsServer {
+    pub fn is_enabled(&self) -> bool {
+        self.disable != Some(1)
+    }
+}
+
+/// Plugin settings shared by `MetricsServerRequest` and `MetricsServerUpdate`. Fields only
+/// one plugin accepts are rejected by PVE for the other. `Debug` redacts `token`.
+#[derive(Clone, Default)]
+pub struct MetricsServerOptions {
+    pub disable: Option<bool>,
+    pub mtu: Option<u32>,
+    /// Graphite TCP / InfluxDB HTTP timeout in seconds.
+    pub timeout: Option<u32>,
+    /// Graphite metric path prefix.
+    pub path: Option<String>,
+    /// Graphite transport.
+    pub proto: Option<MetricsProtocol>,
+    /// InfluxDB transport.
+    pub influxdbproto: Option<MetricsProtocol>,
+    pub organization: Option<String>,
+    pub bucket: Option<String>,
+    /// InfluxDB v2 API token.
+    pub token: Option<String>,
+    pub verify_certificate: Option<bool>,
+}
+
+impl MetricsServerOptions {
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        if let Some(disable) = self.disable {
+            params.insert_bool("disable", disable);
+        }
+        params.insert_opt("mtu", self.mtu.map(|v| v.to_string()));
+        params.insert_opt("timeout", self.timeout.map(|v| v.to_string()));
+        params.insert_opt("path", self.path.clone());
+        params.insert_opt("proto", self.proto.map(|v| v.to_string()));
+        params.insert_opt("influxdbproto", self.influxdbproto.map(|v| v.to_string()));
+        params.insert_opt("organization", self.organization.clone());
+        params.insert_opt("bucket", self.bucket.clone());
+        params.insert_opt("token", self.token.clone());
+        if let Some(verify) = self.verify_certificate {
+            params.insert_bool("verify-certificate", verify);
+        }
+        params
+    }
+}
+
+impl fmt::Debug for MetricsServerOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetricsServerOptions")
+            .field("disable", &self.disable)
+            .field("mtu", &self.mtu)
+            .field("timeout", &self.timeout)
+            .field("path", &self.path)
+            .field("proto", &self.proto)
+            .field("influxdbproto", &self.influxdbproto)
+            .field("organization", &self.organization)
+            .field("bucket", &self.bucket)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .field("verify_certificate", &self.verify_certificate)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricsServerRequest {
+    pub id: String,
+    pub type_: MetricsServerType,
+    pub server: String,
+    pub port: u16,
+    pub options: MetricsServerOptions,
+    pub extra: PveParams,
+}
+
+impl MetricsServerRequest {
+    pub fn new(
+        id: impl Into<String>,
+        type_: MetricsServerType,
+        server: impl Into<String>,
+        port: u16,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            type_,
+            server: server.into(),
+            port,
+            options: MetricsServerOptions::default(),
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn influxdb(id: impl Into<String>, server: impl Into<String>, port: u16) -> Self {
+        Self::new(id, MetricsServerType::Influxdb, server, port)
+    }
+
+    pub fn graphite(id: impl Into<String>, server: impl Into<String>, port: u16) -> Self {
+        Self::new(id, MetricsServerType::Graphite, server, port)
+    }
+
+    /// Form parameters; `id` is part of the path and not included.
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("type", self.type_.to_string());
+        params.insert("server", self.server.clone());
+        params.insert("port", self.port.to_string());
+        params.extend(&self.options.to_params());
+        params.extend(&self.extra);
+        params
+    }
+}
+
+/// Changes to an existing server; the plugin type cannot be changed.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsServerUpdate {
+    pub server: Option<String>,
+    pub port: Option<u16>,
+    pub options: MetricsServerOptions,
+    pub delete: Option<String>,
+    pub digest: Option<String>,
+    pub extra: PveParams,
+}
+
+impl MetricsServerUpdate {
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert_opt("server", self.server.clone());
+        params.insert_opt("port", self.port.map(|v| v.to_string()));
+        params.extend(&self.options.to_params());
+        params.insert_opt("delete", self.delete.clone());
+        params.insert_opt("digest", self.digest.clone());
+        params.extend(&self.extra);
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        MetricsProtocol, MetricsServer, MetricsServerOptions, MetricsServerRequest,
+        MetricsServerUpdate,
+    };
+
+    #[test]
+    fn metrics_server_request_maps_plugin_options() {
+        let mut req = MetricsServerRequest::influxdb("influx", "10.0.0.5", 8086);
+        req.options.influxdbproto = Some(MetricsProtocol::Https);
+        req.options.bucket = Some("pve".to_string());
+        req.options.verify_certificate = Some(false);
+
+        let params = req.to_params();
+        assert_eq!(params.get("type"), Some("influxdb"));
+        assert_eq!(params.get("port"), Some("8086"));
+        assert_eq!(params.get("influxdbproto"), Some("https"));
+        assert_eq!(params.get("verify-certificate"), Some("0"));
+        assert_eq!(params.get("id"), None);
+
+        let update = MetricsServerUpdate {
+            options: MetricsServerOptions {
+                disable: Some(true),
+                ..Default::default()
+            },
+            delete: Some("bucket".to_string()),
+            ..Default::default()
+        };
+        let params = update.to_params();
+        assert_eq!(params.get("disable"), Some("1"));
+        assert_eq!(params.get("type"), None);
+    }
+
+    #[test]
+    fn metrics_server_request_redacts_token_in_debug() {
+        let mut req = MetricsServerRequest::influxdb("influx", "10.0.0.5", 8086);
+        req.options.token = Some("influx-secret".to_string());
+        assert_eq!(req.to_params().get("token"), Some("influx-secret"));
+        let debug = format!("{req:?}");
+        assert!(!debug.contains("influx-secret"));
+        assert!(debug.contains("<redacted>"));
+    }
+
+    #[test]
+    fn metrics_server_decodes_list_entry() {
+        let server: MetricsServer = serde_json::from_str(
+            r#"{"id":"graphite","type":"graphite","server":"10.0.0.6","port":"2003","disable":1,"path":"proxmox"}"#,
+        )
+        .expect("server");
+        assert_eq!(server.port, Some(2003));
+        assert!(!server.is_enabled());
+        assert_eq!(server.extra["path"], "proxmox");
+    }
+}
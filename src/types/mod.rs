@@ -5,11 +5,20 @@
 
 pub mod access;
 pub mod backup;
+pub mod ceph;
 pub mod cluster;
 pub mod common;
+pub mod console;
 pub mod datacenter;
+pub mod disk;
+pub mod ha;
+pub mod hardware;
 pub mod lxc;
+pub mod metrics_server;
 pub mod node;
+pub mod notifications;
 pub mod qemu;
+pub mod sdn;
+pub(crate) mod serde_helpers;
 pub mod storage;
 pub mod task;
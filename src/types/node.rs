@@ -7,14 +7,20 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::params::PveParams;
+use crate::types::serde_helpers::{opt_bool_int, opt_number};
+use crate::types::task::{TaskType, task_kind};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct NodeSummary {
     pub node: String,
     pub status: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub cpu: Option<f64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub mem: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub maxmem: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub uptime: Option<u64>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
@@ -25,7 +31,9 @@ pub struct NetworkInterface {
     pub iface: Option<String>,
     #[serde(rename = "type")]
     pub interface_type: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
     pub active: Option<u8>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
     pub autostart: Option<u8>,
     pub address: Option<String>,
     pub cidr: Option<String>,
@@ -35,6 +43,16 @@ pub struct NetworkInterface {
     pub extra: HashMap<String, Value>,
 }
 
+impl NetworkInterface {
+    pub fn is_active(&self) -> bool {
+        self.active == Some(1)
+    }
+
+    pub fn is_autostart(&self) -> bool {
+        self.autostart == Some(1)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct NodeTask {
     pub upid: String,
@@ -48,6 +66,12 @@ pub struct NodeTask {
     pub extra: HashMap<String, Value>,
 }
 
+impl NodeTask {
+    pub fn kind(&self) -> TaskType {
+        task_kind(self.task_type.as_deref(), Some(&self.upid))
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum TaskSource {
     Archive,
@@ -148,6 +172,11 @@ impl NodeTasksQuery {
         self
     }
 
+    /// Typed form of `typefilter`.
+    pub fn type_filter_kind(self, kind: TaskType) -> Self {
+        self.typefilter(kind.as_str())
+    }
+
     pub fn until(mut self, until: u64) -> Self {
         self.until = Some(until);
         self
@@ -209,3 +238,232 @@ impl NodeNetworkQuery {
         params
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkInterfaceType {
+    Bridge,
+    Bond,
+    Eth,
+    Alias,
+    Vlan,
+    OvsBridge,
+    OvsBond,
+    OvsPort,
+    OvsIntPort,
+}
+
+impl NetworkInterfaceType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Bridge => "bridge",
+            Self::Bond => "bond",
+            Self::Eth => "eth",
+            Self::Alias => "alias",
+            Self::Vlan => "vlan",
+            Self::OvsBridge => "OVSBridge",
+            Self::OvsBond => "OVSBond",
+            Self::OvsPort => "OVSPort",
+            Self::OvsIntPort => "OVSIntPort",
+        }
+    }
+}
+
+impl fmt::Display for NetworkInterfaceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Interface settings shared by `NetworkCreateRequest` and `NetworkUpdateRequest`.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkInterfaceOptions {
+    /// Space-separated member ports of a bridge.
+    pub bridge_ports: Option<String>,
+    pub bridge_vlan_aware: Option<bool>,
+    /// Space-separated member interfaces of a bond.
+    pub bond_slaves: Option<String>,
+    pub bond_mode: Option<String>,
+    pub address: Option<String>,
+    pub cidr: Option<String>,
+    pub gateway: Option<String>,
+    pub autostart: Option<bool>,
+    pub mtu: Option<u32>,
+    pub comments: Option<String>,
+}
+
+impl NetworkInterfaceOptions {
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert_opt("bridge_ports", self.bridge_ports.clone());
+        if let Some(vlan_aware) = self.bridge_vlan_aware {
+            params.insert_bool("bridge_vlan_aware", vlan_aware);
+        }
+        params.insert_opt("slaves", self.bond_slaves.clone());
+        params.insert_opt("bond_mode", self.bond_mode.clone());
+        params.insert_opt("address", self.address.clone());
+        params.insert_opt("cidr", self.cidr.clone());
+        params.insert_opt("gateway", self.gateway.clone());
+        if let Some(autostart) = self.autostart {
+            params.insert_bool("autostart", autostart);
+        }
+        params.insert_opt("mtu", self.mtu.map(|v| v.to_string()));
+        params.insert_opt("comments", self.comments.clone());
+        params
+    }
+}
+
+/// New interface definition. Changes are staged in `interfaces.new` until
+/// `node_apply_network` reloads the configuration.
+#[derive(Debug, Clone)]
+pub struct NetworkCreateRequest {
+    pub iface: String,
+    pub type_: NetworkInterfaceType,
+    pub options: NetworkInterfaceOptions,
+    pub extra: PveParams,
+}
+
+impl NetworkCreateRequest {
+    pub fn new(iface: impl Into<String>, type_: NetworkInterfaceType) -> Self {
+        Self {
+            iface: iface.into(),
+            type_,
+            options: NetworkInterfaceOptions::default(),
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("iface", self.iface.clone());
+        params.insert("type", self.type_.as_str());
+        params.extend(&self.options.to_params());
+        params.extend(&self.extra);
+        params
+    }
+}
+
+/// Interface update. PVE requires the interface `type` on every update.
+#[derive(Debug, Clone)]
+pub struct NetworkUpdateRequest {
+    pub type_: NetworkInterfaceType,
+    pub options: NetworkInterfaceOptions,
+    pub delete: Option<String>,
+    /// Config digest from `node_network`; the update fails if the file changed since.
+    pub digest: Option<String>,
+    pub extra: PveParams,
+}
+
+impl NetworkUpdateRequest {
+    pub fn new(type_: NetworkInterfaceType) -> Self {
+        Self {
+            type_,
+            options: NetworkInterfaceOptions::default(),
+            delete: None,
+            digest: None,
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("type", self.type_.as_str());
+        params.extend(&self.options.to_params());
+        params.insert_opt("delete", self.delete.clone());
+        params.insert_opt("digest", self.digest.clone());
+        params.extend(&self.extra);
+        params
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SyslogLine {
+    pub n: Option<u64>,
+    pub t: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SyslogQuery {
+    pub start: Option<u64>,
+    pub limit: Option<u64>,
+    /// `YYYY-MM-DD HH:MM:SS`, as accepted by PVE.
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub service: Option<String>,
+}
+
+impl SyslogQuery {
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert_opt("start", self.start.map(|v| v.to_string()));
+        params.insert_opt("limit", self.limit.map(|v| v.to_string()));
+        params.insert_opt("since", self.since.clone());
+        params.insert_opt("until", self.until.clone());
+        params.insert_opt("service", self.service.clone());
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct JournalQuery {
+    /// Unix epoch seconds.
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    pub lastentries: Option<u64>,
+}
+
+impl JournalQuery {
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert_opt("since", self.since.map(|v| v.to_string()));
+        params.insert_opt("until", self.until.map(|v| v.to_string()));
+        params.insert_opt("lastentries", self.lastentries.map(|v| v.to_string()));
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        NetworkCreateRequest, NetworkInterfaceType, NetworkUpdateRequest, NodeTasksQuery, TaskType,
+    };
+
+    #[test]
+    fn network_create_maps_bridge_fields() {
+        let mut req = NetworkCreateRequest::new("vmbr1", NetworkInterfaceType::Bridge);
+        req.options.bridge_ports = Some("eno2".to_string());
+        req.options.bridge_vlan_aware = Some(true);
+        req.options.cidr = Some("10.0.0.2/24".to_string());
+        req.options.autostart = Some(true);
+        let params = req.to_params();
+        assert_eq!(params.get("iface"), Some("vmbr1"));
+        assert_eq!(params.get("type"), Some("bridge"));
+        assert_eq!(params.get("bridge_ports"), Some("eno2"));
+        assert_eq!(params.get("bridge_vlan_aware"), Some("1"));
+        assert_eq!(params.get("cidr"), Some("10.0.0.2/24"));
+        assert_eq!(params.get("autostart"), Some("1"));
+    }
+
+    #[test]
+    fn network_update_always_sends_type() {
+        let mut req = NetworkUpdateRequest::new(NetworkInterfaceType::Bond);
+        req.options.bond_slaves = Some("eno1 eno2".to_string());
+        req.options.bond_mode = Some("802.3ad".to_string());
+        req.delete = Some("gateway".to_string());
+        req.digest = Some("abc123".to_string());
+        let params = req.to_params();
+        assert_eq!(params.get("type"), Some("bond"));
+        assert_eq!(params.get("slaves"), Some("eno1 eno2"));
+        assert_eq!(params.get("bond_mode"), Some("802.3ad"));
+        assert_eq!(params.get("delete"), Some("gateway"));
+        assert_eq!(params.get("digest"), Some("abc123"));
+        assert_eq!(params.get("iface"), None);
+    }
+
+    #[test]
+    fn tasks_query_type_filter_uses_wire_name() {
+        let params = NodeTasksQuery::default()
+            .type_filter_kind(TaskType::QmMigrate)
+            .to_params();
+        assert_eq!(params.get("typefilter"), Some("qmigrate"));
+    }
+}
@@ -0,0 +1,487 @@
+//! Notification system (`/cluster/notifications`, PVE 8.1+) types.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::PveError;
+use crate::params::PveParams;
+use crate::types::serde_helpers::{opt_bool_int, opt_number, string_list};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationEndpointType {
+    Sendmail,
+    Smtp,
+    Gotify,
+    Webhook,
+}
+
+impl NotificationEndpointType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sendmail => "sendmail",
+            Self::Smtp => "smtp",
+            Self::Gotify => "gotify",
+            Self::Webhook => "webhook",
+        }
+    }
+}
+
+impl fmt::Display for NotificationEndpointType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SmtpMode {
+    Insecure,
+    Starttls,
+    Tls,
+}
+
+impl SmtpMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Insecure => "insecure",
+            Self::Starttls => "starttls",
+            Self::Tls => "tls",
+        }
+    }
+}
+
+impl fmt::Display for SmtpMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether a matcher fires when all or any of its `match-*` rules apply.
+#[derive(Debug, Clone, Copy)]
+pub enum MatcherMode {
+    All,
+    Any,
+}
+
+impl MatcherMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Any => "any",
+        }
+    }
+}
+
+impl fmt::Display for MatcherMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Entry of `/cluster/notifications/targets`: every endpoint a matcher can route to.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NotificationTarget {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub comment: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub disable: Option<u8>,
+    /// `builtin`, `user-created` or `modified-builtin`.
+    pub origin: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl NotificationTarget {
+    pub fn is_enabled(&self) -> bool {
+        self.disable != Some(1)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NotificationMatcher {
+    pub name: String,
+    #[serde(default, deserialize_with = "string_list")]
+    pub target: Vec<String>,
+    /// Rules such as `exact:type=vzdump` or `regex:hostname=^pve`.
+    #[serde(rename = "match-field", default, deserialize_with = "string_list")]
+    pub match_field: Vec<String>,
+    /// Comma-separated severities per entry, e.g. `warning,error`.
+    #[serde(rename = "match-severity", default, deserialize_with = "string_list")]
+    pub match_severity: Vec<String>,
+    #[serde(rename = "match-calendar", default, deserialize_with = "string_list")]
+    pub match_calendar: Vec<String>,
+    pub mode: Option<String>,
+    #[serde(rename = "invert-match", default, deserialize_with = "opt_bool_int")]
+    pub invert_match: Option<u8>,
+    pub comment: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub disable: Option<u8>,
+    pub origin: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl NotificationMatcher {
+    pub fn is_enabled(&self) -> bool {
+        self.disable != Some(1)
+    }
+}
+
+/// Endpoint created with `POST /cluster/notifications/endpoints/{type}`. Use the
+/// per-type constructors; `validate` rejects options PVE does not accept for the chosen
+/// type. `Debug` redacts `password` and `token`.
+#[derive(Clone)]
+pub struct NotificationEndpointRequest {
+    pub type_: NotificationEndpointType,
+    pub name: String,
+    /// Recipient addresses (sendmail, smtp).
+    pub mailto: Vec<String>,
+    /// PVE users whose configured email is used (sendmail, smtp).
+    pub mailto_user: Vec<String>,
+    pub from_address: Option<String>,
+    pub author: Option<String>,
+    /// SMTP relay host or Gotify base URL.
+    pub server: Option<String>,
+    pub port: Option<u16>,
+    pub mode: Option<SmtpMode>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Gotify application token.
+    pub token: Option<String>,
+    /// Webhook URL; may contain `{{ secrets.* }}` templates.
+    pub url: Option<String>,
+    pub method: Option<String>,
+    pub comment: Option<String>,
+    pub disable: Option<bool>,
+    pub extra: PveParams,
+}
+
+impl NotificationEndpointRequest {
+    pub fn new(type_: NotificationEndpointType, name: impl Into<String>) -> Self {
+        Self {
+            type_,
+            name: name.into(),
+            mailto: Vec::new(),
+            mailto_user: Vec::new(),
+            from_address: None,
+            author: None,
+            server: None,
+            port: None,
+            mode: None,
+            username: None,
+            password: None,
+            token: None,
+            url: None,
+            method: None,
+            comment: None,
+            disable: None,
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn sendmail(name: impl Into<String>) -> Self {
+        Self::new(NotificationEndpointType::Sendmail, name)
+    }
+
+    pub fn smtp(
+        name: impl Into<String>,
+        server: impl Into<String>,
+        from_address: impl Into<String>,
+    ) -> Self {
+        let mut request = Self::new(NotificationEndpointType::Smtp, name);
+        request.server = Some(server.into());
+        request.from_address = Some(from_address.into());
+        request
+    }
+
+    pub fn gotify(
+        name: impl Into<String>,
+        server: impl Into<String>,
+        token: impl Into<String>,
+    ) -> Self {
+        let mut request = Self::new(NotificationEndpointType::Gotify, name);
+        request.server = Some(server.into());
+        request.token = Some(token.into());
+        request
+    }
+
+    pub fn webhook(name: impl Into<String>, url: impl Into<String>) -> Self {
+        let mut request = Self::new(NotificationEndpointType::Webhook, name);
+        request.url = Some(url.into());
+        request
+    }
+
+    /// Rejects typed options that do not belong to `type_`, e.g. a `token` on a sendmail
+    /// endpoint. `extra` is not checked.
+    pub fn validate(&self) -> Result<(), PveError> {
+        use NotificationEndpointType::{Gotify, Sendmail, Smtp, Webhook};
+
+        let set = [
+            ("mailto", !self.mailto.is_empty(), &[Sendmail, Smtp][..]),
+            (
+                "mailto-user",
+                !self.mailto_user.is_empty(),
+                &[Sendmail, Smtp],
+            ),
+            (
+                "from-address",
+                self.from_address.is_some(),
+                &[Sendmail, Smtp],
+            ),
+            ("author", self.author.is_some(), &[Sendmail, Smtp]),
+            ("server", self.server.is_some(), &[Smtp, Gotify]),
+            ("port", self.port.is_some(), &[Smtp]),
+            ("mode", self.mode.is_some(), &[Smtp]),
+            ("username", self.username.is_some(), &[Smtp]),
+            ("password", self.password.is_some(), &[Smtp]),
+            ("token", self.token.is_some(), &[Gotify]),
+            ("url", self.url.is_some(), &[Webhook]),
+            ("method", self.method.is_some(), &[Webhook]),
+        ];
+        let rejected: Vec<&str> = set
+            .iter()
+            .filter(|(_, present, types)| *present && !types.contains(&self.type_))
+            .map(|(name, _, _)| *name)
+            .collect();
+        if !rejected.is_empty() {
+            return Err(PveError::InvalidArgument(format!(
+                "{} endpoint does not accept: {}",
+                self.type_,
+                rejected.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("name", self.name.clone());
+        for mailto in &self.mailto {
+            params.push("mailto", mailto.clone());
+        }
+        for user in &self.mailto_user {
+            params.push("mailto-user", user.clone());
+        }
+        params.insert_opt("from-address", self.from_address.clone());
+        params.insert_opt("author", self.author.clone());
+        params.insert_opt("server", self.server.clone());
+        params.insert_opt("port", self.port.map(|v| v.to_string()));
+        params.insert_opt("mode", self.mode.map(|v| v.to_string()));
+        params.insert_opt("username", self.username.clone());
+        params.insert_opt("password", self.password.clone());
+        params.insert_opt("token", self.token.clone());
+        params.insert_opt("url", self.url.clone());
+        params.insert_opt("method", self.method.clone());
+        params.insert_opt("comment", self.comment.clone());
+        if let Some(disable) = self.disable {
+            params.insert_bool("disable", disable);
+        }
+        params.extend(&self.extra);
+        params
+    }
+}
+
+impl fmt::Debug for NotificationEndpointRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotificationEndpointRequest")
+            .field("type_", &self.type_)
+            .field("name", &self.name)
+            .field("mailto", &self.mailto)
+            .field("mailto_user", &self.mailto_user)
+            .field("from_address", &self.from_address)
+            .field("author", &self.author)
+            .field("server", &self.server)
+            .field("port", &self.port)
+            .field("mode", &self.mode)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .field("url", &self.url)
+            .field("method", &self.method)
+            .field("comment", &self.comment)
+            .field("disable", &self.disable)
+            .field("extra", &self.extra)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NotificationMatcherRequest {
+    pub name: String,
+    pub target: Vec<String>,
+    pub match_field: Vec<String>,
+    pub match_severity: Vec<String>,
+    pub match_calendar: Vec<String>,
+    pub mode: Option<MatcherMode>,
+    pub invert_match: Option<bool>,
+    pub comment: Option<String>,
+    pub disable: Option<bool>,
+    pub extra: PveParams,
+}
+
+impl NotificationMatcherRequest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            target: Vec::new(),
+            match_field: Vec::new(),
+            match_severity: Vec::new(),
+            match_calendar: Vec::new(),
+            mode: None,
+            invert_match: None,
+            comment: None,
+            disable: None,
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target.push(target.into());
+        self
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("name", self.name.clone());
+        for target in &self.target {
+            params.push("target", target.clone());
+        }
+        for rule in &self.match_field {
+            params.push("match-field", rule.clone());
+        }
+        for rule in &self.match_severity {
+            params.push("match-severity", rule.clone());
+        }
+        for rule in &self.match_calendar {
+            params.push("match-calendar", rule.clone());
+        }
+        params.insert_opt("mode", self.mode.map(|v| v.to_string()));
+        if let Some(invert) = self.invert_match {
+            params.insert_bool("invert-match", invert);
+        }
+        params.insert_opt("comment", self.comment.clone());
+        if let Some(disable) = self.disable {
+            params.insert_bool("disable", disable);
+        }
+        params.extend(&self.extra);
+        params
+    }
+}
+
+/// Endpoint as returned by `GET /cluster/notifications/endpoints/{type}`; secrets such as
+/// passwords and tokens are never included.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NotificationEndpoint {
+    pub name: String,
+    #[serde(default, deserialize_with = "string_list")]
+    pub mailto: Vec<String>,
+    #[serde(rename = "mailto-user", default, deserialize_with = "string_list")]
+    pub mailto_user: Vec<String>,
+    #[serde(rename = "from-address")]
+    pub from_address: Option<String>,
+    pub server: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub port: Option<u16>,
+    pub comment: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub disable: Option<u8>,
+    pub origin: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        MatcherMode, NotificationEndpointRequest, NotificationMatcher, NotificationMatcherRequest,
+        SmtpMode,
+    };
+
+    #[test]
+    fn endpoint_request_redacts_secrets_in_debug() {
+        let mut req =
+            NotificationEndpointRequest::smtp("mail", "smtp.example.com", "pve@example.com");
+        req.password = Some("hunter2".to_string());
+        req.token = Some("gotify-token".to_string());
+        let debug = format!("{req:?}");
+        assert!(!debug.contains("hunter2"));
+        assert!(!debug.contains("gotify-token"));
+        assert!(debug.contains("smtp.example.com"));
+    }
+
+    #[test]
+    fn endpoint_request_repeats_recipients() {
+        let mut req =
+            NotificationEndpointRequest::smtp("mail", "smtp.example.com", "pve@example.com");
+        req.mailto = vec![
+            "ops@example.com".to_string(),
+            "oncall@example.com".to_string(),
+        ];
+        req.mailto_user = vec!["root@pam".to_string()];
+        req.mode = Some(SmtpMode::Starttls);
+        req.port = Some(587);
+
+        let params = req.to_params();
+        assert_eq!(params.get("name"), Some("mail"));
+        assert_eq!(params.get_all("mailto").count(), 2);
+        assert_eq!(params.get("mailto-user"), Some("root@pam"));
+        assert_eq!(params.get("from-address"), Some("pve@example.com"));
+        assert_eq!(params.get("mode"), Some("starttls"));
+        assert_eq!(params.get("type"), None);
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn endpoint_request_rejects_options_of_other_types() {
+        let mut req = NotificationEndpointRequest::sendmail("local");
+        req.mailto = vec!["ops@example.com".to_string()];
+        assert!(req.validate().is_ok());
+
+        req.token = Some("secret".to_string());
+        req.url = Some("https://hooks.example.com".to_string());
+        let err = req.validate().expect_err("token and url on sendmail");
+        assert_eq!(
+            err.to_string(),
+            "invalid argument: sendmail endpoint does not accept: token, url"
+        );
+
+        let mut req =
+            NotificationEndpointRequest::gotify("push", "https://gotify.example.com", "t");
+        req.port = Some(443);
+        assert!(req.validate().is_err());
+        assert!(
+            NotificationEndpointRequest::webhook("hook", "https://example.com")
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn matcher_rules_are_repeated_and_decoded() {
+        let mut req = NotificationMatcherRequest::new("backups")
+            .target("mail")
+            .target("gotify");
+        req.match_field = vec!["exact:type=vzdump".to_string()];
+        req.mode = Some(MatcherMode::Any);
+        let params = req.to_params();
+        assert_eq!(
+            params.get_all("target").collect::<Vec<_>>(),
+            ["mail", "gotify"]
+        );
+        assert_eq!(params.get("match-field"), Some("exact:type=vzdump"));
+        assert_eq!(params.get("mode"), Some("any"));
+
+        let matcher: NotificationMatcher = serde_json::from_str(
+            r#"{"name":"default-matcher","target":"mail-to-root","match-severity":["warning,error"],"origin":"builtin","invert-match":0}"#,
+        )
+        .expect("matcher");
+        assert_eq!(matcher.target, ["mail-to-root"]);
+        assert_eq!(matcher.match_severity, ["warning,error"]);
+        assert!(matcher.match_field.is_empty());
+        assert!(matcher.is_enabled());
+    }
+}
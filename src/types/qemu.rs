@@ -1,46 +1,176 @@
 //! QEMU related request/response types.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::error::PveError;
 use crate::params::PveParams;
 pub use crate::types::common::SnapshotInfo;
+use crate::types::common::{Bandwidth, GuestLock, format_bytes};
+use crate::types::serde_helpers::{opt_bool_int, opt_number};
+use crate::types::task::{TaskStatus, WaitTaskOptions};
+use crate::validate;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct QemuVmSummary {
     pub vmid: u32,
     pub name: Option<String>,
     pub status: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub cpu: Option<f64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub mem: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub maxmem: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub maxdisk: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub uptime: Option<u64>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
+impl QemuVmSummary {
+    pub fn mem_human(&self) -> Option<String> {
+        self.mem.map(format_bytes)
+    }
+
+    pub fn maxmem_human(&self) -> Option<String> {
+        self.maxmem.map(format_bytes)
+    }
+
+    pub fn maxdisk_human(&self) -> Option<String> {
+        self.maxdisk.map(format_bytes)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct QemuStatus {
     pub vmid: Option<u32>,
     pub name: Option<String>,
     pub status: Option<String>,
     pub qmpstatus: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub cpu: Option<f64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub mem: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub maxmem: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub netin: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub netout: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub diskread: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub diskwrite: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub uptime: Option<u64>,
+    /// Raw `lock` value; see `lock_state`.
+    pub lock: Option<String>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
+impl QemuStatus {
+    /// The operation holding the VM lock, if any. Most actions fail while it is set.
+    pub fn lock_state(&self) -> Option<GuestLock> {
+        self.lock.as_deref().map(GuestLock::from)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.lock.is_some()
+    }
+
+    /// Whether the guest agent is enabled in the VM config. PVE reports it as `agent: 1`
+    /// on the status, but accept the config string form too.
+    pub fn agent_enabled(&self) -> bool {
+        match self.extra.get("agent") {
+            Some(Value::Number(n)) => n.as_u64() == Some(1),
+            Some(Value::Bool(b)) => *b,
+            Some(Value::String(s)) => agent_enabled(s),
+            _ => false,
+        }
+    }
+}
+
+/// Parses the `agent` config option (`1`, `enabled=1,fstrim_cloned_disks=1`, ...).
+pub fn agent_enabled(agent: &str) -> bool {
+    agent.split(',').enumerate().any(|(idx, part)| {
+        let value = match part.split_once('=') {
+            Some(("enabled", value)) => value,
+            None if idx == 0 => part,
+            _ => return false,
+        };
+        matches!(value.trim(), "1" | "on" | "yes" | "true")
+    })
+}
+
+/// `GET /nodes/{node}/qemu/{vmid}/config`. Device keys (`scsi0`, `net0`, ...) stay in
+/// `extra`; use `disks` and `networks` for their parsed form.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct QemuConfig {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub cores: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub sockets: Option<u32>,
+    pub cpu: Option<String>,
+    pub boot: Option<String>,
+    pub agent: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub onboot: Option<u8>,
+    pub tags: Option<String>,
+    pub description: Option<String>,
+    pub digest: Option<String>,
+    /// Keys whose new value only takes effect after a guest restart. Filled in by
+    /// `qemu_set_config_verified`; a plain config read leaves it empty.
+    #[serde(skip)]
+    pub reboot_pending: Vec<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl QemuConfig {
+    /// Attached `ideN`, `sataN`, `scsiN` and `virtioN` disks, keyed by slot. CD-ROM drives
+    /// are included (`media=cdrom`); `unusedN` volumes are not.
+    pub fn disks(&self) -> BTreeMap<String, DiskSpec> {
+        self.devices(&["ide", "sata", "scsi", "virtio"], DiskSpec::parse)
+    }
+
+    /// `netN` devices, keyed by slot.
+    pub fn networks(&self) -> BTreeMap<String, NetDevice> {
+        self.devices(&["net"], NetDevice::parse)
+    }
+
+    fn devices<T>(
+        &self,
+        prefixes: &[&str],
+        parse: fn(&str) -> Result<T, PveError>,
+    ) -> BTreeMap<String, T> {
+        self.extra
+            .iter()
+            .filter(|(key, _)| {
+                prefixes.iter().any(|prefix| {
+                    key.strip_prefix(prefix).is_some_and(|index| {
+                        !index.is_empty() && index.chars().all(|c| c.is_ascii_digit())
+                    })
+                })
+            })
+            .filter_map(|(key, value)| {
+                let device = parse(value.as_str()?).ok()?;
+                Some((key.clone(), device))
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum QemuBios {
     SeaBios,
@@ -87,6 +217,485 @@ impl fmt::Display for QemuOsType {
     }
 }
 
+/// Device boot order, serialized as PVE's `order=scsi0;net0` form of the `boot` key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootOrder {
+    pub devices: Vec<String>,
+}
+
+impl BootOrder {
+    pub fn new(devices: &[&str]) -> Self {
+        Self {
+            devices: devices.iter().map(|device| device.to_string()).collect(),
+        }
+    }
+
+    /// Parses an `order=...` boot value. The legacy `boot=cdn` letter form is rejected.
+    pub fn parse(value: &str) -> Result<Self, PveError> {
+        let order = value
+            .split(',')
+            .find_map(|part| part.trim().strip_prefix("order="))
+            .ok_or_else(|| {
+                PveError::InvalidArgument(format!("boot value has no order= entry: {value:?}"))
+            })?;
+        let devices = order
+            .split(';')
+            .map(str::trim)
+            .filter(|device| !device.is_empty())
+            .map(str::to_string)
+            .collect();
+        Ok(Self { devices })
+    }
+}
+
+impl fmt::Display for BootOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "order={}", self.devices.join(";"))
+    }
+}
+
+/// Builder for `netN` values, e.g. `virtio,bridge=vmbr0,tag=10`.
+#[derive(Debug, Clone)]
+pub struct NetDevice {
+    pub model: String,
+    pub macaddr: Option<String>,
+    pub bridge: Option<String>,
+    pub tag: Option<u16>,
+    pub firewall: Option<bool>,
+    pub mtu: Option<u16>,
+    pub queues: Option<u8>,
+    pub rate: Option<f64>,
+    pub link_down: Option<bool>,
+    /// Options without a typed field, appended as-is.
+    pub extra: PveParams,
+}
+
+impl NetDevice {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            macaddr: None,
+            bridge: None,
+            tag: None,
+            firewall: None,
+            mtu: None,
+            queues: None,
+            rate: None,
+            link_down: None,
+            extra: PveParams::new(),
+        }
+    }
+
+    /// Parses a `netN` value such as `virtio=BC:24:11:00:00:01,bridge=vmbr0,tag=10`.
+    /// Options that are unknown or fail to parse are kept in `extra`.
+    pub fn parse(value: &str) -> Result<Self, PveError> {
+        let mut parts = value.split(',').map(str::trim);
+        let model = parts
+            .next()
+            .filter(|part| !part.is_empty())
+            .ok_or_else(|| {
+                PveError::InvalidArgument(format!("net value has no model: {value:?}"))
+            })?;
+        let mut net = match model.split_once('=') {
+            Some((model, macaddr)) => Self::new(model).macaddr(macaddr),
+            None => Self::new(model),
+        };
+        for part in parts.filter(|part| !part.is_empty()) {
+            let (key, raw) = part.split_once('=').unwrap_or((part, ""));
+            let known = match key {
+                "macaddr" => {
+                    net.macaddr = Some(raw.to_string());
+                    true
+                }
+                "bridge" => {
+                    net.bridge = Some(raw.to_string());
+                    true
+                }
+                "tag" => set_parsed(&mut net.tag, raw.parse().ok()),
+                "firewall" => set_parsed(&mut net.firewall, parse_flag(raw)),
+                "mtu" => set_parsed(&mut net.mtu, raw.parse().ok()),
+                "queues" => set_parsed(&mut net.queues, raw.parse().ok()),
+                "rate" => set_parsed(&mut net.rate, raw.parse().ok()),
+                "link_down" => set_parsed(&mut net.link_down, parse_flag(raw)),
+                _ => false,
+            };
+            if !known {
+                net.extra.push(key, raw);
+            }
+        }
+        Ok(net)
+    }
+
+    pub fn virtio(bridge: impl Into<String>) -> Self {
+        Self::new("virtio").bridge(bridge)
+    }
+
+    pub fn e1000(bridge: impl Into<String>) -> Self {
+        Self::new("e1000").bridge(bridge)
+    }
+
+    pub fn macaddr(mut self, macaddr: impl Into<String>) -> Self {
+        self.macaddr = Some(macaddr.into());
+        self
+    }
+
+    pub fn bridge(mut self, bridge: impl Into<String>) -> Self {
+        self.bridge = Some(bridge.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: u16) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    pub fn firewall(mut self, firewall: bool) -> Self {
+        self.firewall = Some(firewall);
+        self
+    }
+
+    pub fn mtu(mut self, mtu: u16) -> Self {
+        self.mtu = Some(mtu);
+        self
+    }
+
+    pub fn queues(mut self, queues: u8) -> Self {
+        self.queues = Some(queues);
+        self
+    }
+
+    /// Rate limit in MB/s.
+    pub fn rate(mut self, rate: f64) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    pub fn link_down(mut self, link_down: bool) -> Self {
+        self.link_down = Some(link_down);
+        self
+    }
+
+    pub fn option(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra.insert(key, value);
+        self
+    }
+}
+
+impl fmt::Display for NetDevice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.model)?;
+        if let Some(macaddr) = &self.macaddr {
+            write!(f, "={macaddr}")?;
+        }
+        if let Some(bridge) = &self.bridge {
+            write!(f, ",bridge={bridge}")?;
+        }
+        if let Some(firewall) = self.firewall {
+            write!(f, ",firewall={}", u8::from(firewall))?;
+        }
+        if let Some(link_down) = self.link_down {
+            write!(f, ",link_down={}", u8::from(link_down))?;
+        }
+        if let Some(mtu) = self.mtu {
+            write!(f, ",mtu={mtu}")?;
+        }
+        if let Some(queues) = self.queues {
+            write!(f, ",queues={queues}")?;
+        }
+        if let Some(rate) = self.rate {
+            write!(f, ",rate={rate}")?;
+        }
+        if let Some(tag) = self.tag {
+            write!(f, ",tag={tag}")?;
+        }
+        write_extra(f, &self.extra)
+    }
+}
+
+/// Builder for disk values such as `scsi0`, e.g. `local-lvm:32,ssd=1,discard=on`.
+#[derive(Debug, Clone)]
+pub struct DiskSpec {
+    /// `storage:size_gb` for a new disk, or an existing volume id.
+    pub file: String,
+    pub format: Option<String>,
+    pub cache: Option<String>,
+    pub discard: Option<bool>,
+    pub ssd: Option<bool>,
+    pub iothread: Option<bool>,
+    pub backup: Option<bool>,
+    pub media: Option<String>,
+    /// Options without a typed field (e.g. `size`, `aio`), appended as-is.
+    pub extra: PveParams,
+}
+
+impl DiskSpec {
+    /// Allocates a new `size_gb` GiB disk on `storage`.
+    pub fn new(storage: &str, size_gb: u32) -> Self {
+        Self::volume(format!("{storage}:{size_gb}"))
+    }
+
+    /// Attaches an existing volume, e.g. `local-lvm:vm-100-disk-0` or `local:iso/debian.iso`.
+    pub fn volume(volid: impl Into<String>) -> Self {
+        Self {
+            file: volid.into(),
+            format: None,
+            cache: None,
+            discard: None,
+            ssd: None,
+            iothread: None,
+            backup: None,
+            media: None,
+            extra: PveParams::new(),
+        }
+    }
+
+    /// Parses a disk value such as `local-lvm:vm-100-disk-0,discard=on,size=32G`.
+    /// Options that are unknown or fail to parse are kept in `extra`.
+    pub fn parse(value: &str) -> Result<Self, PveError> {
+        let mut parts = value.split(',').map(str::trim);
+        let file = parts
+            .next()
+            .filter(|part| !part.is_empty())
+            .ok_or_else(|| {
+                PveError::InvalidArgument(format!("disk value has no volume: {value:?}"))
+            })?;
+        let mut disk = Self::volume(file.strip_prefix("file=").unwrap_or(file));
+        for part in parts.filter(|part| !part.is_empty()) {
+            let (key, raw) = part.split_once('=').unwrap_or((part, ""));
+            let known = match key {
+                "format" => {
+                    disk.format = Some(raw.to_string());
+                    true
+                }
+                "cache" => {
+                    disk.cache = Some(raw.to_string());
+                    true
+                }
+                "media" => {
+                    disk.media = Some(raw.to_string());
+                    true
+                }
+                "discard" => set_parsed(&mut disk.discard, parse_flag(raw)),
+                "ssd" => set_parsed(&mut disk.ssd, parse_flag(raw)),
+                "iothread" => set_parsed(&mut disk.iothread, parse_flag(raw)),
+                "backup" => set_parsed(&mut disk.backup, parse_flag(raw)),
+                _ => false,
+            };
+            if !known {
+                disk.extra.push(key, raw);
+            }
+        }
+        Ok(disk)
+    }
+
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    pub fn cache(mut self, cache: impl Into<String>) -> Self {
+        self.cache = Some(cache.into());
+        self
+    }
+
+    pub fn discard(mut self, discard: bool) -> Self {
+        self.discard = Some(discard);
+        self
+    }
+
+    pub fn ssd(mut self, ssd: bool) -> Self {
+        self.ssd = Some(ssd);
+        self
+    }
+
+    pub fn iothread(mut self, iothread: bool) -> Self {
+        self.iothread = Some(iothread);
+        self
+    }
+
+    pub fn backup(mut self, backup: bool) -> Self {
+        self.backup = Some(backup);
+        self
+    }
+
+    pub fn media(mut self, media: impl Into<String>) -> Self {
+        self.media = Some(media.into());
+        self
+    }
+
+    pub fn option(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra.insert(key, value);
+        self
+    }
+}
+
+impl fmt::Display for DiskSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.file)?;
+        if let Some(backup) = self.backup {
+            write!(f, ",backup={}", u8::from(backup))?;
+        }
+        if let Some(cache) = &self.cache {
+            write!(f, ",cache={cache}")?;
+        }
+        if let Some(discard) = self.discard {
+            write!(f, ",discard={}", if discard { "on" } else { "ignore" })?;
+        }
+        if let Some(format) = &self.format {
+            write!(f, ",format={format}")?;
+        }
+        if let Some(iothread) = self.iothread {
+            write!(f, ",iothread={}", u8::from(iothread))?;
+        }
+        if let Some(media) = &self.media {
+            write!(f, ",media={media}")?;
+        }
+        if let Some(ssd) = self.ssd {
+            write!(f, ",ssd={}", u8::from(ssd))?;
+        }
+        write_extra(f, &self.extra)
+    }
+}
+
+/// Stores `parsed` in `slot`, reporting whether the raw value was understood.
+fn set_parsed<T>(slot: &mut Option<T>, parsed: Option<T>) -> bool {
+    let ok = parsed.is_some();
+    if ok {
+        *slot = parsed;
+    }
+    ok
+}
+
+/// Flag values as written in device strings; `discard` uses `on`/`ignore`.
+fn parse_flag(raw: &str) -> Option<bool> {
+    match raw {
+        "1" | "on" | "yes" | "true" => Some(true),
+        "0" | "off" | "no" | "false" | "ignore" => Some(false),
+        _ => None,
+    }
+}
+
+fn write_extra(f: &mut fmt::Formatter<'_>, extra: &PveParams) -> fmt::Result {
+    for (key, value) in &extra.0 {
+        if value.is_empty() {
+            write!(f, ",{key}")?;
+        } else {
+            write!(f, ",{key}={value}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Builder for cloud-init `ipconfigN` values, e.g. `gw=192.168.1.1,ip=192.168.1.50/24`.
+///
+/// Combine an IPv4 and an IPv6 setting with `merge`:
+/// `IpConfig::dhcp().merge(IpConfig::slaac())`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpConfig {
+    /// `dhcp` or an address in CIDR form.
+    pub ip: Option<String>,
+    pub gw: Option<String>,
+    /// `dhcp`, `auto` (SLAAC) or an address in CIDR form.
+    pub ip6: Option<String>,
+    pub gw6: Option<String>,
+}
+
+impl IpConfig {
+    pub fn dhcp() -> Self {
+        Self {
+            ip: Some("dhcp".to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn static_v4(ip: &str, cidr: u8, gateway: Option<&str>) -> Result<Self, PveError> {
+        let addr = parse_ip::<Ipv4Addr>(ip, "ipv4 address")?;
+        if cidr > 32 {
+            return Err(PveError::InvalidArgument(format!(
+                "ipv4 prefix length {cidr} out of range 0..=32"
+            )));
+        }
+        let gw = gateway
+            .map(|gw| parse_ip::<Ipv4Addr>(gw, "ipv4 gateway").map(|gw| gw.to_string()))
+            .transpose()?;
+        Ok(Self {
+            ip: Some(format!("{addr}/{cidr}")),
+            gw,
+            ..Self::default()
+        })
+    }
+
+    pub fn dhcp6() -> Self {
+        Self {
+            ip6: Some("dhcp".to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// IPv6 stateless autoconfiguration (`ip6=auto`).
+    pub fn slaac() -> Self {
+        Self {
+            ip6: Some("auto".to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn static_v6(ip: &str, prefix: u8, gateway: Option<&str>) -> Result<Self, PveError> {
+        let addr = parse_ip::<Ipv6Addr>(ip, "ipv6 address")?;
+        if prefix > 128 {
+            return Err(PveError::InvalidArgument(format!(
+                "ipv6 prefix length {prefix} out of range 0..=128"
+            )));
+        }
+        let gw6 = gateway
+            .map(|gw| parse_ip::<Ipv6Addr>(gw, "ipv6 gateway").map(|gw| gw.to_string()))
+            .transpose()?;
+        Ok(Self {
+            ip6: Some(format!("{addr}/{prefix}")),
+            gw6,
+            ..Self::default()
+        })
+    }
+
+    /// Fields set in `other` replace those in `self`.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            ip: other.ip.or(self.ip),
+            gw: other.gw.or(self.gw),
+            ip6: other.ip6.or(self.ip6),
+            gw6: other.gw6.or(self.gw6),
+        }
+    }
+}
+
+fn parse_ip<T: FromStr>(value: &str, what: &str) -> Result<T, PveError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| PveError::InvalidArgument(format!("invalid {what}: {value:?}")))
+}
+
+impl fmt::Display for IpConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = [
+            ("gw", &self.gw),
+            ("gw6", &self.gw6),
+            ("ip", &self.ip),
+            ("ip6", &self.ip6),
+        ];
+        let mut first = true;
+        for (key, value) in parts {
+            let Some(value) = value else { continue };
+            if !first {
+                f.write_str(",")?;
+            }
+            write!(f, "{key}={value}")?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct QemuCreateRequest {
     pub vmid: u32,
@@ -129,6 +738,36 @@ impl QemuCreateRequest {
         }
     }
 
+    /// Checks the vmid range, the name as a DNS label and any MAC in `net0`.
+    pub fn validate(&self) -> Result<(), PveError> {
+        validate::validate_vmid(self.vmid)?;
+        if let Some(name) = self.name.as_deref() {
+            validate::validate_hostname(name)?;
+        }
+        if let Some(net0) = self.net0.as_deref() {
+            validate::validate_net_mac(net0, &["macaddr"], true)?;
+        }
+        Ok(())
+    }
+
+    /// Accepts a raw string or a `NetDevice`.
+    pub fn net0(mut self, net: impl fmt::Display) -> Self {
+        self.net0 = Some(net.to_string());
+        self
+    }
+
+    /// Accepts a raw string or a `DiskSpec`.
+    pub fn scsi0(mut self, disk: impl fmt::Display) -> Self {
+        self.scsi0 = Some(disk.to_string());
+        self
+    }
+
+    /// Accepts a raw string or a `DiskSpec`.
+    pub fn virtio0(mut self, disk: impl fmt::Display) -> Self {
+        self.virtio0 = Some(disk.to_string());
+        self
+    }
+
     pub fn to_params(&self) -> PveParams {
         let mut params = PveParams::new();
         params.insert("vmid", self.vmid.to_string());
@@ -180,6 +819,8 @@ pub struct QemuSetConfigRequest {
     pub cpu: Option<String>,
     pub agent: Option<String>,
     pub boot: Option<String>,
+    /// Typed alternative to `boot`; takes precedence when both are set.
+    pub boot_order: Option<BootOrder>,
     pub bootdisk: Option<String>,
     pub net0: Option<String>,
     pub scsi0: Option<String>,
@@ -191,6 +832,13 @@ pub struct QemuSetConfigRequest {
 }
 
 impl QemuSetConfigRequest {
+    /// Sets cloud-init `ipconfig{index}`, the address config for `net{index}`.
+    pub fn ipconfig(mut self, index: u8, config: &IpConfig) -> Self {
+        self.extra
+            .insert(format!("ipconfig{index}"), config.to_string());
+        self
+    }
+
     pub fn to_params(&self) -> PveParams {
         let mut params = PveParams::new();
         params.insert_opt("delete", self.delete.clone());
@@ -201,6 +849,7 @@ impl QemuSetConfigRequest {
         params.insert_opt("cpu", self.cpu.clone());
         params.insert_opt("agent", self.agent.clone());
         params.insert_opt("boot", self.boot.clone());
+        params.insert_opt("boot", self.boot_order.as_ref().map(|v| v.to_string()));
         params.insert_opt("bootdisk", self.bootdisk.clone());
         params.insert_opt("net0", self.net0.clone());
         params.insert_opt("scsi0", self.scsi0.clone());
@@ -215,6 +864,32 @@ impl QemuSetConfigRequest {
     }
 }
 
+/// Options for destroying a VM. `purge` also removes it from backup jobs, replication and HA.
+#[derive(Debug, Clone, Default)]
+pub struct QemuDeleteRequest {
+    pub purge: Option<bool>,
+    pub destroy_unreferenced_disks: Option<bool>,
+    pub skiplock: Option<bool>,
+    pub extra: PveParams,
+}
+
+impl QemuDeleteRequest {
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        if let Some(purge) = self.purge {
+            params.insert_bool("purge", purge);
+        }
+        if let Some(destroy) = self.destroy_unreferenced_disks {
+            params.insert_bool("destroy-unreferenced-disks", destroy);
+        }
+        if let Some(skiplock) = self.skiplock {
+            params.insert_bool("skiplock", skiplock);
+        }
+        params.extend(&self.extra);
+        params
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct QemuActionRequest {
     pub timeout: Option<u64>,
@@ -323,6 +998,7 @@ pub struct QemuCloneRequest {
     pub full: Option<bool>,
     pub pool: Option<String>,
     pub snapname: Option<String>,
+    /// KiB/s; see `bandwidth` for a unit-checked setter.
     pub bwlimit: Option<u64>,
     pub format: Option<String>,
     pub description: Option<String>,
@@ -346,6 +1022,11 @@ impl QemuCloneRequest {
         }
     }
 
+    pub fn bandwidth(mut self, bandwidth: Bandwidth) -> Self {
+        self.bwlimit = Some(bandwidth.to_kib());
+        self
+    }
+
     pub fn to_params(&self) -> PveParams {
         let mut params = PveParams::new();
         params.insert("newid", self.newid.to_string());
@@ -395,6 +1076,11 @@ impl QemuMigrateRequest {
         }
     }
 
+    pub fn bandwidth(mut self, bandwidth: Bandwidth) -> Self {
+        self.bwlimit = Some(bandwidth.to_kib());
+        self
+    }
+
     pub fn to_params(&self) -> PveParams {
         let mut params = PveParams::new();
         params.insert("target", self.target.clone());
@@ -419,9 +1105,180 @@ impl QemuMigrateRequest {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct QemuProvisionRequest {
+    pub clone: QemuCloneRequest,
+    pub config: Option<QemuSetConfigRequest>,
+    pub start: bool,
+    pub wait: WaitTaskOptions,
+}
+
+impl QemuProvisionRequest {
+    pub fn new(clone: QemuCloneRequest) -> Self {
+        Self {
+            clone,
+            config: None,
+            start: false,
+            wait: WaitTaskOptions::default(),
+        }
+    }
+
+    pub fn config(mut self, config: QemuSetConfigRequest) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn start(mut self, start: bool) -> Self {
+        self.start = start;
+        self
+    }
+
+    pub fn wait(mut self, wait: WaitTaskOptions) -> Self {
+        self.wait = wait;
+        self
+    }
+}
+
+/// Options for `qemu_ensure_state`.
+#[derive(Debug, Clone)]
+pub struct QemuEnsureStateOptions {
+    pub wait: WaitTaskOptions,
+    /// When the desired state is `Running`, also wait for the guest agent to answer
+    /// pings. The agent wait shares `wait.timeout` with the start task.
+    pub wait_for_agent: bool,
+    /// Bound on the agent wait when `wait.timeout` is `None`, so it cannot hang forever.
+    /// Defaults to `DEFAULT_AGENT_TIMEOUT`.
+    pub agent_timeout: Duration,
+}
+
+impl QemuEnsureStateOptions {
+    /// Agent wait used by `new` and `Default` when the task wait has no timeout.
+    pub const DEFAULT_AGENT_TIMEOUT: Duration = Duration::from_secs(300);
+
+    pub fn new(wait: WaitTaskOptions) -> Self {
+        Self {
+            wait,
+            wait_for_agent: false,
+            agent_timeout: Self::DEFAULT_AGENT_TIMEOUT,
+        }
+    }
+
+    pub fn wait_for_agent(mut self, wait_for_agent: bool) -> Self {
+        self.wait_for_agent = wait_for_agent;
+        self
+    }
+
+    pub fn agent_timeout(mut self, agent_timeout: Duration) -> Self {
+        self.agent_timeout = agent_timeout;
+        self
+    }
+}
+
+impl Default for QemuEnsureStateOptions {
+    fn default() -> Self {
+        Self::new(WaitTaskOptions::default())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QemuProvisionResult {
+    pub vmid: u32,
+    pub node: String,
+    pub status: TaskStatus,
+}
+
+/// Guest agent replies are wrapped as `{"result": ...}`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct AgentResult<T> {
+    pub result: T,
+}
+
+/// Reply of the guest agent's `fstrim`, one entry per mounted filesystem.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AgentFstrimResult {
+    #[serde(default)]
+    pub paths: Vec<AgentFstrimPath>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl AgentFstrimResult {
+    /// Bytes discarded across all filesystems that reported a count.
+    pub fn total_trimmed(&self) -> u64 {
+        self.paths.iter().filter_map(|path| path.trimmed).sum()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AgentFstrimPath {
+    pub path: String,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub trimmed: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub minimum: Option<u64>,
+    /// Set instead of `trimmed` when the filesystem could not be trimmed.
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Result of `GET /nodes/{node}/qemu/{vmid}/migrate`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MigratePreconditions {
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub running: Option<u8>,
+    #[serde(default)]
+    pub allowed_nodes: Vec<String>,
+    /// Node name to the reason it is rejected (e.g. `unavailable_storages`).
+    #[serde(default)]
+    pub not_allowed_nodes: HashMap<String, Value>,
+    #[serde(default)]
+    pub local_disks: Vec<MigrateLocalDisk>,
+    /// Host-bound devices (passthrough, local CD-ROM images) that block migration.
+    #[serde(default)]
+    pub local_resources: Vec<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl MigratePreconditions {
+    pub fn is_running(&self) -> bool {
+        self.running == Some(1)
+    }
+
+    pub fn allows(&self, target: &str) -> bool {
+        self.allowed_nodes.iter().any(|node| node == target)
+    }
+
+    /// Whether migration is blocked regardless of target.
+    pub fn has_local_resources(&self) -> bool {
+        !self.local_resources.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MigrateLocalDisk {
+    pub volid: String,
+    pub size: Option<u64>,
+    pub drivename: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub cdrom: Option<u8>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub is_unused: Option<u8>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub replicated: Option<u8>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{QemuCreateRequest, QemuMigrateRequest};
+    use super::{
+        AgentFstrimResult, AgentResult, BootOrder, DiskSpec, IpConfig, MigratePreconditions,
+        NetDevice, QemuConfig, QemuCreateRequest, QemuDeleteRequest, QemuMigrateRequest,
+        QemuSetConfigRequest, QemuStatus, agent_enabled,
+    };
+    use crate::types::common::GuestLock;
 
     #[test]
     fn qemu_create_maps_bool_and_required_fields() {
@@ -446,4 +1303,233 @@ mod tests {
         assert_eq!(params.get("online"), Some("1"));
         assert_eq!(params.get("with-local-disks"), Some("0"));
     }
+
+    #[test]
+    fn qemu_delete_maps_purge_and_disk_cleanup() {
+        let req = QemuDeleteRequest {
+            purge: Some(true),
+            destroy_unreferenced_disks: Some(true),
+            ..Default::default()
+        };
+        let params = req.to_params();
+
+        assert_eq!(params.get("purge"), Some("1"));
+        assert_eq!(params.get("destroy-unreferenced-disks"), Some("1"));
+        assert_eq!(params.get("skiplock"), None);
+    }
+
+    #[test]
+    fn boot_order_round_trips_order_syntax() {
+        let order = BootOrder::new(&["scsi0", "net0"]);
+        assert_eq!(order.to_string(), "order=scsi0;net0");
+        assert_eq!(BootOrder::parse("order=scsi0;net0").expect("parse"), order);
+        assert!(BootOrder::parse("cdn").is_err());
+    }
+
+    #[test]
+    fn set_config_boot_order_overrides_raw_boot() {
+        let req = QemuSetConfigRequest {
+            boot: Some("order=ide2".to_string()),
+            boot_order: Some(BootOrder::new(&["virtio0", "ide2"])),
+            ..Default::default()
+        };
+        assert_eq!(req.to_params().get("boot"), Some("order=virtio0;ide2"));
+    }
+
+    #[test]
+    fn net_device_and_disk_spec_format_pve_values() {
+        assert_eq!(
+            NetDevice::virtio("vmbr0").tag(10).to_string(),
+            "virtio,bridge=vmbr0,tag=10"
+        );
+        assert_eq!(
+            NetDevice::virtio("vmbr0")
+                .macaddr("BC:24:11:00:00:01")
+                .firewall(true)
+                .to_string(),
+            "virtio=BC:24:11:00:00:01,bridge=vmbr0,firewall=1"
+        );
+        assert_eq!(
+            DiskSpec::new("local-lvm", 32)
+                .ssd(true)
+                .discard(true)
+                .to_string(),
+            "local-lvm:32,discard=on,ssd=1"
+        );
+    }
+
+    #[test]
+    fn qemu_create_setters_accept_builders_and_strings() {
+        let req = QemuCreateRequest::new(300)
+            .net0(NetDevice::virtio("vmbr0"))
+            .scsi0("local-lvm:16");
+        let params = req.to_params();
+        assert_eq!(params.get("net0"), Some("virtio,bridge=vmbr0"));
+        assert_eq!(params.get("scsi0"), Some("local-lvm:16"));
+    }
+
+    #[test]
+    fn qemu_create_validate_rejects_bad_vmid_name_and_mac() {
+        assert!(QemuCreateRequest::new(300).validate().is_ok());
+        assert!(QemuCreateRequest::new(42).validate().is_err());
+
+        let mut req = QemuCreateRequest::new(300);
+        req.name = Some("web_01".to_string());
+        assert!(req.validate().is_err());
+
+        let req = QemuCreateRequest::new(300).net0("virtio=BC:24:11:00:00,bridge=vmbr0");
+        let err = req.validate().expect_err("short mac");
+        assert!(err.to_string().contains("mac address"));
+    }
+
+    #[test]
+    fn migrate_preconditions_decode_blockers() {
+        let pre: MigratePreconditions = serde_json::from_str(
+            r#"{"running":1,"allowed_nodes":["pve2"],"not_allowed_nodes":{"pve3":{"unavailable_storages":["local-zfs"]}},"local_disks":[{"volid":"local-zfs:vm-100-disk-0","size":34359738368,"drivename":"scsi0"}],"local_resources":["hostpci0"]}"#,
+        )
+        .expect("preconditions");
+        assert!(pre.is_running());
+        assert!(pre.allows("pve2"));
+        assert!(!pre.allows("pve3"));
+        assert!(pre.not_allowed_nodes.contains_key("pve3"));
+        assert_eq!(pre.local_disks[0].drivename.as_deref(), Some("scsi0"));
+        assert!(pre.has_local_resources());
+
+        let empty: MigratePreconditions = serde_json::from_str(r#"{"running":0}"#).expect("empty");
+        assert!(empty.allowed_nodes.is_empty());
+        assert!(!empty.has_local_resources());
+    }
+
+    #[test]
+    fn agent_flag_parses_config_and_status_forms() {
+        assert!(agent_enabled("1"));
+        assert!(agent_enabled("enabled=1,fstrim_cloned_disks=1"));
+        assert!(agent_enabled("type=virtio,enabled=yes"));
+        assert!(!agent_enabled("0"));
+        assert!(!agent_enabled("enabled=0"));
+        assert!(!agent_enabled(""));
+
+        let status: QemuStatus =
+            serde_json::from_str(r#"{"vmid":100,"status":"running","agent":1}"#).expect("status");
+        assert!(status.agent_enabled());
+        let status: QemuStatus =
+            serde_json::from_str(r#"{"vmid":100,"status":"running"}"#).expect("status");
+        assert!(!status.agent_enabled());
+    }
+
+    #[test]
+    fn qemu_status_accepts_stringified_counters() {
+        let status: QemuStatus = serde_json::from_str(
+            r#"{"vmid":100,"status":"running","cpu":"0.25","mem":"1073741824","netin":12,"uptime":null}"#,
+        )
+        .expect("status");
+        assert_eq!(status.cpu, Some(0.25));
+        assert_eq!(status.mem, Some(1_073_741_824));
+        assert_eq!(status.netin, Some(12));
+        assert_eq!(status.uptime, None);
+    }
+
+    #[test]
+    fn qemu_status_exposes_lock() {
+        let status: QemuStatus =
+            serde_json::from_str(r#"{"vmid":100,"status":"running","lock":"backup"}"#)
+                .expect("status");
+        assert!(status.is_locked());
+        assert_eq!(status.lock_state(), Some(GuestLock::Backup));
+
+        let status: QemuStatus =
+            serde_json::from_str(r#"{"vmid":100,"status":"running"}"#).expect("status");
+        assert_eq!(status.lock_state(), None);
+    }
+
+    #[test]
+    fn config_devices_parse_into_builders() {
+        let config: QemuConfig = serde_json::from_str(
+            r#"{
+                "name": "web01",
+                "cores": "2",
+                "onboot": 1,
+                "scsi0": "local-lvm:vm-100-disk-0,discard=on,iothread=1,size=32G",
+                "ide2": "none,media=cdrom",
+                "unused0": "local-lvm:vm-100-disk-1",
+                "scsihw": "virtio-scsi-single",
+                "net0": "virtio=BC:24:11:00:00:01,bridge=vmbr0,firewall=1,tag=10",
+                "net1": "e1000=BC:24:11:00:00:02,bridge=vmbr1,mtu=1,trunks=20;30"
+            }"#,
+        )
+        .expect("config");
+        assert_eq!(config.cores, Some(2));
+
+        let disks = config.disks();
+        assert_eq!(disks.keys().collect::<Vec<_>>(), ["ide2", "scsi0"]);
+        let scsi0 = &disks["scsi0"];
+        assert_eq!(scsi0.file, "local-lvm:vm-100-disk-0");
+        assert_eq!(scsi0.discard, Some(true));
+        assert_eq!(scsi0.iothread, Some(true));
+        assert_eq!(scsi0.extra.get("size"), Some("32G"));
+        assert_eq!(
+            scsi0.to_string(),
+            "local-lvm:vm-100-disk-0,discard=on,iothread=1,size=32G"
+        );
+        assert_eq!(disks["ide2"].media.as_deref(), Some("cdrom"));
+
+        let networks = config.networks();
+        assert_eq!(networks.len(), 2);
+        let net0 = &networks["net0"];
+        assert_eq!(net0.model, "virtio");
+        assert_eq!(net0.macaddr.as_deref(), Some("BC:24:11:00:00:01"));
+        assert_eq!(net0.tag, Some(10));
+        assert_eq!(net0.firewall, Some(true));
+        assert_eq!(networks["net1"].extra.get("trunks"), Some("20;30"));
+    }
+
+    #[test]
+    fn device_parse_keeps_unparseable_options() {
+        let net = NetDevice::parse("virtio,bridge=vmbr0,tag=abc").expect("net");
+        assert_eq!(net.tag, None);
+        assert_eq!(net.extra.get("tag"), Some("abc"));
+        assert_eq!(net.to_string(), "virtio,bridge=vmbr0,tag=abc");
+
+        assert!(NetDevice::parse("").is_err());
+        assert!(DiskSpec::parse(",size=1G").is_err());
+        let disk = DiskSpec::parse("file=local:iso/debian.iso,media=cdrom").expect("disk");
+        assert_eq!(disk.file, "local:iso/debian.iso");
+    }
+
+    #[test]
+    fn ip_config_formats_and_validates() {
+        let v4 = IpConfig::static_v4("192.168.1.50", 24, Some("192.168.1.1")).expect("v4");
+        assert_eq!(v4.to_string(), "gw=192.168.1.1,ip=192.168.1.50/24");
+        assert_eq!(IpConfig::dhcp().to_string(), "ip=dhcp");
+        assert_eq!(
+            IpConfig::dhcp().merge(IpConfig::slaac()).to_string(),
+            "ip=dhcp,ip6=auto"
+        );
+        let v6 = IpConfig::static_v6("2001:db8::10", 64, Some("2001:db8::1")).expect("v6");
+        assert_eq!(v6.to_string(), "gw6=2001:db8::1,ip6=2001:db8::10/64");
+
+        assert!(IpConfig::static_v4("192.168.1.300", 24, None).is_err());
+        assert!(IpConfig::static_v4("192.168.1.50", 33, None).is_err());
+        assert!(IpConfig::static_v4("192.168.1.50", 24, Some("2001:db8::1")).is_err());
+        assert!(IpConfig::static_v6("2001:db8::10", 129, None).is_err());
+
+        let params = QemuSetConfigRequest::default()
+            .ipconfig(1, &IpConfig::dhcp())
+            .to_params();
+        assert_eq!(params.get("ipconfig1"), Some("ip=dhcp"));
+    }
+
+    #[test]
+    fn agent_fstrim_result_sums_trimmed_bytes() {
+        let reply: AgentResult<AgentFstrimResult> = serde_json::from_str(
+            r#"{"result":{"paths":[
+                {"path":"/","trimmed":1048576,"minimum":0},
+                {"path":"/boot","trimmed":"4096"},
+                {"path":"/mnt/ro","error":"Read-only file system"}
+            ]}}"#,
+        )
+        .expect("fstrim");
+        assert_eq!(reply.result.total_trimmed(), 1_052_672);
+        assert_eq!(reply.result.paths[2].trimmed, None);
+    }
 }
@@ -0,0 +1,66 @@
+//! SDN (software-defined networking) related types.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::serde_helpers::opt_bool_int;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SdnZone {
+    pub zone: String,
+    #[serde(rename = "type")]
+    pub zone_type: Option<String>,
+    pub bridge: Option<String>,
+    pub mtu: Option<u64>,
+    pub nodes: Option<String>,
+    pub ipam: Option<String>,
+    pub state: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SdnVnet {
+    pub vnet: String,
+    #[serde(rename = "type")]
+    pub vnet_type: Option<String>,
+    pub zone: Option<String>,
+    pub tag: Option<u32>,
+    pub alias: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
+    pub vlanaware: Option<u8>,
+    pub state: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl SdnVnet {
+    pub fn is_vlan_aware(&self) -> bool {
+        self.vlanaware == Some(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SdnVnet, SdnZone};
+
+    #[test]
+    fn sdn_zone_and_vnet_decode_common_fields() {
+        let zone: SdnZone =
+            serde_json::from_str(r#"{"zone":"lab","type":"vlan","bridge":"vmbr0","digest":"abc"}"#)
+                .expect("zone");
+        assert_eq!(zone.zone, "lab");
+        assert_eq!(zone.zone_type.as_deref(), Some("vlan"));
+        assert_eq!(zone.bridge.as_deref(), Some("vmbr0"));
+        assert!(zone.extra.contains_key("digest"));
+
+        let vnet: SdnVnet =
+            serde_json::from_str(r#"{"vnet":"vnet10","zone":"lab","tag":10,"type":"vnet"}"#)
+                .expect("vnet");
+        assert_eq!(vnet.vnet, "vnet10");
+        assert_eq!(vnet.zone.as_deref(), Some("lab"));
+        assert_eq!(vnet.tag, Some(10));
+    }
+}
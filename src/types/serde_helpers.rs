@@ -0,0 +1,163 @@
+//! Deserializers for fields PVE encodes inconsistently across versions and endpoints.
+
+use std::str::FromStr;
+
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Accepts a JSON number or a numeric string (`0.5` or `"0.5"`). Use with
+/// `#[serde(default, deserialize_with = "...")]`; `null` and `""` decode as `None`.
+pub(crate) fn opt_number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + FromStr,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) if s.trim().is_empty() => Ok(None),
+        Some(value) => parse_number(value).map(Some),
+    }
+}
+
+/// Required counterpart of `opt_number`.
+pub(crate) fn number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + FromStr,
+{
+    parse_number(Value::deserialize(deserializer)?)
+}
+
+/// Accepts PVE's 0/1 flags as numbers, booleans or strings (`1`, `true`, `"1"`, `"on"`),
+/// normalized to `Some(0)`/`Some(1)`. Use with `#[serde(default, deserialize_with = "...")]`.
+pub(crate) fn opt_bool_int<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let flag = match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => return Ok(None),
+        Some(Value::Bool(b)) => b,
+        Some(Value::Number(n)) => match n.as_u64() {
+            Some(0) => false,
+            Some(1) => true,
+            _ => return Err(D::Error::custom(format!("invalid flag {n}"))),
+        },
+        Some(Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "" => return Ok(None),
+            "0" | "false" | "no" | "off" => false,
+            "1" | "true" | "yes" | "on" => true,
+            _ => return Err(D::Error::custom(format!("invalid flag {s:?}"))),
+        },
+        Some(other) => return Err(D::Error::custom(format!("expected flag, got {other}"))),
+    };
+    Ok(Some(u8::from(flag)))
+}
+
+/// Accepts a list of strings or a single string, as PVE collapses one-element arrays in
+/// some section-config endpoints. Use with `#[serde(default, deserialize_with = "...")]`.
+pub(crate) fn string_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::String(s)) => Ok(vec![s]),
+        Some(value) => serde_json::from_value(value).map_err(D::Error::custom),
+    }
+}
+
+fn parse_number<T, E>(value: Value) -> Result<T, E>
+where
+    T: DeserializeOwned + FromStr,
+    E: Error,
+{
+    match value {
+        Value::Number(n) => serde_json::from_value(Value::Number(n.clone()))
+            .map_err(|_| E::custom(format!("number {n} out of range"))),
+        Value::String(s) => s
+            .trim()
+            .parse()
+            .map_err(|_| E::custom(format!("invalid numeric string {s:?}"))),
+        other => Err(E::custom(format!("expected number, got {other}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Sample {
+        #[serde(default, deserialize_with = "super::opt_number")]
+        cpu: Option<f64>,
+        #[serde(default, deserialize_with = "super::opt_number")]
+        mem: Option<u64>,
+        #[serde(deserialize_with = "super::number")]
+        port: u16,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Flags {
+        #[serde(default, deserialize_with = "super::opt_bool_int")]
+        enabled: Option<u8>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Targets {
+        #[serde(default, deserialize_with = "super::string_list")]
+        target: Vec<String>,
+    }
+
+    fn flag(json: &str) -> Result<Option<u8>, serde_json::Error> {
+        serde_json::from_str::<Flags>(json).map(|f| f.enabled)
+    }
+
+    #[test]
+    fn bool_int_accepts_numbers_bools_and_strings() {
+        assert_eq!(flag(r#"{"enabled":1}"#).expect("int"), Some(1));
+        assert_eq!(flag(r#"{"enabled":0}"#).expect("int"), Some(0));
+        assert_eq!(flag(r#"{"enabled":true}"#).expect("bool"), Some(1));
+        assert_eq!(flag(r#"{"enabled":"0"}"#).expect("str"), Some(0));
+        assert_eq!(flag(r#"{"enabled":"on"}"#).expect("str"), Some(1));
+        assert_eq!(flag(r#"{}"#).expect("missing"), None);
+        assert_eq!(flag(r#"{"enabled":null}"#).expect("null"), None);
+        assert!(flag(r#"{"enabled":2}"#).is_err());
+        assert!(flag(r#"{"enabled":"maybe"}"#).is_err());
+    }
+
+    #[test]
+    fn string_list_accepts_single_string() {
+        let list = |json| serde_json::from_str::<Targets>(json).map(|t| t.target);
+        assert_eq!(list(r#"{"target":["a","b"]}"#).expect("list"), ["a", "b"]);
+        assert_eq!(list(r#"{"target":"a"}"#).expect("single"), ["a"]);
+        assert!(list(r#"{}"#).expect("missing").is_empty());
+        assert!(list(r#"{"target":[1]}"#).is_err());
+    }
+
+    #[test]
+    fn numbers_and_numeric_strings_decode_alike() {
+        let a: Sample =
+            serde_json::from_str(r#"{"cpu":0.5,"mem":2048,"port":5900}"#).expect("numbers");
+        let b: Sample =
+            serde_json::from_str(r#"{"cpu":"0.5","mem":"2048","port":"5900"}"#).expect("strings");
+        assert_eq!((a.cpu, a.mem, a.port), (Some(0.5), Some(2048), 5900));
+        assert_eq!((b.cpu, b.mem, b.port), (Some(0.5), Some(2048), 5900));
+    }
+
+    #[test]
+    fn missing_null_and_empty_are_none() {
+        let s: Sample = serde_json::from_str(r#"{"mem":null,"cpu":"","port":1}"#).expect("empty");
+        assert_eq!((s.cpu, s.mem), (None, None));
+        let s: Sample = serde_json::from_str(r#"{"port":1}"#).expect("missing");
+        assert_eq!((s.cpu, s.mem), (None, None));
+    }
+
+    #[test]
+    fn garbage_and_out_of_range_are_rejected() {
+        assert!(serde_json::from_str::<Sample>(r#"{"mem":"lots","port":1}"#).is_err());
+        assert!(serde_json::from_str::<Sample>(r#"{"mem":-1,"port":1}"#).is_err());
+        assert!(serde_json::from_str::<Sample>(r#"{"port":70000}"#).is_err());
+        assert!(serde_json::from_str::<Sample>(r#"{"mem":true,"port":1}"#).is_err());
+    }
+}
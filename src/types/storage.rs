@@ -1,12 +1,16 @@
 //! Storage related request/response types.
 
 use std::collections::HashMap;
+use std::fmt;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::error::PveError;
 use crate::params::PveParams;
+use crate::types::common::format_bytes;
+use crate::types::serde_helpers::{opt_bool_int, opt_number};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct StorageIndexItem {
@@ -20,22 +24,69 @@ pub struct NodeStorageStatus {
     pub storage: String,
     #[serde(rename = "type")]
     pub storage_type: Option<String>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
     pub active: Option<u8>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
     pub enabled: Option<u8>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub used: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub avail: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub total: Option<u64>,
+    #[serde(default, deserialize_with = "opt_bool_int")]
     pub shared: Option<u8>,
     pub content: Option<String>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
+const GIB: f64 = (1u64 << 30) as f64;
+
+impl NodeStorageStatus {
+    pub fn is_active(&self) -> bool {
+        self.active == Some(1)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled == Some(1)
+    }
+
+    pub fn is_shared(&self) -> bool {
+        self.shared == Some(1)
+    }
+
+    /// Used space as a percentage (0-100) of `total`. `None` when either is missing or
+    /// `total` is zero (inactive storages report zeros).
+    pub fn percent_used(&self) -> Option<f64> {
+        let used = self.used?;
+        let total = self.total.filter(|t| *t > 0)?;
+        Some(used as f64 / total as f64 * 100.0)
+    }
+
+    /// `used` in GiB.
+    pub fn human_used(&self) -> Option<f64> {
+        self.used.map(|v| v as f64 / GIB)
+    }
+
+    /// `total` in GiB.
+    pub fn human_total(&self) -> Option<f64> {
+        self.total.map(|v| v as f64 / GIB)
+    }
+
+    /// Whether usage is above `threshold` percent. False when usage is unknown.
+    pub fn is_over(&self, threshold: f64) -> bool {
+        self.percent_used().is_some_and(|p| p > threshold)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct StorageContentItem {
     pub volid: String,
     pub format: Option<String>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub size: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
     pub used: Option<u64>,
     pub vmid: Option<u32>,
     pub ctime: Option<u64>,
@@ -44,6 +95,12 @@ pub struct StorageContentItem {
     pub extra: HashMap<String, Value>,
 }
 
+impl StorageContentItem {
+    pub fn size_human(&self) -> Option<String> {
+        self.size.map(format_bytes)
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct NodeStorageQuery {
     pub content: Option<String>,
@@ -76,6 +133,36 @@ pub struct StorageContentQuery {
 }
 
 impl StorageContentQuery {
+    /// Filters by several content types, e.g. `&["images", "rootdir"]`.
+    pub fn content_types(types: &[&str]) -> Self {
+        Self {
+            content: Some(types.join(",")),
+            vmid: None,
+        }
+    }
+
+    /// Rejects combinations PVE would silently ignore, such as a `vmid` filter on ISO images.
+    pub fn validate(&self) -> Result<(), PveError> {
+        let Some(content) = self.content.as_deref() else {
+            return Ok(());
+        };
+        if content.split(',').any(|ty| ty.trim().is_empty()) {
+            return Err(PveError::InvalidArgument(format!(
+                "invalid storage content filter: {content:?}"
+            )));
+        }
+        if self.vmid.is_some()
+            && !content
+                .split(',')
+                .any(|ty| matches!(ty.trim(), "images" | "rootdir" | "backup"))
+        {
+            return Err(PveError::InvalidArgument(format!(
+                "vmid filter does not apply to content type {content:?}"
+            )));
+        }
+        Ok(())
+    }
+
     pub fn to_params(&self) -> PveParams {
         let mut params = PveParams::new();
         params.insert_opt("content", self.content.clone());
@@ -138,3 +225,287 @@ impl StorageUploadRequest {
 pub struct StorageDeleteVolumeRequest {
     pub delay: Option<u32>,
 }
+
+/// Storage plugin of a `/storage` definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageType {
+    Dir,
+    Nfs,
+    Cifs,
+    Lvm,
+    Lvmthin,
+    Zfspool,
+    Pbs,
+    Cephfs,
+    Rbd,
+}
+
+impl StorageType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Dir => "dir",
+            Self::Nfs => "nfs",
+            Self::Cifs => "cifs",
+            Self::Lvm => "lvm",
+            Self::Lvmthin => "lvmthin",
+            Self::Zfspool => "zfspool",
+            Self::Pbs => "pbs",
+            Self::Cephfs => "cephfs",
+            Self::Rbd => "rbd",
+        }
+    }
+}
+
+impl fmt::Display for StorageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `POST /storage`. Only the options relevant to `type_` should be set; PVE rejects the
+/// rest. Anything not modelled here goes in `extra`.
+#[derive(Debug, Clone)]
+pub struct StorageCreateRequest {
+    pub storage: String,
+    pub type_: StorageType,
+    /// Content types, e.g. `["images", "rootdir"]`.
+    pub content: Vec<String>,
+    /// `dir` mount point.
+    pub path: Option<String>,
+    /// `nfs` / `cifs` / `pbs` server.
+    pub server: Option<String>,
+    /// `nfs` export path.
+    pub export: Option<String>,
+    /// `cifs` share name.
+    pub share: Option<String>,
+    /// `zfspool` dataset or `rbd` pool.
+    pub pool: Option<String>,
+    /// `lvm` / `lvmthin` volume group.
+    pub vgname: Option<String>,
+    /// `lvmthin` pool.
+    pub thinpool: Option<String>,
+    /// `pbs` datastore.
+    pub datastore: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// `pbs` server certificate fingerprint.
+    pub fingerprint: Option<String>,
+    /// Nodes the storage is available on; empty means all.
+    pub nodes: Vec<String>,
+    pub shared: Option<bool>,
+    pub enabled: Option<bool>,
+    pub extra: PveParams,
+}
+
+impl StorageCreateRequest {
+    pub fn new(storage: impl Into<String>, type_: StorageType) -> Self {
+        Self {
+            storage: storage.into(),
+            type_,
+            content: Vec::new(),
+            path: None,
+            server: None,
+            export: None,
+            share: None,
+            pool: None,
+            vgname: None,
+            thinpool: None,
+            datastore: None,
+            username: None,
+            password: None,
+            fingerprint: None,
+            nodes: Vec::new(),
+            shared: None,
+            enabled: None,
+            extra: PveParams::new(),
+        }
+    }
+
+    pub fn dir(storage: impl Into<String>, path: impl Into<String>) -> Self {
+        let mut req = Self::new(storage, StorageType::Dir);
+        req.path = Some(path.into());
+        req
+    }
+
+    pub fn nfs(
+        storage: impl Into<String>,
+        server: impl Into<String>,
+        export: impl Into<String>,
+    ) -> Self {
+        let mut req = Self::new(storage, StorageType::Nfs);
+        req.server = Some(server.into());
+        req.export = Some(export.into());
+        req
+    }
+
+    pub fn zfspool(storage: impl Into<String>, pool: impl Into<String>) -> Self {
+        let mut req = Self::new(storage, StorageType::Zfspool);
+        req.pool = Some(pool.into());
+        req
+    }
+
+    pub fn lvmthin(
+        storage: impl Into<String>,
+        vgname: impl Into<String>,
+        thinpool: impl Into<String>,
+    ) -> Self {
+        let mut req = Self::new(storage, StorageType::Lvmthin);
+        req.vgname = Some(vgname.into());
+        req.thinpool = Some(thinpool.into());
+        req
+    }
+
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert("storage", self.storage.clone());
+        params.insert("type", self.type_.to_string());
+        if !self.content.is_empty() {
+            params.insert("content", self.content.join(","));
+        }
+        params.insert_opt("path", self.path.clone());
+        params.insert_opt("server", self.server.clone());
+        params.insert_opt("export", self.export.clone());
+        params.insert_opt("share", self.share.clone());
+        params.insert_opt("pool", self.pool.clone());
+        params.insert_opt("vgname", self.vgname.clone());
+        params.insert_opt("thinpool", self.thinpool.clone());
+        params.insert_opt("datastore", self.datastore.clone());
+        params.insert_opt("username", self.username.clone());
+        params.insert_opt("password", self.password.clone());
+        params.insert_opt("fingerprint", self.fingerprint.clone());
+        if !self.nodes.is_empty() {
+            params.insert("nodes", self.nodes.join(","));
+        }
+        if let Some(shared) = self.shared {
+            params.insert_bool("shared", shared);
+        }
+        if let Some(enabled) = self.enabled {
+            params.insert_bool("disable", !enabled);
+        }
+        params.extend(&self.extra);
+        params
+    }
+}
+
+/// `PUT /storage/{storage}`. The type and backing location cannot be changed.
+#[derive(Debug, Clone, Default)]
+pub struct StorageUpdateRequest {
+    pub content: Option<Vec<String>>,
+    pub nodes: Option<Vec<String>>,
+    pub shared: Option<bool>,
+    pub enabled: Option<bool>,
+    /// Comma-separated options to reset to their defaults.
+    pub delete: Option<String>,
+    pub digest: Option<String>,
+    pub extra: PveParams,
+}
+
+impl StorageUpdateRequest {
+    pub fn to_params(&self) -> PveParams {
+        let mut params = PveParams::new();
+        params.insert_opt("content", self.content.as_ref().map(|c| c.join(",")));
+        params.insert_opt("nodes", self.nodes.as_ref().map(|n| n.join(",")));
+        if let Some(shared) = self.shared {
+            params.insert_bool("shared", shared);
+        }
+        if let Some(enabled) = self.enabled {
+            params.insert_bool("disable", !enabled);
+        }
+        params.insert_opt("delete", self.delete.clone());
+        params.insert_opt("digest", self.digest.clone());
+        params.extend(&self.extra);
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        NodeStorageStatus, StorageContentQuery, StorageCreateRequest, StorageUpdateRequest,
+    };
+
+    fn status(used: Option<u64>, total: Option<u64>) -> NodeStorageStatus {
+        serde_json::from_value(serde_json::json!({
+            "storage": "local",
+            "used": used,
+            "total": total,
+        }))
+        .expect("status")
+    }
+
+    #[test]
+    fn storage_flags_accept_ints_and_bools() {
+        let status: NodeStorageStatus =
+            serde_json::from_str(r#"{"storage":"ceph","active":1,"enabled":true,"shared":"0"}"#)
+                .expect("status");
+        assert!(status.is_active());
+        assert!(status.is_enabled());
+        assert!(!status.is_shared());
+        assert_eq!(status.enabled, Some(1));
+    }
+
+    #[test]
+    fn percent_used_handles_missing_and_zero_total() {
+        let usage = status(Some(3 << 30), Some(4 << 30));
+        assert_eq!(usage.percent_used(), Some(75.0));
+        assert_eq!(usage.human_used(), Some(3.0));
+        assert_eq!(usage.human_total(), Some(4.0));
+        assert!(usage.is_over(70.0));
+        assert!(!usage.is_over(75.0));
+
+        assert_eq!(status(Some(0), Some(0)).percent_used(), None);
+        assert_eq!(status(None, Some(1)).percent_used(), None);
+        assert_eq!(status(Some(1), None).human_total(), None);
+        assert!(!status(None, None).is_over(0.0));
+    }
+
+    #[test]
+    fn content_types_joins_into_comma_list() {
+        let query = StorageContentQuery::content_types(&["images", "rootdir"]);
+        assert_eq!(query.to_params().get("content"), Some("images,rootdir"));
+        assert!(query.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_vmid_with_guestless_content() {
+        let query = StorageContentQuery {
+            content: Some("iso".to_string()),
+            vmid: Some(100),
+        };
+        assert!(query.validate().is_err());
+
+        let query = StorageContentQuery {
+            content: Some("backup".to_string()),
+            vmid: Some(100),
+        };
+        assert!(query.validate().is_ok());
+
+        assert!(StorageContentQuery::content_types(&[]).validate().is_err());
+    }
+
+    #[test]
+    fn storage_create_request_maps_enabled_to_disable() {
+        let mut req = StorageCreateRequest::nfs("backup-nfs", "10.0.0.9", "/export/pve");
+        req.content = vec!["backup".to_string(), "iso".to_string()];
+        req.nodes = vec!["pve1".to_string(), "pve2".to_string()];
+        req.enabled = Some(false);
+
+        let params = req.to_params();
+        assert_eq!(params.get("type"), Some("nfs"));
+        assert_eq!(params.get("export"), Some("/export/pve"));
+        assert_eq!(params.get("content"), Some("backup,iso"));
+        assert_eq!(params.get("nodes"), Some("pve1,pve2"));
+        assert_eq!(params.get("disable"), Some("1"));
+        assert_eq!(params.get("shared"), None);
+
+        let update = StorageUpdateRequest {
+            content: Some(vec!["images".to_string()]),
+            enabled: Some(true),
+            ..Default::default()
+        };
+        let params = update.to_params();
+        assert_eq!(params.get("content"), Some("images"));
+        assert_eq!(params.get("disable"), Some("0"));
+        assert_eq!(params.get("storage"), None);
+    }
+}
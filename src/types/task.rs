@@ -1,13 +1,262 @@
 //! Task related request/response types.
 
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::error::PveError;
 use crate::params::PveParams;
 
+/// Parsed task identifier: `UPID:{node}:{pid}:{pstart}:{starttime}:{type}:{id}:{user}:`.
+///
+/// `pid`, `pstart` and `starttime` are hex encoded by PVE.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upid {
+    raw: String,
+    node: String,
+    pid: u32,
+    pstart: u64,
+    start_time: u64,
+    task_type: String,
+    id: String,
+    user: String,
+}
+
+impl Upid {
+    pub fn parse(value: &str) -> Result<Self, PveError> {
+        let invalid = || PveError::InvalidArgument(format!("invalid upid: {value:?}"));
+        let rest = value.strip_prefix("UPID:").ok_or_else(invalid)?;
+        let rest = rest.strip_suffix(':').unwrap_or(rest);
+
+        let mut parts = rest.splitn(7, ':');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let node = next()?;
+        let pid = next()?;
+        let pstart = next()?;
+        let start_time = next()?;
+        let task_type = next()?;
+        let id = next()?;
+        let user = next()?;
+
+        if node.is_empty() || task_type.is_empty() || user.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            raw: value.to_string(),
+            node: node.to_string(),
+            pid: u32::from_str_radix(pid, 16).map_err(|_| invalid())?,
+            pstart: u64::from_str_radix(pstart, 16).map_err(|_| invalid())?,
+            start_time: u64::from_str_radix(start_time, 16).map_err(|_| invalid())?,
+            task_type: task_type.to_string(),
+            id: id.to_string(),
+            user: user.to_string(),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn node(&self) -> &str {
+        &self.node
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn pstart(&self) -> u64 {
+        self.pstart
+    }
+
+    pub fn task_type(&self) -> &str {
+        &self.task_type
+    }
+
+    pub fn kind(&self) -> TaskType {
+        TaskType::from(self.task_type.as_str())
+    }
+
+    /// Task subject such as the VMID; `None` for tasks without one.
+    pub fn id(&self) -> Option<&str> {
+        if self.id.is_empty() {
+            None
+        } else {
+            Some(&self.id)
+        }
+    }
+
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    /// Task start as Unix epoch seconds.
+    pub fn start_time(&self) -> u64 {
+        self.start_time
+    }
+}
+
+impl FromStr for Upid {
+    type Err = PveError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse(value)
+    }
+}
+
+impl AsRef<str> for Upid {
+    fn as_ref(&self) -> &str {
+        &self.raw
+    }
+}
+
+impl fmt::Display for Upid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// Task type as found in UPIDs and task listings. Unknown types are kept as `Other`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskType {
+    QmCreate,
+    QmStart,
+    QmStop,
+    QmShutdown,
+    QmReboot,
+    QmSuspend,
+    QmResume,
+    QmClone,
+    QmMigrate,
+    QmDestroy,
+    QmSnapshot,
+    QmRollback,
+    QmDelSnapshot,
+    QmMove,
+    QmRestore,
+    QmTemplate,
+    VzCreate,
+    VzStart,
+    VzStop,
+    VzShutdown,
+    VzReboot,
+    VzClone,
+    VzMigrate,
+    VzDestroy,
+    VzSnapshot,
+    VzRollback,
+    VzDelSnapshot,
+    VzRestore,
+    VzTemplate,
+    Vzdump,
+    AptUpdate,
+    Download,
+    ImgCopy,
+    StartAll,
+    StopAll,
+    MigrateAll,
+    SrvReload,
+    SrvRestart,
+    VncProxy,
+    VncShell,
+    TermProxy,
+    SpiceProxy,
+    Other(String),
+}
+
+/// Wire names of every known `TaskType`.
+const TASK_TYPES: &[(TaskType, &str)] = &[
+    (TaskType::QmCreate, "qmcreate"),
+    (TaskType::QmStart, "qmstart"),
+    (TaskType::QmStop, "qmstop"),
+    (TaskType::QmShutdown, "qmshutdown"),
+    (TaskType::QmReboot, "qmreboot"),
+    (TaskType::QmSuspend, "qmsuspend"),
+    (TaskType::QmResume, "qmresume"),
+    (TaskType::QmClone, "qmclone"),
+    (TaskType::QmMigrate, "qmigrate"),
+    (TaskType::QmDestroy, "qmdestroy"),
+    (TaskType::QmSnapshot, "qmsnapshot"),
+    (TaskType::QmRollback, "qmrollback"),
+    (TaskType::QmDelSnapshot, "qmdelsnapshot"),
+    (TaskType::QmMove, "qmmove"),
+    (TaskType::QmRestore, "qmrestore"),
+    (TaskType::QmTemplate, "qmtemplate"),
+    (TaskType::VzCreate, "vzcreate"),
+    (TaskType::VzStart, "vzstart"),
+    (TaskType::VzStop, "vzstop"),
+    (TaskType::VzShutdown, "vzshutdown"),
+    (TaskType::VzReboot, "vzreboot"),
+    (TaskType::VzClone, "vzclone"),
+    (TaskType::VzMigrate, "vzmigrate"),
+    (TaskType::VzDestroy, "vzdestroy"),
+    (TaskType::VzSnapshot, "vzsnapshot"),
+    (TaskType::VzRollback, "vzrollback"),
+    (TaskType::VzDelSnapshot, "vzdelsnapshot"),
+    (TaskType::VzRestore, "vzrestore"),
+    (TaskType::VzTemplate, "vztemplate"),
+    (TaskType::Vzdump, "vzdump"),
+    (TaskType::AptUpdate, "aptupdate"),
+    (TaskType::Download, "download"),
+    (TaskType::ImgCopy, "imgcopy"),
+    (TaskType::StartAll, "startall"),
+    (TaskType::StopAll, "stopall"),
+    (TaskType::MigrateAll, "migrateall"),
+    (TaskType::SrvReload, "srvreload"),
+    (TaskType::SrvRestart, "srvrestart"),
+    (TaskType::VncProxy, "vncproxy"),
+    (TaskType::VncShell, "vncshell"),
+    (TaskType::TermProxy, "termproxy"),
+    (TaskType::SpiceProxy, "spiceproxy"),
+];
+
+impl TaskType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Other(raw) => raw,
+            known => TASK_TYPES
+                .iter()
+                .find(|(task_type, _)| task_type == known)
+                .map(|(_, raw)| *raw)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Guest or node-wide migration, including HA-driven ones.
+    pub fn is_migration(&self) -> bool {
+        matches!(self, Self::QmMigrate | Self::VzMigrate | Self::MigrateAll)
+    }
+}
+
+impl From<&str> for TaskType {
+    fn from(raw: &str) -> Self {
+        TASK_TYPES
+            .iter()
+            .find(|(_, known)| *known == raw)
+            .map(|(task_type, _)| task_type.clone())
+            .unwrap_or_else(|| Self::Other(raw.to_string()))
+    }
+}
+
+impl fmt::Display for TaskType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+pub(crate) fn task_kind(task_type: Option<&str>, upid: Option<&str>) -> TaskType {
+    match (task_type, upid.and_then(|upid| Upid::parse(upid).ok())) {
+        (Some(task_type), _) => TaskType::from(task_type),
+        (None, Some(upid)) => upid.kind(),
+        (None, None) => TaskType::Other(String::new()),
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TaskStatus {
     pub upid: Option<String>,
@@ -22,12 +271,126 @@ pub struct TaskStatus {
     pub extra: HashMap<String, Value>,
 }
 
+impl TaskStatus {
+    /// Typed `task_type`, falling back to the type embedded in the UPID.
+    pub fn kind(&self) -> TaskType {
+        task_kind(self.task_type.as_deref(), self.upid.as_deref())
+    }
+
+    /// Successful status for work that needed no task, e.g. a guest already in the
+    /// requested state.
+    pub(crate) fn completed_without_task(node: &str) -> Self {
+        Self {
+            upid: None,
+            task_type: None,
+            status: "stopped".to_string(),
+            exitstatus: Some("OK".to_string()),
+            user: None,
+            starttime: None,
+            node: Some(node.to_string()),
+            extra: HashMap::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TaskLogLine {
     pub n: Option<u64>,
     pub t: String,
 }
 
+#[cfg(feature = "time")]
+impl TaskLogLine {
+    /// Leading timestamp of the log text, if present.
+    ///
+    /// RFC 3339 prefixes keep their offset. PVE's own `YYYY-MM-DD HH:MM:SS` prefix carries
+    /// no zone and is interpreted as UTC.
+    pub fn timestamp(&self) -> Option<time::OffsetDateTime> {
+        use time::format_description::well_known::Rfc3339;
+        use time::macros::format_description;
+        use time::{OffsetDateTime, PrimitiveDateTime};
+
+        let first = self.t.split_whitespace().next()?;
+        if let Ok(ts) = OffsetDateTime::parse(first, &Rfc3339) {
+            return Some(ts);
+        }
+
+        let head = self.t.get(..19)?;
+        let format = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+        PrimitiveDateTime::parse(head, format)
+            .ok()
+            .map(PrimitiveDateTime::assume_utc)
+    }
+}
+
+/// Progress reported by a clone, disk move or migration log line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransferProgress {
+    pub transferred: u64,
+    pub total: u64,
+    pub percent: f64,
+}
+
+/// Recognizes the progress lines PVE writes for long transfers:
+///
+/// - `drive-scsi0: transferred 1.0 GiB of 32.0 GiB (3.12%) in 5s`
+/// - `migration active, transferred 1.2 GiB of 4.0 GiB VM-state, 110.5 MiB/s`
+/// - `migration status: active (transferred 123456, remaining 789, total 4295032832)`
+///
+/// Sizes rendered with units are rounded by PVE, so `transferred` and `total` are
+/// approximate for the first two forms. `percent` is taken from the line when present.
+pub fn parse_transfer_progress(line: &str) -> Option<TransferProgress> {
+    let (_, rest) = line.split_once("transferred ")?;
+    let (transferred, total) = match rest.split_once(" of ") {
+        Some((done, total)) => (parse_size(done)?, parse_size(total)?),
+        None => {
+            let (_, total) = rest.split_once("total ")?;
+            (parse_size(rest)?, parse_size(total)?)
+        }
+    };
+    let percent = explicit_percent(rest).unwrap_or_else(|| {
+        if total == 0 {
+            0.0
+        } else {
+            transferred as f64 * 100.0 / total as f64
+        }
+    });
+    Some(TransferProgress {
+        transferred,
+        total,
+        percent,
+    })
+}
+
+/// Leading `<number> [unit]` of `text`, in bytes.
+fn parse_size(text: &str) -> Option<u64> {
+    let mut tokens = text
+        .split_whitespace()
+        .map(|token| token.trim_end_matches([',', ')']));
+    let value: f64 = tokens.next()?.parse().ok()?;
+    let scale = tokens.next().and_then(unit_scale).unwrap_or(1);
+    Some((value * scale as f64).round() as u64)
+}
+
+fn unit_scale(unit: &str) -> Option<u64> {
+    let shift = match unit {
+        "B" | "bytes" => 0,
+        "KiB" | "KB" | "K" => 10,
+        "MiB" | "MB" | "M" => 20,
+        "GiB" | "GB" | "G" => 30,
+        "TiB" | "TB" | "T" => 40,
+        "PiB" | "PB" | "P" => 50,
+        _ => return None,
+    };
+    Some(1 << shift)
+}
+
+fn explicit_percent(text: &str) -> Option<f64> {
+    let end = text.find("%)")?;
+    let start = text[..end].rfind('(')?;
+    text[start + 1..end].trim().parse().ok()
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct TaskLogQuery {
     pub start: Option<u64>,
@@ -57,3 +420,139 @@ impl Default for WaitTaskOptions {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        TASK_TYPES, TaskStatus, TaskType, TransferProgress, Upid, parse_transfer_progress,
+    };
+
+    #[test]
+    fn upid_parses_guest_task() {
+        let upid = Upid::parse("UPID:pve1:000A1B2C:01F4E3D2:65A4F0B1:qmstart:100:root@pam:")
+            .expect("upid");
+        assert_eq!(upid.node(), "pve1");
+        assert_eq!(upid.pid(), 0x000A_1B2C);
+        assert_eq!(upid.start_time(), 0x65A4_F0B1);
+        assert_eq!(upid.task_type(), "qmstart");
+        assert_eq!(upid.id(), Some("100"));
+        assert_eq!(upid.user(), "root@pam");
+        assert_eq!(
+            upid.to_string(),
+            "UPID:pve1:000A1B2C:01F4E3D2:65A4F0B1:qmstart:100:root@pam:"
+        );
+    }
+
+    #[test]
+    fn upid_parses_task_without_id_and_token_user() {
+        let upid = Upid::parse("UPID:node-2:0002F3A1:0A5B6C7D:66000000:aptupdate::ci@pve!deploy:")
+            .expect("upid");
+        assert_eq!(upid.id(), None);
+        assert_eq!(upid.user(), "ci@pve!deploy");
+    }
+
+    #[test]
+    fn upid_rejects_malformed_values() {
+        assert!(Upid::parse("pve1:qmstart").is_err());
+        assert!(Upid::parse("UPID:pve1:zz:01F4E3D2:65A4F0B1:qmstart:100:root@pam:").is_err());
+        assert!(Upid::parse("UPID:pve1:000A1B2C:01F4E3D2").is_err());
+    }
+
+    #[cfg(feature = "time")]
+    fn line(t: &str) -> super::TaskLogLine {
+        super::TaskLogLine {
+            n: Some(1),
+            t: t.to_string(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn task_log_line_parses_pve_timestamp_prefix() {
+        let ts = line("2024-01-15 10:23:45 starting migration of VM 100 to node 'pve2'")
+            .timestamp()
+            .expect("timestamp");
+        assert_eq!(ts.unix_timestamp(), 1_705_314_225);
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn task_log_line_parses_rfc3339_prefix() {
+        let ts = line("2024-01-15T10:23:45+02:00 backup started")
+            .timestamp()
+            .expect("timestamp");
+        assert_eq!(ts.unix_timestamp(), 1_705_307_025);
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn task_log_line_without_timestamp_returns_none() {
+        assert!(line("INFO: starting new backup job").timestamp().is_none());
+        assert!(line("TASK OK").timestamp().is_none());
+    }
+
+    #[test]
+    fn task_type_maps_known_and_unknown_strings() {
+        assert_eq!(TaskType::from("qmigrate"), TaskType::QmMigrate);
+        assert_eq!(TaskType::from("vzdump"), TaskType::Vzdump);
+        assert_eq!(TaskType::from("aptupdate"), TaskType::AptUpdate);
+        assert_eq!(TaskType::from("vzstart"), TaskType::VzStart);
+        assert_eq!(
+            TaskType::from("cephcreateosd"),
+            TaskType::Other("cephcreateosd".to_string())
+        );
+        assert_eq!(TaskType::QmDelSnapshot.to_string(), "qmdelsnapshot");
+        assert_eq!(
+            TaskType::Other("zfscreate".to_string()).as_str(),
+            "zfscreate"
+        );
+        assert!(TaskType::from("migrateall").is_migration());
+        for (task_type, raw) in TASK_TYPES {
+            assert_eq!(&TaskType::from(*raw), task_type);
+            assert_eq!(task_type.as_str(), *raw);
+        }
+    }
+
+    #[test]
+    fn task_kind_falls_back_to_upid() {
+        let status: TaskStatus = serde_json::from_str(
+            r#"{"upid":"UPID:pve1:000A1B2C:01F4E3D2:65A4F0B1:qmclone:100:root@pam:","status":"running"}"#,
+        )
+        .expect("status");
+        assert_eq!(status.kind(), TaskType::QmClone);
+    }
+
+    #[test]
+    fn transfer_progress_parses_pve_log_forms() {
+        let mirror =
+            parse_transfer_progress("drive-scsi0: transferred 1.0 GiB of 32.0 GiB (3.12%) in 5s")
+                .expect("mirror");
+        assert_eq!(mirror.transferred, 1 << 30);
+        assert_eq!(mirror.total, 32 << 30);
+        assert_eq!(mirror.percent, 3.12);
+
+        let live = parse_transfer_progress(
+            "2024-01-15 10:00:05 migration active, transferred 1.0 GiB of 4.0 GiB VM-state, 110.5 MiB/s",
+        )
+        .expect("live");
+        assert_eq!(live.total, 4 << 30);
+        assert_eq!(live.percent, 25.0);
+
+        assert_eq!(
+            parse_transfer_progress(
+                "migration status: active (transferred 1024, remaining 3072, total 4096)"
+            ),
+            Some(TransferProgress {
+                transferred: 1024,
+                total: 4096,
+                percent: 25.0,
+            })
+        );
+
+        assert_eq!(
+            parse_transfer_progress("starting migration of VM 100"),
+            None
+        );
+        assert_eq!(parse_transfer_progress("transferred some data"), None);
+    }
+}
@@ -0,0 +1,155 @@
+//! Client-side checks for identifiers PVE would otherwise reject with a bare 400.
+
+use crate::error::PveError;
+
+/// Lowest guest id PVE hands out; ids below it are reserved.
+pub const MIN_VMID: u32 = 100;
+/// Highest guest id PVE accepts.
+pub const MAX_VMID: u32 = 999_999_999;
+
+/// Accepts a unicast MAC address in `aa:bb:cc:dd:ee:ff` form (either case).
+pub fn validate_mac(mac: &str) -> Result<(), PveError> {
+    let octets: Vec<&str> = mac.split(':').collect();
+    let well_formed = octets.len() == 6
+        && octets
+            .iter()
+            .all(|o| o.len() == 2 && o.chars().all(|c| c.is_ascii_hexdigit()));
+    if !well_formed {
+        return Err(PveError::InvalidArgument(format!(
+            "invalid mac address {mac:?}, expected aa:bb:cc:dd:ee:ff"
+        )));
+    }
+    let first = u8::from_str_radix(octets[0], 16).unwrap_or_default();
+    if first & 0x01 != 0 {
+        return Err(PveError::InvalidArgument(format!(
+            "mac address {mac:?} is multicast"
+        )));
+    }
+    Ok(())
+}
+
+pub fn validate_vmid(vmid: u32) -> Result<(), PveError> {
+    if !(MIN_VMID..=MAX_VMID).contains(&vmid) {
+        return Err(PveError::InvalidArgument(format!(
+            "vmid {vmid} out of range {MIN_VMID}..={MAX_VMID}"
+        )));
+    }
+    Ok(())
+}
+
+/// Accepts a DNS name: dot-separated labels of letters, digits and inner hyphens.
+pub fn validate_hostname(hostname: &str) -> Result<(), PveError> {
+    let invalid = |reason: &str| {
+        Err(PveError::InvalidArgument(format!(
+            "invalid hostname {hostname:?}: {reason}"
+        )))
+    };
+    if hostname.is_empty() {
+        return invalid("empty");
+    }
+    if hostname.len() > 253 {
+        return invalid("longer than 253 characters");
+    }
+    for label in hostname.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return invalid("each label must be 1-63 characters");
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return invalid("labels cannot start or end with '-'");
+        }
+        if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return invalid("only letters, digits and '-' are allowed");
+        }
+    }
+    Ok(())
+}
+
+/// NIC models QEMU accepts as the leading `<model>=<mac>` part of a `netN` string.
+const QEMU_NIC_MODELS: &[&str] = &[
+    "e1000",
+    "e1000-82540em",
+    "e1000-82544gc",
+    "e1000-82545em",
+    "e1000e",
+    "i82551",
+    "i82557b",
+    "i82559er",
+    "ne2k_isa",
+    "ne2k_pci",
+    "pcnet",
+    "rtl8139",
+    "virtio",
+    "vmxnet3",
+];
+
+/// Validates the MAC embedded in a net device string, if any. `mac_keys` lists
+/// the option names carrying it (`macaddr` for QEMU, `hwaddr` for LXC); with
+/// `leading`, a first part keyed by a QEMU NIC model (`virtio=<mac>`) counts too.
+pub(crate) fn validate_net_mac(
+    net: &str,
+    mac_keys: &[&str],
+    leading: bool,
+) -> Result<(), PveError> {
+    for (idx, part) in net.split(',').enumerate() {
+        let Some((key, value)) = part.split_once('=') else {
+            continue;
+        };
+        if mac_keys.contains(&key) || (leading && idx == 0 && QEMU_NIC_MODELS.contains(&key)) {
+            validate_mac(value)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_hostname, validate_mac, validate_net_mac, validate_vmid};
+
+    #[test]
+    fn mac_requires_six_hex_octets_and_unicast() {
+        assert!(validate_mac("BC:24:11:AA:bb:01").is_ok());
+        assert!(validate_mac("bc:24:11:aa:bb").is_err());
+        assert!(validate_mac("bc-24-11-aa-bb-01").is_err());
+        assert!(validate_mac("bc:24:11:aa:bb:0g").is_err());
+        assert!(validate_mac("01:00:5e:00:00:01").is_err());
+    }
+
+    #[test]
+    fn vmid_range_matches_pve() {
+        assert!(validate_vmid(99).is_err());
+        assert!(validate_vmid(100).is_ok());
+        assert!(validate_vmid(999_999_999).is_ok());
+        assert!(validate_vmid(1_000_000_000).is_err());
+    }
+
+    #[test]
+    fn hostname_labels_are_checked() {
+        assert!(validate_hostname("web-01.example.com").is_ok());
+        assert!(validate_hostname("").is_err());
+        assert!(validate_hostname("-web").is_err());
+        assert!(validate_hostname("web..example").is_err());
+        assert!(validate_hostname("web_01").is_err());
+        assert!(validate_hostname(&"a".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn net_mac_is_found_by_key_or_leading_model() {
+        assert!(
+            validate_net_mac("virtio=BC:24:11:00:00:01,bridge=vmbr0", &["macaddr"], true).is_ok()
+        );
+        assert!(validate_net_mac("virtio=nope,bridge=vmbr0", &["macaddr"], true).is_err());
+        assert!(validate_net_mac("virtio,bridge=vmbr0", &["macaddr"], true).is_ok());
+        assert!(validate_net_mac("e1000=zz,bridge=vmbr0", &["macaddr"], true).is_err());
+        assert!(validate_net_mac("name=eth0,hwaddr=zz,bridge=vmbr0", &["hwaddr"], false).is_err());
+        assert!(validate_net_mac("name=eth0,bridge=vmbr0", &["hwaddr"], false).is_ok());
+    }
+
+    #[test]
+    fn net_mac_ignores_leading_non_model_keys() {
+        assert!(validate_net_mac("bridge=vmbr0,virtio", &["macaddr"], true).is_ok());
+        assert!(validate_net_mac("model=virtio,bridge=vmbr0", &["macaddr"], true).is_ok());
+        assert!(
+            validate_net_mac("model=virtio,macaddr=zz,bridge=vmbr0", &["macaddr"], true).is_err()
+        );
+    }
+}
@@ -1,8 +1,15 @@
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use pve_sdk_rs::types::task::WaitTaskOptions;
-use pve_sdk_rs::{ClientOption, PveError};
+use futures_util::{StreamExt, TryStreamExt};
+use pve_sdk_rs::types::common::{CreateOutcome, GuestFeature, GuestState};
+use pve_sdk_rs::types::qemu::{
+    QemuActionRequest, QemuCloneRequest, QemuCreateRequest, QemuEnsureStateOptions,
+    QemuMigrateRequest, QemuProvisionRequest, QemuSetConfigRequest,
+};
+use pve_sdk_rs::types::storage::{StorageContentQuery, StorageCreateRequest, StorageUpdateRequest};
+use pve_sdk_rs::types::task::{TaskType, Upid, WaitTaskOptions};
+use pve_sdk_rs::{ClientOption, DryRunRequest, PveError, PveParams, RequestInfo, ResponseInfo};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::task::JoinHandle;
@@ -50,6 +57,15 @@ impl MockResponse {
             body: body.to_string(),
         }
     }
+
+    fn html(status_code: u16, reason_phrase: &'static str, body: &str) -> Self {
+        Self {
+            status_code,
+            reason_phrase,
+            content_type: "text/html; charset=utf-8",
+            body: body.to_string(),
+        }
+    }
 }
 
 async fn spawn_mock_server<F>(responder: F) -> MockServer
@@ -161,6 +177,28 @@ async fn version_parses_data_envelope() {
     assert!(paths.iter().any(|path| path == "/api2/json/version"));
 }
 
+#[tokio::test]
+async fn node_version_is_requested_per_node() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/nodes/pve1/version" => {
+            MockResponse::json(200, "OK", r#"{"data":{"version":"8.2.4","release":"8.2"}}"#)
+        }
+        "/api2/json/nodes/pve2/version" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":{"version":"8.1.10","release":"8.1"}}"#,
+        ),
+        _ => MockResponse::json(404, "Not Found", r#"{"data":null}"#),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let pve1 = client.node().version("pve1").await.expect("pve1 version");
+    let pve2 = client.node_version("pve2").await.expect("pve2 version");
+    assert_eq!(pve1.version, "8.2.4");
+    assert_eq!(pve2.release.as_deref(), Some("8.1"));
+}
+
 #[tokio::test]
 async fn api_status_error_surfaces_401_body() {
     let server = spawn_mock_server(|_method, _path| {
@@ -226,3 +264,1819 @@ async fn wait_for_task_returns_timeout_when_status_never_stops() {
         other => panic!("expected TaskTimeout, got: {other:?}"),
     }
 }
+
+#[tokio::test]
+async fn provision_from_template_reports_failed_phase() {
+    let server = spawn_mock_server(|method, path| {
+        if method == "POST" && path.ends_with("/qemu/9000/clone") {
+            return MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":"UPID:pve1:0001:0002:0003:qmclone:9000:root@pam:"}"#,
+            );
+        }
+        if method == "GET" && path.contains("/tasks/") {
+            return MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":{"status":"stopped","exitstatus":"OK"}}"#,
+            );
+        }
+        MockResponse::text(500, "Internal Server Error", "config update failed")
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let mut clone = QemuCloneRequest::new(321);
+    clone.full = Some(true);
+    let request = QemuProvisionRequest::new(clone)
+        .config(QemuSetConfigRequest {
+            memory: Some(2048),
+            ..QemuSetConfigRequest::default()
+        })
+        .start(true)
+        .wait(WaitTaskOptions {
+            poll_interval: Duration::from_millis(5),
+            timeout: Some(Duration::from_secs(1)),
+        });
+
+    let err = client
+        .qemu_provision_from_template("pve1", 9000, &request)
+        .await
+        .expect_err("configure must fail");
+
+    match err {
+        PveError::ProvisionFailed { phase, source } => {
+            assert_eq!(phase, "configure");
+            assert!(matches!(*source, PveError::ApiStatus { status: 500, .. }));
+        }
+        other => panic!("expected ProvisionFailed, got: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn request_and_response_hooks_observe_calls() {
+    let server = spawn_mock_server(|_method, path| {
+        if path.ends_with("/version") {
+            MockResponse::json(200, "OK", r#"{"data":{"version":"8.2.1"}}"#)
+        } else {
+            MockResponse::text(500, "Internal Server Error", "boom")
+        }
+    })
+    .await;
+
+    let requests = Arc::new(Mutex::new(Vec::<String>::new()));
+    let responses = Arc::new(Mutex::new(Vec::<(String, Option<u16>)>::new()));
+    let seen_requests = Arc::clone(&requests);
+    let seen_responses = Arc::clone(&responses);
+
+    let client = ClientOption::new("127.0.0.1")
+        .port(server.port())
+        .https(false)
+        .api_token("root@pam!ci=secret")
+        .on_request(Arc::new(move |info: &RequestInfo| {
+            seen_requests
+                .lock()
+                .expect("requests lock")
+                .push(format!("{} {}", info.method, info.path));
+        }))
+        .on_response(Arc::new(move |info: &ResponseInfo| {
+            seen_responses
+                .lock()
+                .expect("responses lock")
+                .push((info.path.clone(), info.status));
+        }))
+        .build()
+        .await
+        .expect("build client");
+
+    client.version().await.expect("version");
+    let _ = client.nodes().await.expect_err("nodes must fail");
+
+    let requests = requests.lock().expect("requests lock");
+    assert_eq!(
+        *requests,
+        vec!["GET /api2/json/version", "GET /api2/json/nodes"]
+    );
+    assert!(requests.iter().all(|line| !line.contains("secret")));
+
+    let responses = responses.lock().expect("responses lock");
+    assert_eq!(
+        *responses,
+        vec![
+            ("/api2/json/version".to_string(), Some(200)),
+            ("/api2/json/nodes".to_string(), Some(500)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn cluster_log_passes_max_and_decodes_entries() {
+    let server = spawn_mock_server(|_method, path| {
+        if path == "/api2/json/cluster/log?max=2" {
+            MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":[{"uid":7,"time":1700000000,"node":"pve1","user":"root@pam","tag":"pvedaemon","pri":6,"pid":1234,"msg":"starting task"}]}"#,
+            )
+        } else {
+            MockResponse::text(404, "Not Found", "unexpected path")
+        }
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let entries = client.cluster().log(Some(2)).await.expect("cluster log");
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].uid, Some(7));
+    assert_eq!(entries[0].node.as_deref(), Some("pve1"));
+    assert_eq!(entries[0].pri, Some(6));
+    assert_eq!(entries[0].msg.as_deref(), Some("starting task"));
+}
+
+#[tokio::test]
+async fn ct_templates_filter_storage_content_by_type() {
+    let server = spawn_mock_server(|_method, path| {
+        if path == "/api2/json/nodes/pve1/storage/local/content?content=vztmpl" {
+            MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":[
+                    {"volid":"local:vztmpl/debian-12-standard_12.2-1_amd64.tar.zst","content":"vztmpl","format":"tzst","size":123},
+                    {"volid":"local:iso/debian-12.iso","content":"iso","format":"iso","size":456}
+                ]}"#,
+            )
+        } else {
+            MockResponse::text(404, "Not Found", "unexpected path")
+        }
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let templates = client
+        .lxc()
+        .templates("pve1", "local")
+        .await
+        .expect("templates");
+
+    assert_eq!(templates.len(), 1);
+    assert_eq!(
+        templates[0].volid,
+        "local:vztmpl/debian-12-standard_12.2-1_amd64.tar.zst"
+    );
+}
+
+#[tokio::test]
+async fn qemu_exists_maps_missing_config_to_false() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/nodes/pve1/qemu/100/status/current" => {
+            MockResponse::json(200, "OK", r#"{"data":{"vmid":100,"status":"running"}}"#)
+        }
+        "/api2/json/nodes/pve1/qemu/101/status/current" => MockResponse::json(
+            500,
+            "Internal Server Error",
+            r#"{"data":null,"message":"Configuration file 'nodes/pve1/qemu-server/101.conf' does not exist\n"}"#,
+        ),
+        _ => MockResponse::text(403, "Forbidden", "permission denied"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    assert!(client.qemu().exists("pve1", 100).await.expect("exists"));
+    assert!(!client.qemu().exists("pve1", 101).await.expect("missing"));
+    assert!(
+        client
+            .qemu()
+            .status_opt("pve1", 101)
+            .await
+            .expect("missing")
+            .is_none()
+    );
+
+    let err = client.qemu().exists("pve1", 102).await.expect_err("403");
+    assert!(matches!(err, PveError::PermissionDenied { .. }));
+}
+
+#[tokio::test]
+async fn storage_content_stream_yields_items_incrementally() {
+    let server = spawn_mock_server(|_method, path| {
+        if path == "/api2/json/nodes/pve1/storage/local/content?content=iso" {
+            MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":[{"volid":"local:iso/a.iso","size":1},{"volid":"local:iso/b.iso","size":2},{"volid":"local:iso/c.iso","size":3}]}"#,
+            )
+        } else {
+            MockResponse::text(404, "Not Found", "unexpected path")
+        }
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let query = StorageContentQuery::content_types(&["iso"]);
+
+    let all: Vec<_> = client
+        .storage()
+        .content_stream("pve1", "local", &query)
+        .try_collect()
+        .await
+        .expect("stream items");
+    assert_eq!(all.len(), 3);
+    assert_eq!(all[2].volid, "local:iso/c.iso");
+
+    let first: Vec<_> = client
+        .storage()
+        .content_stream("pve1", "local", &query)
+        .take(1)
+        .collect()
+        .await;
+    assert_eq!(first.len(), 1);
+    assert_eq!(
+        first[0].as_ref().expect("first item").volid,
+        "local:iso/a.iso"
+    );
+}
+
+#[tokio::test]
+async fn storage_content_stream_rejects_misrouted_and_failed_replies() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/nodes/pve1/storage/local/content" => {
+            MockResponse::html(200, "OK", "<html><body>Please log in</body></html>")
+        }
+        "/api2/json/nodes/pve1/storage/bare/content" => {
+            MockResponse::json(200, "OK", r#"{"data":null}"#)
+        }
+        "/api2/json/nodes/pve1/storage/gone/content" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"success":0,"data":null,"message":"storage 'gone' does not exist\n"}"#,
+        ),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let query = StorageContentQuery::default();
+
+    let result: Result<Vec<_>, _> = client
+        .storage()
+        .content_stream("pve1", "local", &query)
+        .try_collect()
+        .await;
+    assert!(
+        matches!(result, Err(PveError::UnexpectedContentType { .. })),
+        "{result:?}"
+    );
+
+    let result: Result<Vec<_>, _> = client
+        .storage()
+        .content_stream("pve1", "bare", &query)
+        .try_collect()
+        .await;
+    assert!(matches!(result, Err(PveError::Decode(_))), "{result:?}");
+
+    let result: Result<Vec<_>, _> = client
+        .storage()
+        .content_stream("pve1", "gone", &query)
+        .try_collect()
+        .await;
+    assert!(
+        matches!(result, Err(PveError::NotFound { .. })),
+        "{result:?}"
+    );
+}
+
+#[tokio::test]
+async fn storage_definitions_are_created_updated_and_deleted() {
+    let requests = Arc::new(Mutex::new(Vec::<String>::new()));
+    let seen = Arc::clone(&requests);
+    let server = spawn_mock_server(move |method, path| {
+        seen.lock()
+            .expect("capture request")
+            .push(format!("{method} {path}"));
+        match (method, path) {
+            ("POST", "/api2/json/storage") => {
+                MockResponse::json(200, "OK", r#"{"data":{"storage":"tank","type":"zfspool"}}"#)
+            }
+            _ => MockResponse::json(200, "OK", r#"{"data":null}"#),
+        }
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let storage = client.storage();
+    let mut create = StorageCreateRequest::zfspool("tank", "tank/vmdata");
+    create.content = vec!["images".to_string(), "rootdir".to_string()];
+    storage.create_storage_with(&create).await.expect("create");
+    storage
+        .update_storage_with(
+            "tank",
+            &StorageUpdateRequest {
+                enabled: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("update");
+    storage.delete_storage("tank").await.expect("delete");
+
+    assert_eq!(
+        *requests.lock().expect("requests"),
+        [
+            "POST /api2/json/storage",
+            "PUT /api2/json/storage/tank",
+            "DELETE /api2/json/storage/tank",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn with_timeout_bounds_a_single_call() {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind listener");
+    let port = listener.local_addr().expect("listener addr").port();
+    let _server = tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok((socket, _addr)) = listener.accept().await {
+            held.push(socket);
+        }
+    });
+
+    let client = build_client(port).await;
+    let err = client
+        .with_timeout(Duration::from_millis(50))
+        .version()
+        .await
+        .expect_err("expected timeout");
+
+    match err {
+        PveError::Http(err) => assert!(err.is_timeout()),
+        other => panic!("expected http timeout, got: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn wait_for_upid_polls_the_node_from_the_upid() {
+    let server = spawn_mock_server(|_method, path| {
+        if path.starts_with("/api2/json/nodes/pve2/tasks/") && path.ends_with("/status") {
+            MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":{"status":"stopped","exitstatus":"OK"}}"#,
+            )
+        } else {
+            MockResponse::text(404, "Not Found", "unexpected path")
+        }
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let upid =
+        Upid::parse("UPID:pve2:000A1B2C:01F4E3D2:65A4F0B1:qmigrate:100:root@pam:").expect("upid");
+    let status = client
+        .wait_for_upid(&upid, &WaitTaskOptions::default())
+        .await
+        .expect("task status");
+
+    assert_eq!(status.exitstatus.as_deref(), Some("OK"));
+}
+
+#[tokio::test]
+async fn api_index_decodes_subdir_and_name_entries() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[{"subdir":"version"},{"subdir":"nodes"}]}"#,
+        ),
+        "/api2/json/nodes/pve1" => {
+            MockResponse::json(200, "OK", r#"{"data":[{"name":"qemu"},{"name":"lxc"}]}"#)
+        }
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let root = client.api_index("/").await.expect("root index");
+    assert_eq!(root[1].entry(), Some("nodes"));
+
+    let node = client.raw().index("/nodes/pve1").await.expect("node index");
+    let entries: Vec<_> = node.iter().filter_map(|item| item.entry()).collect();
+    assert_eq!(entries, ["qemu", "lxc"]);
+}
+
+#[tokio::test]
+async fn access_domain_fills_realm_from_path() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/access/domains/corp" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":{"type":"ldap","base_dn":"dc=corp","server1":"ldap.corp"}}"#,
+        ),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let domain = client.access().domain("corp").await.expect("domain");
+
+    assert_eq!(domain.realm, "corp");
+    assert_eq!(domain.type_, pve_sdk_rs::types::access::RealmType::Ldap);
+    assert_eq!(domain.extra["server1"], "ldap.corp");
+}
+
+#[tokio::test]
+async fn access_permissions_decodes_nested_privilege_map() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/access/permissions?userid=ops%40pve&path=%2Fvms" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":{"/vms":{"VM.Audit":1,"VM.PowerMgmt":0}}}"#,
+        ),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let perms = client
+        .access()
+        .permissions(Some("ops@pve"), Some("/vms"))
+        .await
+        .expect("permissions");
+
+    assert_eq!(perms["/vms"]["VM.Audit"], 1);
+    assert_eq!(perms["/vms"].get("Sys.Modify"), None);
+}
+
+#[tokio::test]
+async fn dry_run_skips_writes_and_reports_them() {
+    let hits = Arc::new(Mutex::new(Vec::<String>::new()));
+    let seen = Arc::clone(&hits);
+    let server = spawn_mock_server(move |method, path| {
+        seen.lock().expect("lock").push(format!("{method} {path}"));
+        match path {
+            "/api2/json/nodes/pve1/qemu/300/status/current" => {
+                MockResponse::json(200, "OK", r#"{"data":{"status":"stopped"}}"#)
+            }
+            _ => MockResponse::text(500, "Internal Server Error", "write reached server"),
+        }
+    })
+    .await;
+
+    let skipped = Arc::new(Mutex::new(Vec::<DryRunRequest>::new()));
+    let recorder = Arc::clone(&skipped);
+    let client = ClientOption::new("127.0.0.1")
+        .port(server.port())
+        .https(false)
+        .auth_none()
+        .dry_run(true)
+        .on_dry_run(Arc::new(move |req: &DryRunRequest| {
+            recorder.lock().expect("lock").push(req.clone());
+        }))
+        .build()
+        .await
+        .expect("build client");
+
+    let upid = client
+        .qemu()
+        .create_with("pve1", &QemuCreateRequest::new(300))
+        .await
+        .expect("dry-run create");
+    let status = client
+        .task()
+        .wait_upid(
+            &Upid::parse(&upid).expect("upid"),
+            &WaitTaskOptions::default(),
+        )
+        .await
+        .expect("dry-run wait");
+    assert_eq!(status.exitstatus.as_deref(), Some("OK"));
+    client
+        .qemu()
+        .delete("pve1", 300, &Default::default())
+        .await
+        .expect("dry-run delete");
+    let raw = client
+        .raw_post("/nodes/pve1/qemu/300/status/start", None)
+        .await
+        .expect("dry-run raw post");
+    assert!(raw.as_str().is_some_and(|upid| upid.contains(":dryrun:")));
+    let current = client.qemu_status("pve1", 300).await.expect("read");
+    assert_eq!(current.status.as_deref(), Some("stopped"));
+
+    let skipped = skipped.lock().expect("lock");
+    assert_eq!(skipped.len(), 3);
+    assert_eq!(skipped[0].method, "POST");
+    assert_eq!(skipped[0].path, "/nodes/pve1/qemu");
+    assert_eq!(skipped[0].params.get("vmid"), Some("300"));
+    assert_eq!(skipped[1].method, "DELETE");
+    assert_eq!(
+        *hits.lock().expect("lock"),
+        ["GET /api2/json/nodes/pve1/qemu/300/status/current"]
+    );
+}
+
+#[tokio::test]
+async fn qemu_create_idempotent_skips_taken_vmid() {
+    let creates = Arc::new(Mutex::new(0usize));
+    let counter = Arc::clone(&creates);
+    let server = spawn_mock_server(move |method, path| match (method, path) {
+        ("GET", "/api2/json/cluster/resources?type=vm") => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[{"id":"lxc/300","type":"lxc","node":"pve2","vmid":300}]}"#,
+        ),
+        ("POST", "/api2/json/nodes/pve1/qemu") => {
+            *counter.lock().expect("lock") += 1;
+            MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":"UPID:pve1:00001234:00000001:65A4F0B1:qmcreate:301:root@pam:"}"#,
+            )
+        }
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let taken = client
+        .qemu()
+        .create_idempotent("pve1", &QemuCreateRequest::new(300))
+        .await
+        .expect("taken vmid");
+    match taken {
+        CreateOutcome::AlreadyExists(existing) => {
+            assert_eq!(existing.resource_type, "lxc");
+            assert_eq!(existing.node.as_deref(), Some("pve2"));
+        }
+        other => panic!("unexpected outcome: {other:?}"),
+    }
+
+    let created = client
+        .qemu()
+        .create_idempotent("pve1", &QemuCreateRequest::new(301))
+        .await
+        .expect("free vmid");
+    assert!(created.is_created());
+    assert_eq!(*creates.lock().expect("lock"), 1);
+}
+
+#[tokio::test]
+async fn qemu_create_auto_id_retries_on_collision() {
+    let next_ids = Arc::new(Mutex::new(vec![401u32, 400]));
+    let ids = Arc::clone(&next_ids);
+    let server = spawn_mock_server(move |method, path| match (method, path) {
+        ("GET", "/api2/json/cluster/nextid") => {
+            let id = ids.lock().expect("lock").pop().expect("next id");
+            MockResponse::json(200, "OK", &format!(r#"{{"data":"{id}"}}"#))
+        }
+        ("POST", "/api2/json/nodes/pve1/qemu") => MockResponse::text(
+            500,
+            "Internal Server Error",
+            "unable to create VM 400 - VM 400 already exists on node 'pve2'",
+        ),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let err = client
+        .qemu()
+        .create_auto_id_with_attempts("pve1", &QemuCreateRequest::new(100), 2)
+        .await
+        .expect_err("both ids taken");
+
+    assert!(matches!(err, PveError::ApiStatus { status: 500, .. }));
+    assert!(next_ids.lock().expect("lock").is_empty());
+}
+
+#[tokio::test]
+async fn cluster_is_vmid_free_maps_taken_id_to_false() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/cluster/nextid?vmid=100" => MockResponse::json(
+            400,
+            "Parameter verification failed.",
+            r#"{"data":null,"errors":{"vmid":"VM 100 already exists"}}"#,
+        ),
+        "/api2/json/cluster/nextid?vmid=200" => MockResponse::json(200, "OK", r#"{"data":"200"}"#),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    assert!(!client.cluster().is_vmid_free(100).await.expect("taken"));
+    assert!(client.cluster().is_vmid_free(200).await.expect("free"));
+}
+
+#[tokio::test]
+async fn ticket_cache_reuses_login_across_builds() {
+    let issued = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock")
+        .as_secs();
+    let ticket = format!("PVE:root@pam:{issued:X}::sig");
+    let logins = Arc::new(Mutex::new(0usize));
+    let counter = Arc::clone(&logins);
+    let server = spawn_mock_server(move |method, path| match (method, path) {
+        ("POST", "/api2/json/access/ticket") => {
+            *counter.lock().expect("lock") += 1;
+            MockResponse::json(
+                200,
+                "OK",
+                &format!(
+                    r#"{{"data":{{"username":"root@pam","ticket":"{ticket}","CSRFPreventionToken":"csrf"}}}}"#
+                ),
+            )
+        }
+        ("GET", "/api2/json/version") => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":{"version":"8.2.2","release":"8.2","repoid":"abcdef"}}"#,
+        ),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let dir = std::env::temp_dir().join(format!("pve-sdk-cache-it-{}", std::process::id()));
+    let cache = dir.join("ticket.json");
+    let _ = std::fs::remove_dir_all(&dir);
+    for _ in 0..2 {
+        let client = ClientOption::new("127.0.0.1")
+            .port(server.port())
+            .https(false)
+            .auth(pve_sdk_rs::ClientAuth::Password {
+                username: "root".to_string(),
+                password: "secret".to_string(),
+                otp: None,
+                realm: Some("pam".to_string()),
+                tfa_challenge: None,
+            })
+            .ticket_cache_path(&cache)
+            .build()
+            .await
+            .expect("build client");
+        assert!(matches!(client.auth(), pve_sdk_rs::Auth::Ticket { .. }));
+    }
+
+    assert_eq!(*logins.lock().expect("lock"), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn raw_text_returns_body_without_envelope() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/nodes/pve1/qemu/100/config-backup" => {
+            MockResponse::text(200, "OK", "boot: order=scsi0\nmemory: 2048\n")
+        }
+        _ => MockResponse::text(404, "Not Found", "no such file"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let text = client
+        .raw()
+        .text(
+            reqwest::Method::GET,
+            "/nodes/pve1/qemu/100/config-backup",
+            None,
+        )
+        .await
+        .expect("text body");
+    assert_eq!(text, "boot: order=scsi0\nmemory: 2048\n");
+
+    let err = client
+        .raw_bytes(reqwest::Method::GET, "/nodes/pve1/missing", None)
+        .await
+        .expect_err("404");
+    assert!(matches!(err, PveError::NotFound { ref path, .. } if path == "/nodes/pve1/missing"));
+}
+
+#[tokio::test]
+async fn raw_download_streams_body_to_file_with_progress() {
+    let payload: String = (0..4096u32)
+        .map(|i| char::from(b'a' + (i % 26) as u8))
+        .collect();
+    let body = payload.clone();
+    let server = spawn_mock_server(move |_method, path| match path {
+        "/api2/json/nodes/pve1/qemu/100/config?current=1" => MockResponse::text(200, "OK", &body),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let dest = std::env::temp_dir().join(format!("pve-sdk-download-{}.bin", std::process::id()));
+    let client = build_client(server.port()).await;
+    let mut query = PveParams::new();
+    query.insert("current", "1");
+    let mut reported = 0;
+    let written = client
+        .raw()
+        .download(
+            "/nodes/pve1/qemu/100/config",
+            Some(&query),
+            &dest,
+            |bytes| reported = bytes,
+        )
+        .await
+        .expect("download");
+
+    assert_eq!(written, 4096);
+    assert_eq!(reported, 4096);
+    assert_eq!(std::fs::read_to_string(&dest).expect("file"), payload);
+    let _ = std::fs::remove_file(&dest);
+
+    let err = client
+        .raw()
+        .download("/nodes/pve1/missing", None, &dest, |_| {})
+        .await
+        .expect_err("404");
+    assert!(matches!(err, PveError::NotFound { .. }), "{err:?}");
+    assert!(!dest.exists());
+}
+
+#[tokio::test]
+async fn storage_upload_reports_progress_up_to_file_size() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let port = listener.local_addr().expect("addr").port();
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.expect("accept");
+        let mut buffer = Vec::new();
+        let mut chunk = [0_u8; 4096];
+        loop {
+            let read = socket.read(&mut chunk).await.expect("read");
+            buffer.extend_from_slice(&chunk[..read]);
+            let text = String::from_utf8_lossy(&buffer);
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let length = text[..header_end]
+                    .lines()
+                    .find_map(|l| {
+                        l.to_ascii_lowercase()
+                            .strip_prefix("content-length: ")
+                            .map(str::to_string)
+                    })
+                    .and_then(|v| v.trim().parse::<usize>().ok())
+                    .expect("content-length");
+                if buffer.len() >= header_end + 4 + length {
+                    break;
+                }
+            }
+        }
+        let body = r#"{"data":"UPID:pve1:00001234:00000001:65A4F0B1:imgcopy::root@pam:"}"#;
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        );
+        socket.write_all(head.as_bytes()).await.expect("write");
+        socket.write_all(body.as_bytes()).await.expect("write");
+    });
+
+    let file = std::env::temp_dir().join(format!("pve-sdk-upload-{}.iso", std::process::id()));
+    std::fs::write(&file, vec![7_u8; 20_000]).expect("write file");
+    let reports = Arc::new(Mutex::new(Vec::<(u64, u64)>::new()));
+    let sink = Arc::clone(&reports);
+
+    let client = build_client(port).await;
+    client
+        .storage()
+        .upload_file_with_progress(
+            "pve1",
+            "local",
+            "iso",
+            &file,
+            None,
+            None,
+            move |sent, total| {
+                sink.lock().expect("lock").push((sent, total));
+            },
+        )
+        .await
+        .expect("upload");
+    server.await.expect("server");
+    let _ = std::fs::remove_file(&file);
+
+    let reports = reports.lock().expect("lock");
+    assert!(!reports.is_empty());
+    assert!(reports.windows(2).all(|w| w[0].0 < w[1].0));
+    assert_eq!(reports.last(), Some(&(20_000, 20_000)));
+}
+
+#[tokio::test]
+async fn node_pci_devices_decode_list() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/nodes/pve1/hardware/pci" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[{"id":"0000:00:02.0","class":"0x030000","vendor":"0x8086","iommugroup":-1},{"id":"0000:03:00.0","class":"0x020000","vendor":"0x8086","iommugroup":9}]}"#,
+        ),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let devices = client.node().pci_devices("pve1").await.expect("pci");
+    assert_eq!(devices.len(), 2);
+    assert!(devices[0].is_gpu());
+    assert_eq!(devices[1].iommugroup, Some(9));
+}
+
+#[tokio::test]
+async fn node_network_apply_and_revert_use_collection_path() {
+    let server = spawn_mock_server(|method, path| match (method, path) {
+        ("PUT", "/api2/json/nodes/pve1/network") => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":"UPID:pve1:00001234:00005678:65000000:srvreload:networking:root@pam:"}"#,
+        ),
+        ("DELETE", "/api2/json/nodes/pve1/network") => {
+            MockResponse::json(200, "OK", r#"{"data":null}"#)
+        }
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let upid = client.node().apply_network("pve1").await.expect("apply");
+    assert!(upid.contains(":srvreload:networking:"));
+    client.node().revert_network("pve1").await.expect("revert");
+}
+
+#[tokio::test]
+async fn backup_job_included_guests_unwraps_tree() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/cluster/backup/nightly/included_volumes" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":{"children":[{"id":100,"name":"web","type":"qemu","children":[{"id":"scsi0","name":"local-lvm:vm-100-disk-0","included":1}]},{"id":201,"type":"lxc"}]}}"#,
+        ),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let guests = client
+        .backup()
+        .backup_job_included_guests("nightly")
+        .await
+        .expect("included");
+    assert_eq!(guests.iter().map(|g| g.id).collect::<Vec<_>>(), [100, 201]);
+    assert!(guests[0].volumes[0].is_included());
+    assert!(guests[1].volumes.is_empty());
+}
+
+#[tokio::test]
+async fn forbidden_response_maps_to_permission_denied_with_privilege() {
+    let server = spawn_mock_server(|_method, _path| {
+        MockResponse::json(
+            403,
+            "Permission check failed (/vms/100, VM.Audit)",
+            r#"{"data":null,"message":"Permission check failed (/vms/100, VM.Audit)\n"}"#,
+        )
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let err = client
+        .qemu()
+        .status("pve1", 100)
+        .await
+        .expect_err("forbidden");
+    match err {
+        PveError::PermissionDenied {
+            path, privilege, ..
+        } => {
+            assert_eq!(path, "/nodes/pve1/qemu/100/status/current");
+            assert_eq!(privilege.as_deref(), Some("VM.Audit"));
+        }
+        other => panic!("expected PermissionDenied, got: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn missing_resources_map_to_not_found() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/nodes/pve1/storage/gone/status" => MockResponse::text(404, "Not Found", ""),
+        "/api2/json/nodes/pve1/lxc/300/snapshot/nope/config" => MockResponse::json(
+            500,
+            "snapshot 'nope' does not exist",
+            r#"{"data":null,"message":"snapshot 'nope' does not exist\n"}"#,
+        ),
+        _ => MockResponse::text(500, "Internal Server Error", "boom"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let err = client
+        .raw_get("/nodes/pve1/storage/gone/status", None)
+        .await
+        .expect_err("404");
+    assert!(
+        matches!(err, PveError::NotFound { ref path, .. } if path == "/nodes/pve1/storage/gone/status")
+    );
+
+    let err = client
+        .lxc()
+        .snapshot_config("pve1", 300, "nope")
+        .await
+        .expect_err("missing snapshot");
+    assert!(matches!(err, PveError::NotFound { .. }));
+
+    let err = client
+        .raw_get("/nodes/pve1/other", None)
+        .await
+        .expect_err("500");
+    assert!(matches!(err, PveError::ApiStatus { status: 500, .. }));
+}
+
+#[cfg(feature = "cancellation")]
+#[tokio::test]
+async fn wait_cancellable_returns_cancelled_while_task_runs() {
+    let server = spawn_mock_server(|_method, _path| {
+        MockResponse::json(200, "OK", r#"{"data":{"status":"running"}}"#)
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let token = pve_sdk_rs::CancellationToken::new();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        canceller.cancel();
+    });
+
+    let options = WaitTaskOptions {
+        poll_interval: Duration::from_millis(10),
+        timeout: None,
+    };
+    let err = client
+        .task()
+        .wait_cancellable(
+            "pve1",
+            "UPID:pve1:00001234:00000001:65A4F0B1:qmstart:100:root@pam:",
+            &options,
+            &token,
+        )
+        .await
+        .expect_err("cancelled");
+    assert!(matches!(err, PveError::Cancelled));
+}
+
+#[tokio::test]
+async fn qemu_ensure_state_skips_noop_and_waits_for_shutdown() {
+    let requested = Arc::new(Mutex::new(Vec::<String>::new()));
+    let seen = Arc::clone(&requested);
+    let server = spawn_mock_server(move |method, path| {
+        seen.lock().expect("lock").push(format!("{method} {path}"));
+        match (method, path) {
+            ("GET", "/api2/json/nodes/pve1/qemu/100/status/current") => {
+                MockResponse::json(200, "OK", r#"{"data":{"vmid":100,"status":"running"}}"#)
+            }
+            ("POST", "/api2/json/nodes/pve1/qemu/100/status/shutdown") => MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":"UPID:pve1:00001234:00000001:65A4F0B1:qmshutdown:100:root@pam:"}"#,
+            ),
+            ("GET", path) if path.ends_with("/status") && path.contains("/tasks/") => {
+                MockResponse::json(
+                    200,
+                    "OK",
+                    r#"{"data":{"status":"stopped","exitstatus":"OK"}}"#,
+                )
+            }
+            _ => MockResponse::text(404, "Not Found", "unexpected path"),
+        }
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let options = QemuEnsureStateOptions::new(WaitTaskOptions {
+        poll_interval: Duration::from_millis(10),
+        timeout: Some(Duration::from_secs(5)),
+    });
+    let status = client
+        .qemu()
+        .ensure_state("pve1", 100, GuestState::Running, &options)
+        .await
+        .expect("noop");
+    assert!(status.upid.is_none());
+    assert_eq!(status.exitstatus.as_deref(), Some("OK"));
+    assert_eq!(requested.lock().expect("lock").len(), 1);
+
+    client
+        .qemu()
+        .ensure_state("pve1", 100, GuestState::ShutDown, &options)
+        .await
+        .expect("shutdown");
+    let requested = requested.lock().expect("lock");
+    assert_eq!(
+        requested[2],
+        "POST /api2/json/nodes/pve1/qemu/100/status/shutdown"
+    );
+    assert_eq!(requested.len(), 4);
+}
+
+#[tokio::test]
+async fn qemu_ensure_state_resumes_paused_vm() {
+    let requested = Arc::new(Mutex::new(Vec::<String>::new()));
+    let seen = Arc::clone(&requested);
+    let server = spawn_mock_server(move |method, path| {
+        seen.lock().expect("lock").push(format!("{method} {path}"));
+        match (method, path) {
+            ("GET", "/api2/json/nodes/pve1/qemu/100/status/current") => MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":{"vmid":100,"status":"running","qmpstatus":"paused"}}"#,
+            ),
+            ("POST", "/api2/json/nodes/pve1/qemu/100/status/resume") => MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":"UPID:pve1:00001234:00000001:65A4F0B1:qmresume:100:root@pam:"}"#,
+            ),
+            ("GET", path) if path.ends_with("/status") && path.contains("/tasks/") => {
+                MockResponse::json(
+                    200,
+                    "OK",
+                    r#"{"data":{"status":"stopped","exitstatus":"OK"}}"#,
+                )
+            }
+            _ => MockResponse::text(404, "Not Found", "unexpected path"),
+        }
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let options = QemuEnsureStateOptions::new(WaitTaskOptions {
+        poll_interval: Duration::from_millis(10),
+        timeout: Some(Duration::from_secs(5)),
+    });
+    let status = client
+        .qemu()
+        .ensure_state("pve1", 100, GuestState::Running, &options)
+        .await
+        .expect("resumed");
+    assert_eq!(status.exitstatus.as_deref(), Some("OK"));
+    assert_eq!(
+        requested.lock().expect("lock")[1],
+        "POST /api2/json/nodes/pve1/qemu/100/status/resume"
+    );
+}
+
+#[tokio::test]
+async fn qemu_wait_for_agent_retries_until_ping_succeeds() {
+    let pings = Arc::new(Mutex::new(0u32));
+    let counter = Arc::clone(&pings);
+    let server = spawn_mock_server(move |method, path| match (method, path) {
+        ("POST", "/api2/json/nodes/pve1/qemu/100/agent/ping") => {
+            let mut count = counter.lock().expect("lock");
+            *count += 1;
+            if *count == 1 {
+                MockResponse::text(
+                    500,
+                    "QEMU guest agent is not running",
+                    "QEMU guest agent is not running",
+                )
+            } else {
+                MockResponse::json(200, "OK", r#"{"data":{}}"#)
+            }
+        }
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    client
+        .qemu()
+        .wait_for_agent("pve1", 100, Duration::from_secs(10))
+        .await
+        .expect("agent up");
+    assert_eq!(*pings.lock().expect("lock"), 2);
+
+    let err = client
+        .qemu()
+        .wait_for_agent("pve1", 101, Duration::from_secs(10))
+        .await
+        .expect_err("unknown vm");
+    assert!(matches!(err, PveError::NotFound { .. }));
+}
+
+#[tokio::test]
+async fn qemu_wait_for_agent_fails_fast_without_agent_or_when_stopped() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/nodes/pve1/qemu/100/agent/ping" => MockResponse::json(
+            500,
+            "No QEMU guest agent configured",
+            r#"{"data":null,"message":"No QEMU guest agent configured\n"}"#,
+        ),
+        "/api2/json/nodes/pve1/qemu/101/agent/ping" => MockResponse::json(
+            500,
+            "VM 101 is not running",
+            r#"{"data":null,"message":"VM 101 is not running\n"}"#,
+        ),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let started = std::time::Instant::now();
+    for vmid in [100, 101] {
+        let err = client
+            .qemu()
+            .wait_for_agent("pve1", vmid, Duration::from_secs(30))
+            .await
+            .expect_err("agent unavailable");
+        assert!(
+            matches!(err, PveError::ApiStatus { status: 500, .. }),
+            "{err:?}"
+        );
+    }
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn qemu_ensure_state_waits_for_agent_after_start() {
+    let requested = Arc::new(Mutex::new(Vec::<String>::new()));
+    let seen = Arc::clone(&requested);
+    let server = spawn_mock_server(move |method, path| {
+        seen.lock().expect("lock").push(format!("{method} {path}"));
+        match (method, path) {
+            ("GET", "/api2/json/nodes/pve1/qemu/100/status/current") => {
+                MockResponse::json(200, "OK", r#"{"data":{"vmid":100,"status":"stopped"}}"#)
+            }
+            ("POST", "/api2/json/nodes/pve1/qemu/100/status/start") => MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":"UPID:pve1:00001234:00000001:65A4F0B1:qmstart:100:root@pam:"}"#,
+            ),
+            ("GET", path) if path.ends_with("/status") && path.contains("/tasks/") => {
+                MockResponse::json(
+                    200,
+                    "OK",
+                    r#"{"data":{"status":"stopped","exitstatus":"OK"}}"#,
+                )
+            }
+            ("POST", "/api2/json/nodes/pve1/qemu/100/agent/ping") => {
+                MockResponse::json(200, "OK", r#"{"data":{}}"#)
+            }
+            _ => MockResponse::text(404, "Not Found", "unexpected path"),
+        }
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let options = QemuEnsureStateOptions::new(WaitTaskOptions {
+        poll_interval: Duration::from_millis(10),
+        timeout: Some(Duration::from_secs(5)),
+    })
+    .wait_for_agent(true);
+    client
+        .qemu()
+        .ensure_state("pve1", 100, GuestState::Running, &options)
+        .await
+        .expect("running with agent");
+    let requested = requested.lock().expect("lock");
+    assert_eq!(
+        requested.last().map(String::as_str),
+        Some("POST /api2/json/nodes/pve1/qemu/100/agent/ping")
+    );
+}
+
+#[tokio::test]
+async fn qemu_migrate_and_track_reports_transfer_progress() {
+    let server = spawn_mock_server(|method, path| match (method, path) {
+        ("POST", "/api2/json/nodes/pve1/qemu/100/migrate") => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":"UPID:pve1:00001234:00000001:65A4F0B1:qmigrate:100:root@pam:"}"#,
+        ),
+        ("GET", path) if path.contains("/tasks/") && path.ends_with("/status") => {
+            MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":{"status":"stopped","exitstatus":"OK"}}"#,
+            )
+        }
+        ("GET", path) if path.contains("/log?start=0") => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[
+                {"n":1,"t":"starting migration of VM 100 to node 'pve2'"},
+                {"n":2,"t":"drive-scsi0: transferred 8.0 GiB of 32.0 GiB (25.00%) in 10s"},
+                {"n":3,"t":"drive-scsi0: transferred 32.0 GiB of 32.0 GiB (100.00%) in 40s"},
+                {"n":4,"t":"migration finished successfully"}
+            ]}"#,
+        ),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let mut seen = Vec::new();
+    client
+        .qemu()
+        .migrate_and_track(
+            "pve1",
+            100,
+            &QemuMigrateRequest::new("pve2"),
+            &WaitTaskOptions::default(),
+            |progress| seen.push(progress.percent),
+        )
+        .await
+        .expect("migrate");
+    assert_eq!(seen, [25.0, 100.0]);
+}
+
+#[tokio::test]
+async fn qemu_migrate_and_track_times_out_on_a_stuck_task() {
+    let server = spawn_mock_server(|method, path| match (method, path) {
+        ("POST", "/api2/json/nodes/pve1/qemu/100/migrate") => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":"UPID:pve1:00001234:00000001:65A4F0B1:qmigrate:100:root@pam:"}"#,
+        ),
+        ("GET", path) if path.contains("/tasks/") && path.ends_with("/status") => {
+            MockResponse::json(200, "OK", r#"{"data":{"status":"running"}}"#)
+        }
+        ("GET", path) if path.contains("/log?") => MockResponse::json(200, "OK", r#"{"data":[]}"#),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let err = client
+        .qemu()
+        .migrate_and_track(
+            "pve1",
+            100,
+            &QemuMigrateRequest::new("pve2"),
+            &WaitTaskOptions {
+                poll_interval: Duration::from_millis(10),
+                timeout: Some(Duration::from_millis(50)),
+            },
+            |_| {},
+        )
+        .await
+        .expect_err("stuck migration");
+    assert!(matches!(err, PveError::TaskTimeout { .. }), "{err:?}");
+}
+
+#[tokio::test]
+async fn qemu_start_upid_parses_task_id() {
+    let server = spawn_mock_server(|method, path| match (method, path) {
+        ("POST", "/api2/json/nodes/pve1/qemu/100/status/start") => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":"UPID:pve1:00001234:00000001:65A4F0B1:qmstart:100:root@pam:"}"#,
+        ),
+        ("GET", path) if path.contains("/tasks/") && path.ends_with("/status") => {
+            MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":{"status":"stopped","exitstatus":"OK"}}"#,
+            )
+        }
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let upid = client
+        .qemu()
+        .start_upid("pve1", 100, &QemuActionRequest::default())
+        .await
+        .expect("start");
+    assert_eq!(upid.node(), "pve1");
+    assert_eq!(upid.kind(), TaskType::QmStart);
+    assert_eq!(upid.id(), Some("100"));
+    let options = WaitTaskOptions {
+        poll_interval: Duration::from_millis(10),
+        timeout: Some(Duration::from_secs(5)),
+    };
+    client
+        .wait_for_upid(&upid, &options)
+        .await
+        .expect("task finished");
+}
+
+#[tokio::test]
+async fn cluster_tasks_fans_out_over_online_nodes() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/nodes" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[{"node":"pve1","status":"online"},{"node":"pve2","status":"offline"},{"node":"pve3","status":"online"}]}"#,
+        ),
+        "/api2/json/nodes/pve1/tasks" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[{"upid":"UPID:pve1:00000001:00000001:65A4F0B1:qmstart:100:root@pam:","type":"qmstart"}]}"#,
+        ),
+        "/api2/json/nodes/pve3/tasks" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[{"upid":"UPID:pve3:00000002:00000001:65A4F0B1:vzdump::root@pam:","type":"vzdump"},{"upid":"UPID:pve3:00000003:00000001:65A4F0B1:qmstop:101:root@pam:","type":"qmstop"}]}"#,
+        ),
+        _ => MockResponse::text(500, "Internal Server Error", "node unreachable"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let tasks = client
+        .cluster()
+        .tasks_with_concurrency(&Default::default(), 2)
+        .await
+        .expect("cluster tasks");
+    let nodes: Vec<&str> = tasks.iter().map(|(node, _)| node.as_str()).collect();
+    assert_eq!(nodes, ["pve1", "pve3", "pve3"]);
+}
+
+#[tokio::test]
+async fn qemu_set_pool_moves_guest_between_pools() {
+    let requested = Arc::new(Mutex::new(Vec::<String>::new()));
+    let seen = Arc::clone(&requested);
+    let server = spawn_mock_server(move |method, path| {
+        seen.lock()
+            .expect("lock")
+            .push(format!("{method} {path}"));
+        match (method, path) {
+            ("GET", "/api2/json/cluster/resources?type=vm") => MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":[{"id":"qemu/100","type":"qemu","node":"pve1","vmid":100,"pool":"staging"}]}"#,
+            ),
+            ("PUT", "/api2/json/pools/staging") | ("PUT", "/api2/json/pools/prod") => {
+                MockResponse::json(200, "OK", r#"{"data":null}"#)
+            }
+            _ => MockResponse::text(404, "Not Found", "unexpected path"),
+        }
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    client
+        .qemu()
+        .set_pool("pve1", 100, Some("prod"))
+        .await
+        .expect("move");
+    client
+        .qemu()
+        .set_pool("pve1", 100, Some("staging"))
+        .await
+        .expect("already there");
+    let err = client
+        .lxc()
+        .set_pool("pve1", 100, None)
+        .await
+        .expect_err("not a container");
+    assert!(matches!(err, PveError::NotFound { .. }));
+
+    let requested = requested.lock().expect("lock");
+    assert_eq!(
+        requested[..3],
+        [
+            "GET /api2/json/cluster/resources?type=vm",
+            "PUT /api2/json/pools/staging",
+            "PUT /api2/json/pools/prod",
+        ]
+    );
+    assert_eq!(requested.len(), 5);
+}
+
+#[tokio::test]
+async fn decode_failure_reports_path_and_body() {
+    let server = spawn_mock_server(|_method, _path| {
+        MockResponse::json(200, "OK", r#"{"data":{"version":8}}"#)
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let err = client.version().await.expect_err("bad payload");
+    match err {
+        PveError::Deserialize {
+            path, body_snippet, ..
+        } => {
+            assert_eq!(path, "/version");
+            assert_eq!(body_snippet, r#"{"data":{"version":8}}"#);
+        }
+        other => panic!("expected Deserialize, got: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn max_response_bytes_rejects_oversized_bodies() {
+    let server = spawn_mock_server(|_method, path| {
+        if path.ends_with("/version") {
+            MockResponse::json(200, "OK", r#"{"data":{"version":"8.2.1","release":"8.2"}}"#)
+        } else {
+            let nodes = format!(r#"{{"data":[{{"node":"{}"}}]}}"#, "x".repeat(256));
+            MockResponse::json(200, "OK", &nodes)
+        }
+    })
+    .await;
+
+    let client = ClientOption::new("127.0.0.1")
+        .port(server.port())
+        .https(false)
+        .auth_none()
+        .max_response_bytes(128)
+        .build()
+        .await
+        .expect("build client");
+    client.version().await.expect("small body fits");
+    let err = client.nodes().await.expect_err("body over limit");
+    assert!(matches!(err, PveError::ResponseTooLarge { limit: 128 }));
+}
+
+#[tokio::test]
+async fn qemu_needs_reboot_checks_pending_values() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/nodes/pve1/qemu/100/pending" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[{"key":"cores","value":2,"pending":4},{"key":"name","value":"web01"}]}"#,
+        ),
+        _ => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[{"key":"cores","value":2},{"key":"name","value":"web02"}]}"#,
+        ),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    assert!(
+        client
+            .qemu()
+            .needs_reboot("pve1", 100)
+            .await
+            .expect("pending")
+    );
+    assert!(
+        !client
+            .qemu_needs_reboot("pve1", 101)
+            .await
+            .expect("pending")
+    );
+}
+
+#[tokio::test]
+async fn qemu_set_config_verified_rereads_config_and_pending() {
+    let server = spawn_mock_server(|method, path| match (method, path) {
+        ("PUT", "/api2/json/nodes/pve1/qemu/100/config") => {
+            MockResponse::json(200, "OK", r#"{"data":null}"#)
+        }
+        ("GET", "/api2/json/nodes/pve1/qemu/100/config") => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":{"name":"web01","cores":4,"memory":2048}}"#,
+        ),
+        ("GET", "/api2/json/nodes/pve1/qemu/100/pending") => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[{"key":"cores","value":2,"pending":4},{"key":"memory","value":2048}]}"#,
+        ),
+        _ => MockResponse::json(404, "Not Found", r#"{"data":null}"#),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let config = client
+        .qemu()
+        .set_config_verified(
+            "pve1",
+            100,
+            &QemuSetConfigRequest {
+                memory: Some(2048),
+                ..QemuSetConfigRequest::default()
+            },
+        )
+        .await
+        .expect("set config");
+    assert_eq!(config.cores, Some(4));
+    assert_eq!(config.reboot_pending, ["cores"]);
+}
+
+#[tokio::test]
+async fn lxc_pending_and_feature_checks() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/nodes/pve1/lxc/200/pending" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[{"key":"memory","value":512,"pending":1024},{"key":"hostname","value":"ct200"}]}"#,
+        ),
+        "/api2/json/nodes/pve1/lxc/200/feature?feature=snapshot" => {
+            MockResponse::json(200, "OK", r#"{"data":{"hasFeature":1}}"#)
+        }
+        "/api2/json/nodes/pve1/lxc/200/feature?feature=clone&snapname=pre-upgrade" => {
+            MockResponse::json(200, "OK", r#"{"data":{"hasFeature":0}}"#)
+        }
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let pending = client.lxc().pending("pve1", 200).await.expect("pending");
+    assert_eq!(pending.len(), 2);
+    assert!(
+        client
+            .lxc()
+            .needs_reboot("pve1", 200)
+            .await
+            .expect("reboot")
+    );
+    assert!(
+        client
+            .lxc()
+            .feature("pve1", 200, GuestFeature::Snapshot, None)
+            .await
+            .expect("snapshot feature")
+    );
+    assert!(
+        !client
+            .lxc_feature("pve1", 200, GuestFeature::Clone, Some("pre-upgrade"))
+            .await
+            .expect("clone feature")
+    );
+}
+
+#[tokio::test]
+async fn guest_description_is_read_and_written() {
+    let requests = Arc::new(Mutex::new(Vec::<String>::new()));
+    let seen = Arc::clone(&requests);
+    let server = spawn_mock_server(move |method, path| {
+        seen.lock()
+            .expect("capture request")
+            .push(format!("{method} {path}"));
+        match (method, path) {
+            ("GET", "/api2/json/nodes/pve1/qemu/100/config") => MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":{"name":"web01","description":"owner: ops\nrole: web"}}"#,
+            ),
+            ("GET", "/api2/json/nodes/pve1/lxc/200/config") => {
+                MockResponse::json(200, "OK", r#"{"data":{"hostname":"ct200"}}"#)
+            }
+            _ => MockResponse::json(200, "OK", r#"{"data":null}"#),
+        }
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    assert_eq!(
+        client
+            .qemu()
+            .get_description("pve1", 100)
+            .await
+            .expect("qemu description")
+            .as_deref(),
+        Some("owner: ops\nrole: web")
+    );
+    assert_eq!(
+        client
+            .lxc()
+            .get_description("pve1", 200)
+            .await
+            .expect("lxc description"),
+        None
+    );
+    client
+        .lxc()
+        .set_description("pve1", 200, "owner: ops")
+        .await
+        .expect("set description");
+    assert_eq!(
+        requests
+            .lock()
+            .expect("requests")
+            .last()
+            .map(String::as_str),
+        Some("PUT /api2/json/nodes/pve1/lxc/200/config")
+    );
+}
+
+#[tokio::test]
+async fn guest_tags_are_merged_and_written_back() {
+    let requests = Arc::new(Mutex::new(Vec::<String>::new()));
+    let seen = Arc::clone(&requests);
+    let server = spawn_mock_server(move |method, path| {
+        seen.lock()
+            .expect("capture request")
+            .push(format!("{method} {path}"));
+        match (method, path) {
+            ("GET", "/api2/json/nodes/pve1/qemu/100/config") => MockResponse::json(
+                200,
+                "OK",
+                r#"{"data":{"name":"web01","tags":"prod;web","digest":"abc123"}}"#,
+            ),
+            _ => MockResponse::json(200, "OK", r#"{"data":null}"#),
+        }
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let tags = client
+        .qemu()
+        .add_tags("pve1", 100, &["web", "backup"])
+        .await
+        .expect("add tags");
+    assert_eq!(tags.to_string(), "prod;web;backup");
+
+    let tags = client
+        .qemu()
+        .remove_tags("pve1", 100, &["staging"])
+        .await
+        .expect("remove tags");
+    assert_eq!(tags.to_string(), "prod;web");
+
+    let err = client
+        .qemu()
+        .set_tags("pve1", 100, &["bad tag"])
+        .await
+        .expect_err("invalid tag");
+    assert!(matches!(err, PveError::InvalidArgument(_)));
+
+    let requests = requests.lock().expect("requests");
+    assert_eq!(
+        *requests,
+        [
+            "GET /api2/json/nodes/pve1/qemu/100/config",
+            "PUT /api2/json/nodes/pve1/qemu/100/config",
+            "GET /api2/json/nodes/pve1/qemu/100/config",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn cluster_resources_filter_by_pool_and_tag() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/json/cluster/resources" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[
+                {"id":"qemu/100","type":"qemu","vmid":100,"pool":"tenant-a","tags":"prod;web"},
+                {"id":"storage/pve1/local","type":"storage","pool":"tenant-a"},
+                {"id":"/pool/tenant-a","type":"pool","pool":"tenant-a"},
+                {"id":"lxc/200","type":"lxc","vmid":200,"pool":"tenant-b"}
+            ]}"#,
+        ),
+        "/api2/json/cluster/resources?type=vm" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"data":[
+                {"id":"qemu/100","type":"qemu","vmid":100,"tags":"prod;web"},
+                {"id":"lxc/200","type":"lxc","vmid":200,"tags":"dev"}
+            ]}"#,
+        ),
+        _ => MockResponse::text(404, "Not Found", "unexpected path"),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let in_pool = client
+        .cluster()
+        .resources_in_pool("tenant-a")
+        .await
+        .expect("pool resources");
+    let ids: Vec<_> = in_pool.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, ["qemu/100", "storage/pve1/local"]);
+
+    let tagged = client
+        .cluster()
+        .resources_with_tag("prod")
+        .await
+        .expect("tagged resources");
+    assert_eq!(tagged.len(), 1);
+    assert_eq!(tagged[0].vmid, Some(100));
+}
+
+#[tokio::test]
+async fn task_output_pages_through_log() {
+    let server = spawn_mock_server(|_method, path| {
+        let query = path.split_once('?').map(|(_, q)| q).unwrap_or_default();
+        let param = |key: &str| {
+            query
+                .split('&')
+                .find_map(|kv| kv.strip_prefix(key)?.strip_prefix('='))
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or_default()
+        };
+        let (start, limit) = (param("start"), param("limit"));
+        let lines: Vec<String> = (start..(start + limit).min(620))
+            .map(|n| format!(r#"{{"n":{},"t":"line {n}"}}"#, n + 1))
+            .collect();
+        MockResponse::json(200, "OK", &format!(r#"{{"data":[{}]}}"#, lines.join(",")))
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let output = client
+        .task()
+        .output(
+            "pve1",
+            "UPID:pve1:000A1B2C:01F4E3D2:65A4F0B1:vzdump::root@pam:",
+        )
+        .await
+        .expect("task output");
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 620);
+    assert_eq!(lines[0], "line 0");
+    assert_eq!(lines[619], "line 619");
+}
+
+#[tokio::test]
+async fn qemu_agent_fsfreeze_unwraps_agent_result() {
+    let server = spawn_mock_server(|method, path| match (method, path) {
+        ("POST", "/api2/json/nodes/pve1/qemu/100/agent/fsfreeze-freeze") => {
+            MockResponse::json(200, "OK", r#"{"data":{"result":2}}"#)
+        }
+        ("POST", "/api2/json/nodes/pve1/qemu/100/agent/fsfreeze-status") => {
+            MockResponse::json(200, "OK", r#"{"data":{"result":"frozen"}}"#)
+        }
+        _ => MockResponse::json(404, "Not Found", r#"{"data":null}"#),
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let qemu = client.qemu();
+    assert_eq!(
+        qemu.agent_fsfreeze_freeze("pve1", 100)
+            .await
+            .expect("freeze"),
+        2
+    );
+    assert_eq!(
+        qemu.agent_fsfreeze_status("pve1", 100)
+            .await
+            .expect("status"),
+        "frozen"
+    );
+}
+
+#[tokio::test]
+async fn non_json_success_reports_content_type() {
+    let server = spawn_mock_server(|_method, _path| {
+        MockResponse::text(200, "OK", "<html><body>Sign in to continue</body></html>")
+    })
+    .await;
+
+    let client = build_client(server.port()).await;
+    let err = client.version().await.expect_err("html page");
+    match err {
+        PveError::UnexpectedContentType {
+            content_type,
+            body_snippet,
+        } => {
+            assert!(content_type.starts_with("text/"));
+            assert!(body_snippet.contains("Sign in"));
+        }
+        other => panic!("expected UnexpectedContentType, got: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn extjs_base_path_reports_unsuccessful_replies_as_errors() {
+    let server = spawn_mock_server(|_method, path| match path {
+        "/api2/extjs/version" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"success":1,"data":{"version":"8.2.4","release":"8.2"}}"#,
+        ),
+        "/api2/extjs/nodes/pve1/storage/gone/status" => MockResponse::json(
+            200,
+            "OK",
+            r#"{"success":0,"status":500,"data":null,"message":"storage 'gone' does not exist\n"}"#,
+        ),
+        _ => MockResponse::json(
+            200,
+            "OK",
+            r#"{"success":0,"data":null,"message":"parameter verification failed"}"#,
+        ),
+    })
+    .await;
+
+    let client = ClientOption::new("127.0.0.1")
+        .port(server.port())
+        .https(false)
+        .api_base_path("/api2/extjs")
+        .auth_none()
+        .build()
+        .await
+        .expect("build client");
+
+    let version = client.version().await.expect("version");
+    assert_eq!(version.version, "8.2.4");
+
+    let err = client
+        .raw_get("/nodes/pve1/storage/gone/status", None)
+        .await
+        .expect_err("missing storage");
+    assert!(matches!(err, PveError::NotFound { .. }), "{err:?}");
+
+    let err = client
+        .raw_post("/nodes/pve1/qemu/100/config", None)
+        .await
+        .expect_err("failed write");
+    assert!(
+        matches!(err, PveError::ApiStatus { status: 500, .. }),
+        "{err:?}"
+    );
+}